@@ -0,0 +1,9 @@
+#![cfg_attr(
+    all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")),
+    feature(stdarch_x86_avx512),
+    feature(avx512_target_feature)
+)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod gemm;
+pub use half::bf16;
@@ -0,0 +1,253 @@
+//! Products of bit matrices, packed 64 elements per `u64`. `lhs` is an `m × k` matrix and `rhs` a
+//! `k × n` matrix, each stored in either [`BitLayout`].
+
+use crate::{
+    microkernel::{self, BitKernelFn, MR, NR},
+    pack_operands::{pack_bits, BitOperand},
+};
+use core::sync::atomic::{AtomicU64, Ordering};
+use gemm_common::{
+    cache::DivCeil,
+    driver::{self, Product},
+    gemm::AllocError,
+    Parallelism, Ptr,
+};
+
+pub use crate::pack_operands::BitLayout;
+
+/// dst\[i, j\] := number of depth indices where lhs\[i, depth\] == rhs\[depth, j\]
+///
+/// With the usual encoding of `-1` as `0` and `+1` as `1`, the dot product of the row and the
+/// column is `2 × dst[i, j] - k`. `k` must fit in an `i32`.
+///
+/// # Safety
+///
+/// `dst` must be valid for writes of `m × n` elements with the given strides. `lhs` and `rhs`
+/// must be valid for reads of their rows (or columns), which are `stride` words apart.
+pub unsafe fn gemm_xnor_popcount(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut i32,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const u64,
+    lhs_layout: BitLayout,
+    lhs_stride: usize,
+    rhs: *const u64,
+    rhs_layout: BitLayout,
+    rhs_stride: usize,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    assert!(k <= i32::MAX as usize);
+
+    let mut kernel: BitKernelFn<u32> = microkernel::xor_popcount::scalar;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if gemm_common::feature_detected!("popcnt") {
+        kernel = microkernel::xor_popcount::popcnt;
+    }
+
+    let dst = Ptr(dst);
+    let (lhs, rhs) = operands(
+        m, n, lhs, lhs_layout, lhs_stride, rhs, rhs_layout, rhs_stride,
+    );
+    driver::gemm_product_generic(
+        &Bits {
+            lhs,
+            rhs,
+            k,
+            kernel,
+            store: &|row, col, nrows, ncols, acc| {
+                // the padding bits are zero in both operands, so they always match
+                for j in 0..ncols {
+                    for i in 0..nrows {
+                        let mismatches = *acc.add(i + j * MR);
+                        *dst.wrapping_offset(
+                            (row + i) as isize * dst_rs + (col + j) as isize * dst_cs,
+                        )
+                        .0 = k as i32 - mismatches as i32;
+                    }
+                }
+            },
+        },
+        m,
+        n,
+        k.msrv_div_ceil(64),
+        parallelism,
+    )
+}
+
+/// dst\[i, j\] := OR over the depth of (lhs\[i, depth\] AND rhs\[depth, j\])
+///
+/// The bits of `dst` outside of the `m × n` matrix are left unchanged.
+///
+/// # Safety
+///
+/// `dst`, `lhs` and `rhs` must be valid for the given layouts, with rows (or columns) that are
+/// `stride` words apart. `dst` must not overlap `lhs` or `rhs`.
+pub unsafe fn gemm_or_and(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut u64,
+    dst_layout: BitLayout,
+    dst_stride: usize,
+    lhs: *const u64,
+    lhs_layout: BitLayout,
+    lhs_stride: usize,
+    rhs: *const u64,
+    rhs_layout: BitLayout,
+    rhs_stride: usize,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    let dst = Ptr(dst);
+    // replaces the bits of `mask` in a word of `dst` with `bits`. the tiles of other threads may
+    // share the word, but not these bits
+    let update = |word: usize, mask: u64, bits: u64| {
+        if mask != 0 {
+            let word = &*(dst.wrapping_add(word).0 as *const AtomicU64);
+            word.fetch_and(!mask | bits, Ordering::Relaxed);
+            word.fetch_or(bits, Ordering::Relaxed);
+        }
+    };
+
+    let (lhs, rhs) = operands(
+        m, n, lhs, lhs_layout, lhs_stride, rhs, rhs_layout, rhs_stride,
+    );
+    driver::gemm_product_generic(
+        &Bits {
+            lhs,
+            rhs,
+            k,
+            kernel: microkernel::or_and::scalar,
+            store: &|row, col, nrows, ncols, acc| {
+                // the elements are visited along the words of `dst`, so that each word is
+                // updated once per line of the tile
+                let (n_lines, line_len) = match dst_layout {
+                    BitLayout::RowMajor => (nrows, ncols),
+                    BitLayout::ColMajor => (ncols, nrows),
+                };
+                for line in 0..n_lines {
+                    let (mut word, mut mask, mut bits) = (0, 0u64, 0u64);
+                    for pos in 0..line_len {
+                        let (i, j) = match dst_layout {
+                            BitLayout::RowMajor => (line, pos),
+                            BitLayout::ColMajor => (pos, line),
+                        };
+                        let (row, col) = (row + i, col + j);
+                        let (w, bit) = match dst_layout {
+                            BitLayout::RowMajor => (row * dst_stride + col / 64, col % 64),
+                            BitLayout::ColMajor => (col * dst_stride + row / 64, row % 64),
+                        };
+                        if w != word {
+                            update(word, mask, bits);
+                            (word, mask, bits) = (w, 0, 0);
+                        }
+                        mask |= 1 << bit;
+                        if *acc.add(i + j * MR) != 0 {
+                            bits |= 1 << bit;
+                        }
+                    }
+                    update(word, mask, bits);
+                }
+            },
+        },
+        m,
+        n,
+        k.msrv_div_ceil(64),
+        parallelism,
+    )
+}
+
+/// Describes `lhs` by its rows and `rhs` by its columns.
+fn operands(
+    m: usize,
+    n: usize,
+    lhs: *const u64,
+    lhs_layout: BitLayout,
+    lhs_stride: usize,
+    rhs: *const u64,
+    rhs_layout: BitLayout,
+    rhs_stride: usize,
+) -> (BitOperand, BitOperand) {
+    (
+        BitOperand {
+            ptr: lhs,
+            stride: lhs_stride,
+            depth_contiguous: lhs_layout == BitLayout::RowMajor,
+            outer_len: m,
+        },
+        BitOperand {
+            ptr: rhs,
+            stride: rhs_stride,
+            depth_contiguous: rhs_layout == BitLayout::ColMajor,
+            outer_len: n,
+        },
+    )
+}
+
+/// Product of bit matrices computed by [`driver::gemm_product_generic`], whose depth is counted
+/// in words. `store(row, col, nrows, ncols, acc)` writes a tile of accumulators to `dst`, where
+/// `acc` is column-major with a column stride of `MR`.
+struct Bits<'a, Acc> {
+    lhs: BitOperand,
+    rhs: BitOperand,
+    /// Depth in bits.
+    k: usize,
+    kernel: BitKernelFn<Acc>,
+    store: &'a (dyn Fn(usize, usize, usize, usize, *const Acc) + Sync),
+}
+
+impl<Acc: Copy> Product for Bits<'_, Acc> {
+    type Lhs = u64;
+    type Rhs = u64;
+    type Acc = Acc;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut u64,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_bits::<MR>(packed, k * MR, self.lhs, row, nrows, depth, k, self.k);
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut u64,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_bits::<NR>(packed, k * NR, self.rhs, col, ncols, depth, k, self.k);
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut Acc,
+        lhs: *const u64,
+        rhs: *const u64,
+        _: usize,
+        _: usize,
+        k: usize,
+        first: bool,
+    ) {
+        (self.kernel)(k, acc, MR, lhs, rhs, first);
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const Acc, row: usize, col: usize, nrows: usize, ncols: usize) {
+        (self.store)(row, col, nrows, ncols, acc);
+    }
+}
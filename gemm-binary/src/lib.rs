@@ -0,0 +1,5 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod gemm;
+pub mod microkernel;
+pub mod pack_operands;
@@ -0,0 +1,129 @@
+//! Microkernels over packed panels of `MR` rows of `lhs` and `NR` columns of `rhs`, whose words
+//! run along the depth. Each one updates a full `MR × NR` block of accumulators, stored in
+//! column-major order with a column stride of `acc_cs`.
+
+pub const MR: usize = 2;
+pub const NR: usize = 4;
+
+pub type BitKernelFn<Acc> = unsafe fn(
+    k_words: usize,
+    acc: *mut Acc,
+    acc_cs: usize,
+    packed_lhs: *const u64,
+    packed_rhs: *const u64,
+    first: bool,
+);
+
+/// Folds `op` over the depth, starting from `zero` if `first` is set, and from the current value
+/// of the accumulators otherwise.
+#[inline(always)]
+unsafe fn kernel<Acc: Copy>(
+    k_words: usize,
+    acc: *mut Acc,
+    acc_cs: usize,
+    packed_lhs: *const u64,
+    packed_rhs: *const u64,
+    first: bool,
+    zero: Acc,
+    op: impl Fn(Acc, u64, u64) -> Acc,
+) {
+    let mut local = [[zero; MR]; NR];
+    if !first {
+        for (j, local) in local.iter_mut().enumerate() {
+            for (i, local) in local.iter_mut().enumerate() {
+                *local = *acc.add(i + j * acc_cs);
+            }
+        }
+    }
+
+    for depth in 0..k_words {
+        let lhs: [u64; MR] = core::array::from_fn(|i| *packed_lhs.add(depth * MR + i));
+        for (j, local) in local.iter_mut().enumerate() {
+            let rhs = *packed_rhs.add(depth * NR + j);
+            for (local, &lhs) in local.iter_mut().zip(&lhs) {
+                *local = op(*local, lhs, rhs);
+            }
+        }
+    }
+
+    for (j, local) in local.iter().enumerate() {
+        for (i, &local) in local.iter().enumerate() {
+            *acc.add(i + j * acc_cs) = local;
+        }
+    }
+}
+
+/// Counts the positions where the bits of `lhs` and `rhs` differ.
+pub mod xor_popcount {
+    use super::*;
+
+    #[inline(always)]
+    unsafe fn imp(
+        k_words: usize,
+        acc: *mut u32,
+        acc_cs: usize,
+        packed_lhs: *const u64,
+        packed_rhs: *const u64,
+        first: bool,
+    ) {
+        kernel(
+            k_words,
+            acc,
+            acc_cs,
+            packed_lhs,
+            packed_rhs,
+            first,
+            0u32,
+            |acc, lhs, rhs| acc + (lhs ^ rhs).count_ones(),
+        );
+    }
+
+    pub unsafe fn scalar(
+        k_words: usize,
+        acc: *mut u32,
+        acc_cs: usize,
+        packed_lhs: *const u64,
+        packed_rhs: *const u64,
+        first: bool,
+    ) {
+        imp(k_words, acc, acc_cs, packed_lhs, packed_rhs, first);
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    #[target_feature(enable = "popcnt")]
+    pub unsafe fn popcnt(
+        k_words: usize,
+        acc: *mut u32,
+        acc_cs: usize,
+        packed_lhs: *const u64,
+        packed_rhs: *const u64,
+        first: bool,
+    ) {
+        imp(k_words, acc, acc_cs, packed_lhs, packed_rhs, first);
+    }
+}
+
+/// Gathers the bits that are set in both `lhs` and `rhs`.
+pub mod or_and {
+    use super::*;
+
+    pub unsafe fn scalar(
+        k_words: usize,
+        acc: *mut u64,
+        acc_cs: usize,
+        packed_lhs: *const u64,
+        packed_rhs: *const u64,
+        first: bool,
+    ) {
+        kernel(
+            k_words,
+            acc,
+            acc_cs,
+            packed_lhs,
+            packed_rhs,
+            first,
+            0u64,
+            |acc, lhs, rhs| acc | (lhs & rhs),
+        );
+    }
+}
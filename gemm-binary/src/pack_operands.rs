@@ -0,0 +1,126 @@
+//! Packing of bit matrices into panels whose words run along the depth, which is the layout the
+//! microkernels expect regardless of the storage order of the operands.
+
+use gemm_common::cache::DivCeil;
+
+/// Storage order of a bit matrix, packed 64 elements per `u64`. Element `b` of a word is stored
+/// in bit `b`, and the last word of a row (or column) may contain arbitrary padding bits.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum BitLayout {
+    /// Each row is stored in consecutive words, and consecutive rows start `stride` words apart.
+    RowMajor,
+    /// Each column is stored in consecutive words, and consecutive columns start `stride` words
+    /// apart.
+    ColMajor,
+}
+
+/// Bit matrix seen as a set of lines along the outer dimension (rows of `lhs`, or columns of
+/// `rhs`), each made of `k` bits along the depth.
+#[derive(Copy, Clone, Debug)]
+pub struct BitOperand {
+    pub ptr: *const u64,
+    pub stride: usize,
+    /// Whether each line is stored in consecutive words. Otherwise, the words run along the
+    /// outer dimension, and the operand is transposed while it is packed.
+    pub depth_contiguous: bool,
+    /// Number of lines of the whole matrix.
+    pub outer_len: usize,
+}
+
+unsafe impl Send for BitOperand {}
+unsafe impl Sync for BitOperand {}
+
+/// Transposes a 64×64 bit matrix in place, where bit `c` of `a[r]` is the element `(r, c)`.
+#[inline(always)]
+pub fn transpose64(a: &mut [u64; 64]) {
+    let mut j = 32;
+    let mut mask = 0x0000_0000_FFFF_FFFFu64;
+    while j != 0 {
+        // swap the off-diagonal j×j blocks of each 2j×2j block along the diagonal
+        let mut r = 0;
+        while r < 64 {
+            let t = ((a[r] >> j) ^ a[r + j]) & mask;
+            a[r] ^= t << j;
+            a[r + j] ^= t;
+            r = (r + j + 1) & !j;
+        }
+        j >>= 1;
+        mask ^= mask << j;
+    }
+}
+
+/// Mask of the bits of the depth word `word` that are below `k`.
+#[inline(always)]
+fn depth_mask(word: usize, k: usize) -> u64 {
+    let end = (word + 1) * 64;
+    if end <= k {
+        !0
+    } else {
+        (1u64 << (k % 64)) - 1
+    }
+}
+
+/// Loads the bits `start..start + 64` of a line of `len` bits, without reading past the end of the
+/// line. The bits past `len` are unspecified.
+#[inline(always)]
+unsafe fn load_bits(line: *const u64, start: usize, len: usize) -> u64 {
+    let idx = start / 64;
+    let shift = start % 64;
+    let mut bits = *line.add(idx) >> shift;
+    if shift != 0 && (idx + 1) * 64 < len {
+        bits |= *line.add(idx + 1) << (64 - shift);
+    }
+    bits
+}
+
+/// Packs the lines `outer..outer + n_outer` of `src`, restricted to the depth words
+/// `word..word + n_words`, into panels of `P` lines that are `dst_stride` words apart. Word `w` of
+/// the line `p` of a panel is stored at `w * P + p`, and the padding lines and bits are zeroed.
+pub unsafe fn pack_bits<const P: usize>(
+    dst: *mut u64,
+    dst_stride: usize,
+    src: BitOperand,
+    outer: usize,
+    n_outer: usize,
+    word: usize,
+    n_words: usize,
+    k: usize,
+) {
+    let dst_index = |o: usize, w: usize| o / P * dst_stride + w * P + o % P;
+
+    if src.depth_contiguous {
+        for o in 0..n_outer {
+            let line = src.ptr.add((outer + o) * src.stride + word);
+            for w in 0..n_words {
+                *dst.add(dst_index(o, w)) = *line.add(w) & depth_mask(word + w, k);
+            }
+        }
+    } else {
+        let mut tile = [0u64; 64];
+        let mut o = 0;
+        while o < n_outer {
+            let tile_len = Ord::min(64, n_outer - o);
+            for w in 0..n_words {
+                for (d, bits) in tile.iter_mut().enumerate() {
+                    let depth = (word + w) * 64 + d;
+                    *bits = if depth < k {
+                        load_bits(src.ptr.add(depth * src.stride), outer + o, src.outer_len)
+                    } else {
+                        0
+                    };
+                }
+                transpose64(&mut tile);
+                for (p, &bits) in tile[..tile_len].iter().enumerate() {
+                    *dst.add(dst_index(o + p, w)) = bits;
+                }
+            }
+            o += tile_len;
+        }
+    }
+
+    for o in n_outer..n_outer.msrv_next_multiple_of(P) {
+        for w in 0..n_words {
+            *dst.add(dst_index(o, w)) = 0;
+        }
+    }
+}
@@ -5,3 +5,14 @@ pub mod f32 {
     type T = f32;
     gemm_common::gemm_cplx_def!(f32, c32, 2);
 }
+
+/// `c32` operands and destination, accumulated in `c32`.
+pub mod c32_c32 {
+    #[allow(unused_imports)]
+    use gemm_common::gemm::c32;
+
+    gemm_common::gemm_mixed_def!(
+        c32, c32, c32, c32, f32, CPLX_UKR, CPLX_MR_DIV_N, CPLX_NR;
+        scalar: 1, fma: 4, avx512f: 8,
+    );
+}
@@ -5,3 +5,36 @@ pub mod f64 {
     type T = f64;
     gemm_common::gemm_cplx_def!(f64, c64, 1);
 }
+
+/// `c64` operands and destination, accumulated in `c64`.
+pub mod c64_c64 {
+    #[allow(unused_imports)]
+    use gemm_common::gemm::c64;
+
+    gemm_common::gemm_mixed_def!(
+        c64, c64, c64, c64, f64, CPLX_UKR, CPLX_MR_DIV_N, CPLX_NR;
+        scalar: 1, fma: 2, avx512f: 4,
+    );
+}
+
+/// `c32` operands and destination, accumulated in `c64`.
+pub mod c32_c32 {
+    #[allow(unused_imports)]
+    use gemm_common::gemm::{c32, c64};
+
+    gemm_common::gemm_mixed_def!(
+        c32, c32, c32, c64, f64, CPLX_UKR, CPLX_MR_DIV_N, CPLX_NR;
+        scalar: 1, fma: 2, avx512f: 4,
+    );
+}
+
+/// `c32` operands with a `c64` destination.
+pub mod c32_c64 {
+    #[allow(unused_imports)]
+    use gemm_common::gemm::{c32, c64};
+
+    gemm_common::gemm_mixed_def!(
+        c32, c32, c64, c64, f64, CPLX_UKR, CPLX_MR_DIV_N, CPLX_NR;
+        scalar: 1, fma: 2, avx512f: 4,
+    );
+}
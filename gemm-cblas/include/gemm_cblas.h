@@ -0,0 +1,68 @@
+/* CBLAS and Fortran BLAS gemm symbols exported by gemm-cblas.
+ *
+ * Generated by tests/header.rs from the Rust signatures, do not edit. */
+
+#ifndef GEMM_CBLAS_H
+#define GEMM_CBLAS_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#ifndef CBLAS_H
+typedef enum CBLAS_LAYOUT {
+  CblasRowMajor = 101,
+  CblasColMajor = 102
+} CBLAS_LAYOUT;
+
+typedef enum CBLAS_TRANSPOSE {
+  CblasNoTrans = 111,
+  CblasTrans = 112,
+  CblasConjTrans = 113
+} CBLAS_TRANSPOSE;
+
+typedef CBLAS_LAYOUT CBLAS_ORDER;
+#endif
+
+void cblas_sgemm(CBLAS_LAYOUT layout, CBLAS_TRANSPOSE transa,
+                 CBLAS_TRANSPOSE transb, int m, int n, int k, float alpha,
+                 const float *a, int lda, const float *b, int ldb, float beta,
+                 float *c, int ldc);
+void cblas_dgemm(CBLAS_LAYOUT layout, CBLAS_TRANSPOSE transa,
+                 CBLAS_TRANSPOSE transb, int m, int n, int k, double alpha,
+                 const double *a, int lda, const double *b, int ldb,
+                 double beta, double *c, int ldc);
+/* complex scalars are passed by pointer and stored as interleaved
+ * (real, imaginary) pairs */
+void cblas_cgemm(CBLAS_LAYOUT layout, CBLAS_TRANSPOSE transa,
+                 CBLAS_TRANSPOSE transb, int m, int n, int k, const void *alpha,
+                 const void *a, int lda, const void *b, int ldb,
+                 const void *beta, void *c, int ldc);
+void cblas_zgemm(CBLAS_LAYOUT layout, CBLAS_TRANSPOSE transa,
+                 CBLAS_TRANSPOSE transb, int m, int n, int k, const void *alpha,
+                 const void *a, int lda, const void *b, int ldb,
+                 const void *beta, void *c, int ldc);
+
+/* fortran interface, column-major */
+void sgemm_(const char *transa, const char *transb, const int *m, const int *n,
+            const int *k, const float *alpha, const float *a, const int *lda,
+            const float *b, const int *ldb, const float *beta, float *c,
+            const int *ldc);
+void dgemm_(const char *transa, const char *transb, const int *m, const int *n,
+            const int *k, const double *alpha, const double *a, const int *lda,
+            const double *b, const int *ldb, const double *beta, double *c,
+            const int *ldc);
+void cgemm_(const char *transa, const char *transb, const int *m, const int *n,
+            const int *k, const void *alpha, const void *a, const int *lda,
+            const void *b, const int *ldb, const void *beta, void *c,
+            const int *ldc);
+void zgemm_(const char *transa, const char *transb, const int *m, const int *n,
+            const int *k, const void *alpha, const void *a, const int *lda,
+            const void *b, const int *ldb, const void *beta, void *c,
+            const int *ldc);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* GEMM_CBLAS_H */
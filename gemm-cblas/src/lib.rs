@@ -0,0 +1,281 @@
+//! CBLAS (`cblas_?gemm`) and Fortran BLAS (`?gemm_`) symbols implemented on top of
+//! [`gemm::gemm`].
+//!
+//! The declarations are in `include/gemm_cblas.h`, which is generated from the signatures below by
+//! `tests/header.rs`. After changing a signature, regenerate it with
+//! `GEMM_CBLAS_BLESS=1 cargo test -p gemm-cblas --test header`.
+//!
+//! The number of threads is read once from the `GEMM_NUM_THREADS` environment variable: `1` runs
+//! on the calling thread, other values use that many rayon threads, and `0` or an unset variable
+//! uses the rayon default.
+
+#![warn(rust_2018_idioms)]
+
+use core::ffi::{c_char, c_int, c_void};
+use gemm::blas::{gemm_strides, Layout, Transpose};
+use gemm::{c32, c64, GemmScalar, Parallelism};
+use num_traits::{One, Zero};
+use std::sync::OnceLock;
+
+pub const CBLAS_ROW_MAJOR: c_int = 101;
+pub const CBLAS_COL_MAJOR: c_int = 102;
+pub const CBLAS_NO_TRANS: c_int = 111;
+pub const CBLAS_TRANS: c_int = 112;
+pub const CBLAS_CONJ_TRANS: c_int = 113;
+
+/// Environment variable controlling the number of threads.
+pub const NUM_THREADS_VAR: &str = "GEMM_NUM_THREADS";
+
+fn parallelism() -> Parallelism {
+    static PARALLELISM: OnceLock<Parallelism> = OnceLock::new();
+    *PARALLELISM.get_or_init(|| {
+        let n_threads = std::env::var(NUM_THREADS_VAR)
+            .ok()
+            .and_then(|var| var.trim().parse::<usize>().ok())
+            .unwrap_or(0);
+        match n_threads {
+            1 => Parallelism::None,
+            #[cfg(feature = "rayon")]
+            n_threads => Parallelism::Rayon(n_threads),
+            #[cfg(not(feature = "rayon"))]
+            _ => Parallelism::None,
+        }
+    })
+}
+
+fn cblas_layout(layout: c_int) -> Option<Layout> {
+    match layout {
+        CBLAS_ROW_MAJOR => Some(Layout::RowMajor),
+        CBLAS_COL_MAJOR => Some(Layout::ColMajor),
+        _ => None,
+    }
+}
+
+fn cblas_transpose(trans: c_int) -> Option<Transpose> {
+    match trans {
+        CBLAS_NO_TRANS => Some(Transpose::No),
+        CBLAS_TRANS => Some(Transpose::Trans),
+        CBLAS_CONJ_TRANS => Some(Transpose::ConjTrans),
+        _ => None,
+    }
+}
+
+unsafe fn fortran_transpose(trans: *const c_char) -> Option<Transpose> {
+    match *trans as u8 {
+        b'N' | b'n' => Some(Transpose::No),
+        b'T' | b't' => Some(Transpose::Trans),
+        b'C' | b'c' => Some(Transpose::ConjTrans),
+        _ => None,
+    }
+}
+
+/// Reports an invalid argument the way the reference `xerbla` does. `pos` is the one-based
+/// position of the argument.
+fn xerbla(name: &str, pos: usize) {
+    eprintln!(" ** On entry to {name} parameter number {pos} had an illegal value");
+}
+
+/// C := alpha×op(A)×op(B) + beta×C
+///
+/// Returns the position of the first invalid argument, following the numbering of the Fortran
+/// interface.
+unsafe fn gemm_blas<T: GemmScalar + Zero + One>(
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
+    m: c_int,
+    n: c_int,
+    k: c_int,
+    alpha: T,
+    a: *const T,
+    lda: c_int,
+    b: *const T,
+    ldb: c_int,
+    beta: T,
+    c: *mut T,
+    ldc: c_int,
+) -> Result<(), usize> {
+    if m < 0 {
+        return Err(3);
+    }
+    if n < 0 {
+        return Err(4);
+    }
+    if k < 0 {
+        return Err(5);
+    }
+    // negative leading dimensions are rejected by `gemm_strides` as being too small
+    let ld = |ld: c_int| usize::try_from(ld).unwrap_or(0);
+    let strides = gemm_strides(
+        layout,
+        transa,
+        transb,
+        m as usize,
+        n as usize,
+        k as usize,
+        ld(lda),
+        ld(ldb),
+        ld(ldc),
+    )?;
+
+    if m == 0 || n == 0 || ((alpha.is_zero() || k == 0) && beta.is_one()) {
+        return Ok(());
+    }
+
+    let ((a_rs, a_cs), (b_rs, b_cs), (c_rs, c_cs)) = (strides.a, strides.b, strides.c);
+
+    // the inputs are not read when alpha is zero
+    let k = if alpha.is_zero() { 0 } else { k as usize };
+
+    gemm::gemm(
+        m as usize,
+        n as usize,
+        k,
+        c,
+        c_cs,
+        c_rs,
+        !beta.is_zero(),
+        a,
+        a_cs,
+        a_rs,
+        b,
+        b_cs,
+        b_rs,
+        beta,
+        alpha,
+        false,
+        transa == Transpose::ConjTrans,
+        transb == Transpose::ConjTrans,
+        parallelism(),
+    );
+    Ok(())
+}
+
+macro_rules! blas_def {
+    ($cblas: ident, $fortran: ident, $fortran_name: literal, $ty: ty, $alpha: ty, $ptr: ty, $read: expr) => {
+        /// CBLAS `?gemm`.
+        ///
+        /// # Safety
+        ///
+        /// The pointers must be valid for the given dimensions and leading dimensions.
+        #[no_mangle]
+        pub unsafe extern "C" fn $cblas(
+            layout: c_int,
+            transa: c_int,
+            transb: c_int,
+            m: c_int,
+            n: c_int,
+            k: c_int,
+            alpha: $alpha,
+            a: *const $ptr,
+            lda: c_int,
+            b: *const $ptr,
+            ldb: c_int,
+            beta: $alpha,
+            c: *mut $ptr,
+            ldc: c_int,
+        ) {
+            let name = stringify!($cblas);
+            let Some(layout) = cblas_layout(layout) else {
+                return xerbla(name, 1);
+            };
+            let Some(transa) = cblas_transpose(transa) else {
+                return xerbla(name, 2);
+            };
+            let Some(transb) = cblas_transpose(transb) else {
+                return xerbla(name, 3);
+            };
+
+            if let Err(pos) = gemm_blas::<$ty>(
+                layout,
+                transa,
+                transb,
+                m,
+                n,
+                k,
+                $read(alpha),
+                a as *const $ty,
+                lda,
+                b as *const $ty,
+                ldb,
+                $read(beta),
+                c as *mut $ty,
+                ldc,
+            ) {
+                // the layout is the first cblas argument
+                xerbla(name, pos + 1);
+            }
+        }
+
+        /// Fortran BLAS `?gemm`. Matrices are column-major.
+        ///
+        /// # Safety
+        ///
+        /// The pointers must be valid for the given dimensions and leading dimensions.
+        #[no_mangle]
+        pub unsafe extern "C" fn $fortran(
+            transa: *const c_char,
+            transb: *const c_char,
+            m: *const c_int,
+            n: *const c_int,
+            k: *const c_int,
+            alpha: *const $ty,
+            a: *const $ty,
+            lda: *const c_int,
+            b: *const $ty,
+            ldb: *const c_int,
+            beta: *const $ty,
+            c: *mut $ty,
+            ldc: *const c_int,
+        ) {
+            let name = $fortran_name;
+            let Some(transa) = fortran_transpose(transa) else {
+                return xerbla(name, 1);
+            };
+            let Some(transb) = fortran_transpose(transb) else {
+                return xerbla(name, 2);
+            };
+
+            if let Err(pos) = gemm_blas::<$ty>(
+                Layout::ColMajor,
+                transa,
+                transb,
+                *m,
+                *n,
+                *k,
+                *alpha,
+                a,
+                *lda,
+                b,
+                *ldb,
+                *beta,
+                c,
+                *ldc,
+            ) {
+                xerbla(name, pos);
+            }
+        }
+    };
+}
+
+// complex scalars are passed by pointer in cblas
+blas_def!(cblas_sgemm, sgemm_, "SGEMM", f32, f32, f32, |x| x);
+blas_def!(cblas_dgemm, dgemm_, "DGEMM", f64, f64, f64, |x| x);
+blas_def!(
+    cblas_cgemm,
+    cgemm_,
+    "CGEMM",
+    c32,
+    *const c_void,
+    c_void,
+    |x: *const c_void| { *(x as *const c32) }
+);
+blas_def!(
+    cblas_zgemm,
+    zgemm_,
+    "ZGEMM",
+    c64,
+    *const c_void,
+    c_void,
+    |x: *const c_void| { *(x as *const c64) }
+);
@@ -0,0 +1,183 @@
+/* Checks every transpose combination of the cblas and fortran gemm symbols
+ * against a naive implementation. */
+
+#include "gemm_cblas.h"
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+enum { M = 7, N = 5, K = 6, LD = 13, LEN = LD * 13 };
+
+static const CBLAS_TRANSPOSE TRANS[3] = {CblasNoTrans, CblasTrans,
+                                         CblasConjTrans};
+static const char FORTRAN_TRANS[3] = {'N', 't', 'C'};
+
+static int failures = 0;
+
+static double rand_value(void) { return (double)rand() / RAND_MAX - 0.5; }
+
+/* element (i, j) of op(x), as an interleaved (re, im) pair when cplx is set */
+static void load(const double *x, int row_major, int trans, int i, int j,
+                 int cplx, double *re, double *im) {
+  if (trans != 0) {
+    int tmp = i;
+    i = j;
+    j = tmp;
+  }
+  int idx = row_major ? i * LD + j : i + j * LD;
+  if (cplx) {
+    *re = x[2 * idx];
+    *im = trans == 2 ? -x[2 * idx + 1] : x[2 * idx + 1];
+  } else {
+    *re = x[idx];
+    *im = 0.0;
+  }
+}
+
+/* c := alpha op(a) op(b) + beta c, in double precision */
+static void reference(int row_major, int ta, int tb, int cplx,
+                      const double *alpha, const double *a, const double *b,
+                      const double *beta, double *c) {
+  for (int i = 0; i < M; ++i) {
+    for (int j = 0; j < N; ++j) {
+      double acc_re = 0.0, acc_im = 0.0;
+      for (int depth = 0; depth < K; ++depth) {
+        double a_re, a_im, b_re, b_im;
+        load(a, row_major, ta, i, depth, cplx, &a_re, &a_im);
+        load(b, row_major, tb, depth, j, cplx, &b_re, &b_im);
+        acc_re += a_re * b_re - a_im * b_im;
+        acc_im += a_re * b_im + a_im * b_re;
+      }
+      int idx = row_major ? i * LD + j : i + j * LD;
+      if (cplx) {
+        double c_re = c[2 * idx], c_im = c[2 * idx + 1];
+        c[2 * idx] = alpha[0] * acc_re - alpha[1] * acc_im +
+                     beta[0] * c_re - beta[1] * c_im;
+        c[2 * idx + 1] = alpha[0] * acc_im + alpha[1] * acc_re +
+                         beta[0] * c_im + beta[1] * c_re;
+      } else {
+        c[idx] = alpha[0] * acc_re + beta[0] * c[idx];
+      }
+    }
+  }
+}
+
+static void check(const char *name, int row_major, int ta, int tb,
+                  const double *expected, const double *actual, int len,
+                  double tol) {
+  for (int i = 0; i < len; ++i) {
+    if (fabs(expected[i] - actual[i]) > tol) {
+      fprintf(stderr, "%s: layout=%s transa=%d transb=%d: index %d: %g != %g\n",
+              name, row_major ? "row" : "col", ta, tb, i, actual[i],
+              expected[i]);
+      ++failures;
+      return;
+    }
+  }
+}
+
+int main(void) {
+  static double a[2 * LEN], b[2 * LEN], c[2 * LEN], expected[2 * LEN];
+  static float af[2 * LEN], bf[2 * LEN], cf[2 * LEN];
+  const double alpha[2] = {1.5, -0.5}, beta[2] = {0.25, 2.0};
+  const float alphaf[2] = {1.5f, -0.5f}, betaf[2] = {0.25f, 2.0f};
+  const int m = M, n = N, k = K, ld = LD;
+
+  for (int row_major = 0; row_major < 2; ++row_major) {
+    CBLAS_LAYOUT layout = row_major ? CblasRowMajor : CblasColMajor;
+    for (int ta = 0; ta < 3; ++ta) {
+      for (int tb = 0; tb < 3; ++tb) {
+        for (int cplx = 0; cplx < 2; ++cplx) {
+          int len = cplx ? 2 * LEN : LEN;
+          for (int i = 0; i < 2 * LEN; ++i) {
+            a[i] = rand_value();
+            b[i] = rand_value();
+            c[i] = rand_value();
+            expected[i] = c[i];
+            af[i] = (float)a[i];
+            bf[i] = (float)b[i];
+            cf[i] = (float)c[i];
+          }
+          reference(row_major, ta, tb, cplx, alpha, a, b, beta, expected);
+
+          if (cplx) {
+            cblas_zgemm(layout, TRANS[ta], TRANS[tb], M, N, K, alpha, a, LD,
+                        b, LD, beta, c, LD);
+            cblas_cgemm(layout, TRANS[ta], TRANS[tb], M, N, K, alphaf, af,
+                        LD, bf, LD, betaf, cf, LD);
+          } else {
+            cblas_dgemm(layout, TRANS[ta], TRANS[tb], M, N, K, alpha[0], a,
+                        LD, b, LD, beta[0], c, LD);
+            cblas_sgemm(layout, TRANS[ta], TRANS[tb], M, N, K, alphaf[0], af,
+                        LD, bf, LD, betaf[0], cf, LD);
+          }
+          check(cplx ? "cblas_zgemm" : "cblas_dgemm", row_major, ta, tb,
+                expected, c, len, 1e-12);
+          for (int i = 0; i < len; ++i) {
+            c[i] = cf[i];
+          }
+          check(cplx ? "cblas_cgemm" : "cblas_sgemm", row_major, ta, tb,
+                expected, c, len, 1e-4);
+
+          if (row_major) {
+            continue;
+          }
+
+          /* the fortran interface is column-major only */
+          for (int i = 0; i < 2 * LEN; ++i) {
+            c[i] = rand_value();
+            expected[i] = c[i];
+            cf[i] = (float)c[i];
+          }
+          reference(0, ta, tb, cplx, alpha, a, b, beta, expected);
+          if (cplx) {
+            zgemm_(&FORTRAN_TRANS[ta], &FORTRAN_TRANS[tb], &m, &n, &k, alpha,
+                   a, &ld, b, &ld, beta, c, &ld);
+            cgemm_(&FORTRAN_TRANS[ta], &FORTRAN_TRANS[tb], &m, &n, &k, alphaf,
+                   af, &ld, bf, &ld, betaf, cf, &ld);
+          } else {
+            dgemm_(&FORTRAN_TRANS[ta], &FORTRAN_TRANS[tb], &m, &n, &k, alpha,
+                   a, &ld, b, &ld, beta, c, &ld);
+            sgemm_(&FORTRAN_TRANS[ta], &FORTRAN_TRANS[tb], &m, &n, &k, alphaf,
+                   af, &ld, bf, &ld, betaf, cf, &ld);
+          }
+          check(cplx ? "zgemm_" : "dgemm_", row_major, ta, tb, expected, c,
+                len, 1e-12);
+          for (int i = 0; i < len; ++i) {
+            c[i] = cf[i];
+          }
+          check(cplx ? "cgemm_" : "sgemm_", row_major, ta, tb, expected, c,
+                len, 1e-4);
+        }
+      }
+    }
+  }
+
+  /* beta == 0 must not read c */
+  for (int i = 0; i < 2 * LEN; ++i) {
+    c[i] = NAN;
+    expected[i] = 0.0;
+  }
+  const double zero[2] = {0.0, 0.0};
+  reference(0, 0, 0, 0, alpha, a, b, zero, expected);
+  cblas_dgemm(CblasColMajor, CblasNoTrans, CblasNoTrans, M, N, K, alpha[0], a,
+              LD, b, LD, 0.0, c, LD);
+  for (int j = 0; j < N; ++j) {
+    for (int i = 0; i < M; ++i) {
+      if (!(fabs(c[i + j * LD] - expected[i + j * LD]) <= 1e-12)) {
+        fprintf(stderr, "cblas_dgemm: beta == 0 read the destination\n");
+        ++failures;
+        i = M;
+        j = N;
+      }
+    }
+  }
+
+  if (failures != 0) {
+    fprintf(stderr, "%d failures\n", failures);
+    return EXIT_FAILURE;
+  }
+  printf("ok\n");
+  return EXIT_SUCCESS;
+}
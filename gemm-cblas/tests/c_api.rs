@@ -0,0 +1,50 @@
+//! Builds `tests/c/test_cblas.c` against the shared library and runs it.
+
+#![cfg(all(unix, not(miri)))]
+
+use std::path::PathBuf;
+use std::process::Command;
+
+#[test]
+fn test_c_program() {
+    let manifest_dir = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    // the cdylib is built next to the test binary, in target/<profile>/deps. it is only copied to
+    // target/<profile> when the library itself is a build target
+    let lib_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_path_buf();
+    let exe = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("test_cblas");
+
+    let cc = std::env::var("CC").unwrap_or_else(|_| "cc".into());
+    let status = Command::new(cc)
+        .arg("-std=c99")
+        .arg("-Wall")
+        .arg("-Werror")
+        .arg("-I")
+        .arg(manifest_dir.join("include"))
+        .arg(manifest_dir.join("tests/c/test_cblas.c"))
+        .arg("-o")
+        .arg(&exe)
+        .arg("-L")
+        .arg(&lib_dir)
+        .arg(format!("-Wl,-rpath,{}", lib_dir.display()))
+        .arg("-lgemm_cblas")
+        .arg("-lm")
+        .status()
+        .expect("failed to run the c compiler");
+    assert!(status.success());
+
+    for n_threads in ["1", "3"] {
+        let output = Command::new(&exe)
+            .env("GEMM_NUM_THREADS", n_threads)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr),
+        );
+    }
+}
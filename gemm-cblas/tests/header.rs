@@ -0,0 +1,180 @@
+//! Generates `include/gemm_cblas.h` from the signatures of the Rust functions, and checks that
+//! the checked-in header is up to date. Run with `GEMM_CBLAS_BLESS=1` to rewrite it.
+
+#![allow(non_camel_case_types)]
+
+use core::ffi::{c_char, c_int, c_void};
+
+// the cblas enums are passed as `int`
+type CBLAS_LAYOUT = c_int;
+type CBLAS_TRANSPOSE = c_int;
+
+macro_rules! c_type {
+    (CBLAS_LAYOUT) => {
+        "CBLAS_LAYOUT"
+    };
+    (CBLAS_TRANSPOSE) => {
+        "CBLAS_TRANSPOSE"
+    };
+    (c_int) => {
+        "int"
+    };
+    (c_char) => {
+        "char"
+    };
+    (f32) => {
+        "float"
+    };
+    (f64) => {
+        "double"
+    };
+    // complex values are declared as `void` in the header
+    (c_void) => {
+        "void"
+    };
+    (Complex32) => {
+        "void"
+    };
+    (Complex64) => {
+        "void"
+    };
+    (*const $($ty: tt)+) => {
+        concat!("const ", c_type!($($ty)+), " *")
+    };
+    (*mut $($ty: tt)+) => {
+        concat!(c_type!($($ty)+), " *")
+    };
+}
+
+// checks the type of each function at compile time, and returns its c declaration
+macro_rules! declarations {
+    ($(fn $name: ident($($arg: ident: [$($ty: tt)+]),* $(,)?);)*) => {
+        [$({
+            let _: unsafe extern "C" fn($($($ty)+),*) = gemm_cblas::$name;
+            let args: &[(&str, &str)] = &[$((c_type!($($ty)+), stringify!($arg))),*];
+            declaration(stringify!($name), args)
+        }),*]
+    };
+}
+
+const WIDTH: usize = 80;
+
+// the arguments are wrapped to `WIDTH` columns and aligned after the opening parenthesis
+fn declaration(name: &str, args: &[(&str, &str)]) -> String {
+    let open = format!("void {name}(");
+    let indent = " ".repeat(open.len());
+    let mut out = String::new();
+    let mut line = open;
+    for (i, (ty, arg)) in args.iter().enumerate() {
+        // `const float *a`
+        let sep = if ty.ends_with('*') { "" } else { " " };
+        let end = if i + 1 == args.len() { ");" } else { "," };
+        let arg = format!("{ty}{sep}{arg}{end}");
+        if line.len() + 1 + arg.len() > WIDTH && !line.ends_with('(') {
+            out.push_str(&line);
+            out.push('\n');
+            line = format!("{indent}{arg}");
+        } else {
+            if !line.ends_with('(') {
+                line.push(' ');
+            }
+            line.push_str(&arg);
+        }
+    }
+    out.push_str(&line);
+    out.push('\n');
+    out
+}
+
+fn enumeration(name: &str, values: &[(&str, c_int)]) -> String {
+    let values = values
+        .iter()
+        .map(|(name, value)| format!("  {name} = {value}"))
+        .collect::<Vec<_>>()
+        .join(",\n");
+    format!("typedef enum {name} {{\n{values}\n}} {name};\n")
+}
+
+fn header() -> String {
+    use num_complex::{Complex32, Complex64};
+
+    let cblas_real = declarations! {
+        fn cblas_sgemm(layout: [CBLAS_LAYOUT], transa: [CBLAS_TRANSPOSE], transb: [CBLAS_TRANSPOSE], m: [c_int], n: [c_int], k: [c_int], alpha: [f32], a: [*const f32], lda: [c_int], b: [*const f32], ldb: [c_int], beta: [f32], c: [*mut f32], ldc: [c_int]);
+        fn cblas_dgemm(layout: [CBLAS_LAYOUT], transa: [CBLAS_TRANSPOSE], transb: [CBLAS_TRANSPOSE], m: [c_int], n: [c_int], k: [c_int], alpha: [f64], a: [*const f64], lda: [c_int], b: [*const f64], ldb: [c_int], beta: [f64], c: [*mut f64], ldc: [c_int]);
+    };
+    let cblas_complex = declarations! {
+        fn cblas_cgemm(layout: [CBLAS_LAYOUT], transa: [CBLAS_TRANSPOSE], transb: [CBLAS_TRANSPOSE], m: [c_int], n: [c_int], k: [c_int], alpha: [*const c_void], a: [*const c_void], lda: [c_int], b: [*const c_void], ldb: [c_int], beta: [*const c_void], c: [*mut c_void], ldc: [c_int]);
+        fn cblas_zgemm(layout: [CBLAS_LAYOUT], transa: [CBLAS_TRANSPOSE], transb: [CBLAS_TRANSPOSE], m: [c_int], n: [c_int], k: [c_int], alpha: [*const c_void], a: [*const c_void], lda: [c_int], b: [*const c_void], ldb: [c_int], beta: [*const c_void], c: [*mut c_void], ldc: [c_int]);
+    };
+    let fortran = declarations! {
+        fn sgemm_(transa: [*const c_char], transb: [*const c_char], m: [*const c_int], n: [*const c_int], k: [*const c_int], alpha: [*const f32], a: [*const f32], lda: [*const c_int], b: [*const f32], ldb: [*const c_int], beta: [*const f32], c: [*mut f32], ldc: [*const c_int]);
+        fn dgemm_(transa: [*const c_char], transb: [*const c_char], m: [*const c_int], n: [*const c_int], k: [*const c_int], alpha: [*const f64], a: [*const f64], lda: [*const c_int], b: [*const f64], ldb: [*const c_int], beta: [*const f64], c: [*mut f64], ldc: [*const c_int]);
+        fn cgemm_(transa: [*const c_char], transb: [*const c_char], m: [*const c_int], n: [*const c_int], k: [*const c_int], alpha: [*const Complex32], a: [*const Complex32], lda: [*const c_int], b: [*const Complex32], ldb: [*const c_int], beta: [*const Complex32], c: [*mut Complex32], ldc: [*const c_int]);
+        fn zgemm_(transa: [*const c_char], transb: [*const c_char], m: [*const c_int], n: [*const c_int], k: [*const c_int], alpha: [*const Complex64], a: [*const Complex64], lda: [*const c_int], b: [*const Complex64], ldb: [*const c_int], beta: [*const Complex64], c: [*mut Complex64], ldc: [*const c_int]);
+    };
+
+    let layout = enumeration(
+        "CBLAS_LAYOUT",
+        &[
+            ("CblasRowMajor", gemm_cblas::CBLAS_ROW_MAJOR),
+            ("CblasColMajor", gemm_cblas::CBLAS_COL_MAJOR),
+        ],
+    );
+    let transpose = enumeration(
+        "CBLAS_TRANSPOSE",
+        &[
+            ("CblasNoTrans", gemm_cblas::CBLAS_NO_TRANS),
+            ("CblasTrans", gemm_cblas::CBLAS_TRANS),
+            ("CblasConjTrans", gemm_cblas::CBLAS_CONJ_TRANS),
+        ],
+    );
+
+    format!(
+        "\
+/* CBLAS and Fortran BLAS gemm symbols exported by gemm-cblas.
+ *
+ * Generated by tests/header.rs from the Rust signatures, do not edit. */
+
+#ifndef GEMM_CBLAS_H
+#define GEMM_CBLAS_H
+
+#ifdef __cplusplus
+extern \"C\" {{
+#endif
+
+#ifndef CBLAS_H
+{layout}
+{transpose}
+typedef CBLAS_LAYOUT CBLAS_ORDER;
+#endif
+
+{}/* complex scalars are passed by pointer and stored as interleaved
+ * (real, imaginary) pairs */
+{}
+/* fortran interface, column-major */
+{}
+#ifdef __cplusplus
+}}
+#endif
+
+#endif /* GEMM_CBLAS_H */
+",
+        cblas_real.concat(),
+        cblas_complex.concat(),
+        fortran.concat(),
+    )
+}
+
+#[test]
+fn test_header_is_up_to_date() {
+    let path = concat!(env!("CARGO_MANIFEST_DIR"), "/include/gemm_cblas.h");
+    let header = header();
+    if std::env::var_os("GEMM_CBLAS_BLESS").is_some() {
+        std::fs::write(path, header).unwrap();
+    } else {
+        assert!(
+            std::fs::read_to_string(path).unwrap() == header,
+            "include/gemm_cblas.h is out of date, run the test with GEMM_CBLAS_BLESS=1",
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Products of the absolute values of `f64` matrices, with the absolute values taken while the
+//! operands are packed, so that the `f64` microkernels can be reused without materializing
+//! `|lhs|` and `|rhs|`.
+
+use crate::{
+    cache::DivCeil,
+    driver::{self, Product},
+    gemm::AllocError,
+    microkernel::MicroKernelFn,
+    pack_operands::{pack_lhs_bytes, pack_rhs_bytes, Convert},
+    simd::Simd,
+    Parallelism, Ptr,
+};
+
+const SIZE: isize = core::mem::size_of::<f64>() as isize;
+
+#[derive(Copy, Clone)]
+struct Abs;
+
+impl Convert<f64, f64> for Abs {
+    #[inline(always)]
+    fn convert(self, src: f64) -> f64 {
+        f64::from_bits(src.to_bits() & !(1 << 63))
+    }
+}
+
+/// dst := |lhs|×|rhs|, computed by [`driver::gemm_product_generic`] with the `f64` microkernels.
+struct AbsProduct<'a, const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, S> {
+    simd: S,
+    dst: Ptr<f64>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<f64>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<f64>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    dispatcher: &'a [[MicroKernelFn<f64>; NR]; MR_DIV_N],
+}
+
+impl<const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, S: Simd> Product
+    for AbsProduct<'_, N, MR, NR, MR_DIV_N, S>
+{
+    type Lhs = f64;
+    type Rhs = f64;
+    type Acc = f64;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut f64,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs_bytes::<f64, f64, _, N, MR, _>(
+            self.simd,
+            Abs,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs * SIZE,
+            self.lhs_rs * SIZE,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut f64,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs_bytes::<f64, f64, _, 1, NR, _>(
+            self.simd,
+            Abs,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs * SIZE,
+            self.rhs_rs * SIZE,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut f64,
+        lhs: *const f64,
+        rhs: *const f64,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        self.dispatcher[nrows.msrv_div_ceil(N) - 1][ncols - 1](
+            nrows,
+            ncols,
+            k,
+            acc,
+            lhs,
+            rhs,
+            MR as isize,
+            1,
+            MR as isize,
+            NR as isize,
+            1,
+            1.0,
+            1.0,
+            if first { 0 } else { 1 },
+            false,
+            false,
+            false,
+            lhs.wrapping_add(self.lhs_panel_len(k)),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const f64, row: usize, col: usize, nrows: usize, ncols: usize) {
+        for j in 0..ncols {
+            for i in 0..nrows {
+                *self
+                    .dst
+                    .wrapping_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0 = *acc.add(i + j * MR);
+            }
+        }
+    }
+}
+
+/// dst := |lhs|×|rhs|
+///
+/// # Safety
+///
+/// The matrices must be valid for the given dimensions and strides, and `dst` must not alias the
+/// operands.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_basic_generic<
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    S: Simd,
+>(
+    simd: S,
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut f64,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const f64,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const f64,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    dispatcher: &[[MicroKernelFn<f64>; NR]; MR_DIV_N],
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    driver::gemm_product_generic(
+        &AbsProduct::<N, MR, NR, MR_DIV_N, S> {
+            simd,
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut f64),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut f64),
+            rhs_cs,
+            rhs_rs,
+            dispatcher,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
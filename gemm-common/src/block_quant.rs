@@ -0,0 +1,341 @@
+//! Block-quantized `rhs` operands, dequantized to `f32` panels while they are packed, so that the
+//! `f32` microkernels can be reused without materializing the dequantized matrix.
+//!
+//! Each column of `rhs` is stored as `k / QK` consecutive blocks of [`QK`] elements along the
+//! depth, with one `f16` scale per block.
+
+use crate::{
+    cache::DivCeil,
+    driver::{self, Product},
+    gemm::AllocError,
+    microkernel::MicroKernelFn,
+    pack_operands::pack_lhs,
+    simd::Simd,
+    Parallelism, Ptr,
+};
+use half::f16;
+
+/// Number of elements in a quantized block.
+pub const QK: usize = 32;
+
+/// Block of [`QK`] 4-bit values with a shared scale, with the same layout as the `Q4_0` format of
+/// ggml.
+///
+/// The low nibbles of `qs` hold the first half of the block and the high nibbles the second
+/// half. Each element stands for `scale × (q - 8)`.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct BlockQ4 {
+    pub scale: f16,
+    pub qs: [u8; QK / 2],
+}
+
+/// Block of [`QK`] 8-bit values with a shared scale, with the same layout as the `Q8_0` format of
+/// ggml.
+///
+/// Each element stands for `scale × q`.
+#[derive(Copy, Clone, Debug)]
+#[repr(C)]
+pub struct BlockQ8 {
+    pub scale: f16,
+    pub qs: [i8; QK],
+}
+
+/// Block formats that can be dequantized into `f32`.
+pub trait QuantBlock: Copy + Send + Sync + 'static {
+    fn dequantize(&self, dst: &mut [f32; QK]);
+}
+
+impl QuantBlock for BlockQ4 {
+    #[inline(always)]
+    fn dequantize(&self, dst: &mut [f32; QK]) {
+        let scale = self.scale.to_f32();
+        let (lo, hi) = dst.split_at_mut(QK / 2);
+        for ((lo, hi), &q) in lo.iter_mut().zip(hi).zip(&self.qs) {
+            *lo = scale * ((q & 0xF) as i32 - 8) as f32;
+            *hi = scale * ((q >> 4) as i32 - 8) as f32;
+        }
+    }
+}
+
+impl QuantBlock for BlockQ8 {
+    #[inline(always)]
+    fn dequantize(&self, dst: &mut [f32; QK]) {
+        let scale = self.scale.to_f32();
+        for (dst, &q) in dst.iter_mut().zip(&self.qs) {
+            *dst = scale * q as f32;
+        }
+    }
+}
+
+#[inline(always)]
+unsafe fn pack_blocks<B: QuantBlock, const NR: usize>(
+    n: usize,
+    k: usize,
+    dst: *mut f32,
+    src: *const B,
+    src_cs: isize,
+    dst_stride: usize,
+) {
+    let mut block = [0.0f32; QK];
+
+    let mut j = 0;
+    while j < n {
+        let src_width = NR.min(n - j);
+        let dst = dst.add(j / NR * dst_stride);
+
+        for col in 0..src_width {
+            let src = src.offset((j + col) as isize * src_cs);
+            for b in 0..k / QK {
+                (*src.add(b)).dequantize(&mut block);
+                for (depth, &value) in block.iter().enumerate() {
+                    *dst.add((b * QK + depth) * NR + col) = value;
+                }
+            }
+        }
+        for depth in 0..k {
+            for col in src_width..NR {
+                *dst.add(depth * NR + col) = 0.0;
+            }
+        }
+
+        j += NR;
+    }
+}
+
+/// Packs `n` columns of a block-quantized `rhs` into panels of `NR` columns, dequantizing them to
+/// `f32`. `k` must be a multiple of [`QK`], and `src_cs` is the distance between two columns in
+/// blocks.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `n` columns of `k / QK` blocks, and `dst` for writes of
+/// `n.div_ceil(NR)` panels of `dst_stride` elements, each holding at least `k × NR` elements.
+#[inline(never)]
+pub unsafe fn pack_rhs_blocks<B: QuantBlock, const NR: usize, S: Simd>(
+    _: S,
+    n: usize,
+    k: usize,
+    dst: Ptr<f32>,
+    src: Ptr<B>,
+    src_cs: isize,
+    dst_stride: usize,
+) {
+    let dst = dst.0;
+    let src = src.0 as *const B;
+    S::vectorize(
+        #[inline(always)]
+        || pack_blocks::<B, NR>(n, k, dst, src, src_cs, dst_stride),
+    );
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, computed by [`driver::gemm_product_generic`] with the `f32`
+/// microkernels, with `rhs` dequantized while it is packed.
+struct BlockQuant<'a, const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, B, S>
+{
+    simd: S,
+    dst: Ptr<f32>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<f32>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<B>,
+    rhs_cs: isize,
+    alpha: f32,
+    beta: f32,
+    alpha_status: u8,
+    dispatcher: &'a [[MicroKernelFn<f32>; NR]; MR_DIV_N],
+}
+
+impl<
+        const N: usize,
+        const MR: usize,
+        const NR: usize,
+        const MR_DIV_N: usize,
+        B: QuantBlock,
+        S: Simd,
+    > Product for BlockQuant<'_, N, MR, NR, MR_DIV_N, B, S>
+{
+    type Lhs = f32;
+    type Rhs = f32;
+    type Acc = f32;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+    // the depth chunks start at the beginning of a block
+    const KC_ALIGN: usize = QK;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut f32,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs::<f32, N, MR, _>(
+            self.simd,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut f32,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs_blocks::<B, NR, _>(
+            self.simd,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(col as isize * self.rhs_cs)
+                .wrapping_add(depth / QK),
+            self.rhs_cs,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut f32,
+        lhs: *const f32,
+        rhs: *const f32,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        self.dispatcher[nrows.msrv_div_ceil(N) - 1][ncols - 1](
+            nrows,
+            ncols,
+            k,
+            acc,
+            lhs,
+            rhs,
+            MR as isize,
+            1,
+            MR as isize,
+            NR as isize,
+            1,
+            1.0,
+            self.beta,
+            if first { 0 } else { 1 },
+            false,
+            false,
+            false,
+            lhs.wrapping_add(self.lhs_panel_len(k)),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const f32, row: usize, col: usize, nrows: usize, ncols: usize) {
+        for j in 0..ncols {
+            for i in 0..nrows {
+                let acc = *acc.add(i + j * MR);
+                let dst = self
+                    .dst
+                    .wrapping_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0;
+                *dst = match self.alpha_status {
+                    0 => acc,
+                    1 => *dst + acc,
+                    _ => self.alpha * *dst + acc,
+                };
+            }
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, where `rhs` is block-quantized and `k` is a multiple of
+/// [`QK`].
+///
+/// # Safety
+///
+/// `dst` and `lhs` must be valid for the given dimensions and strides, `rhs` must be valid for
+/// reads of `n` columns of `k / QK` blocks, `rhs_cs` blocks apart, and `dst` must not alias the
+/// operands.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_basic_generic<
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    B: QuantBlock,
+    S: Simd,
+>(
+    simd: S,
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut f32,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const f32,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const B,
+    rhs_cs: isize,
+    alpha: f32,
+    beta: f32,
+    dispatcher: &[[MicroKernelFn<f32>; NR]; MR_DIV_N],
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    assert!(k.msrv_is_multiple_of(QK));
+
+    let alpha = if read_dst { alpha } else { 0.0 };
+    let alpha_status = if alpha == 0.0 {
+        0
+    } else if alpha == 1.0 {
+        if k == 0 {
+            return Ok(());
+        }
+        1
+    } else {
+        2
+    };
+
+    driver::gemm_product_generic(
+        &BlockQuant::<N, MR, NR, MR_DIV_N, B, S> {
+            simd,
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut f32),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut B),
+            rhs_cs,
+            alpha,
+            // the product is zero, even if `beta` is not finite
+            beta: if k == 0 { 0.0 } else { beta },
+            alpha_status,
+            dispatcher,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
@@ -16,6 +16,7 @@ pub trait DivCeil: Sized {
     fn msrv_div_ceil(self, rhs: Self) -> Self;
     fn msrv_next_multiple_of(self, rhs: Self) -> Self;
     fn msrv_checked_next_multiple_of(self, rhs: Self) -> Option<Self>;
+    fn msrv_is_multiple_of(self, rhs: Self) -> bool;
 }
 
 impl DivCeil for usize {
@@ -47,6 +48,14 @@ impl DivCeil for usize {
             }
         }
     }
+
+    #[inline]
+    fn msrv_is_multiple_of(self, rhs: Self) -> bool {
+        match self.checked_rem(rhs) {
+            Some(r) => r == 0,
+            None => self == 0,
+        }
+    }
 }
 
 #[cfg(target_vendor = "apple")]
@@ -454,6 +463,15 @@ static CACHE_INFO_DEFAULT: [CacheInfo; 3] = [
     },
 ];
 
+// the blocking parameters are derived from the l2 size, so a missing value would make them
+// degenerate
+fn with_l2_fallback(mut info: [CacheInfo; 3]) -> [CacheInfo; 3] {
+    if info[1].cache_bytes == 0 {
+        info[1] = CACHE_INFO_DEFAULT[1];
+    }
+    info
+}
+
 pub struct CacheInfoDeref;
 #[cfg(target_vendor = "apple")]
 pub struct HasAmx;
@@ -467,14 +485,23 @@ impl core::ops::Deref for CacheInfoDeref {
         {
             static CACHE_INFO: once_cell::race::OnceBox<[CacheInfo; 3]> =
                 once_cell::race::OnceBox::new();
-            CACHE_INFO
-                .get_or_init(|| alloc::boxed::Box::new(cache_info().unwrap_or(CACHE_INFO_DEFAULT)))
+            CACHE_INFO.get_or_init(|| {
+                alloc::boxed::Box::new(
+                    cache_info()
+                        .map(with_l2_fallback)
+                        .unwrap_or(CACHE_INFO_DEFAULT),
+                )
+            })
         }
         #[cfg(feature = "std")]
         {
             static CACHE_INFO: once_cell::sync::OnceCell<[CacheInfo; 3]> =
                 once_cell::sync::OnceCell::new();
-            CACHE_INFO.get_or_init(|| cache_info().unwrap_or(CACHE_INFO_DEFAULT))
+            CACHE_INFO.get_or_init(|| {
+                cache_info()
+                    .map(with_l2_fallback)
+                    .unwrap_or(CACHE_INFO_DEFAULT)
+            })
         }
     }
 }
@@ -571,8 +598,9 @@ pub fn kernel_params(
     //  - B micropanel: nr×kc: assume 1 assoc degree
     //  - A macropanel: mc×kc
     // mc×kc×scalar_bytes
-    let auto_mc = if l2_cache_bytes == 0 {
-        panic!();
+    let auto_mc = if l2_cache_bytes / l2_assoc == 0 {
+        // unknown l2 size, which `CACHE_INFO` should have replaced with a default
+        mr
     } else {
         let rhs_micropanel_bytes = nr * auto_kc * sizeof;
         let rhs_l2_assoc = rhs_micropanel_bytes.msrv_div_ceil(l2_cache_bytes / l2_assoc);
@@ -582,7 +610,7 @@ pub fn kernel_params(
             (lhs_l2_assoc * l2_cache_bytes) / (l2_assoc * sizeof * auto_kc)
         };
 
-        let auto_mc = round_down(mc_from_lhs_l2_assoc(lhs_l2_assoc), mr);
+        let auto_mc = round_down(mc_from_lhs_l2_assoc(lhs_l2_assoc), mr).max(mr);
         let m_iter = m.msrv_div_ceil(auto_mc);
         m.msrv_div_ceil(m_iter * mr) * mr
     };
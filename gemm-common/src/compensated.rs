@@ -0,0 +1,313 @@
+//! Compensated products of `f32` and `f64` matrices.
+//!
+//! Each dot product is accumulated with error-free transformations: the rounding error of each
+//! product is recovered with a fused multiply-add, and the rounding error of each sum with
+//! TwoSum. The errors are summed into a separate compensation term, which is carried along with
+//! the sum across depth blocks, and only added to it when the result is stored. The result is as
+//! accurate as if it had been computed with twice the working precision, then rounded.
+
+use crate::{
+    driver::{self, Product},
+    gemm::AllocError,
+    pack_operands::{pack_lhs, pack_rhs},
+    simd::Scalar,
+    Parallelism, Ptr,
+};
+use core::ops::{Add, Mul, Neg, Sub};
+use num_traits::Zero;
+
+/// Register block of the microkernel.
+const MR: usize = 4;
+const NR: usize = 4;
+
+/// Floating point types that [`gemm_compensated`] can multiply, implemented for `f32` and `f64`.
+pub trait CompensatedScalar:
+    Copy
+    + Zero
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+    + Send
+    + Sync
+    + 'static
+{
+    /// Returns `a × b + c`, rounded once.
+    #[doc(hidden)]
+    fn fma(a: Self, b: Self, c: Self) -> Self;
+}
+
+impl CompensatedScalar for f32 {
+    #[inline(always)]
+    fn fma(a: Self, b: Self, c: Self) -> Self {
+        #[cfg(feature = "std")]
+        {
+            f32::mul_add(a, b, c)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            libm::fmaf(a, b, c)
+        }
+    }
+}
+
+impl CompensatedScalar for f64 {
+    #[inline(always)]
+    fn fma(a: Self, b: Self, c: Self) -> Self {
+        #[cfg(feature = "std")]
+        {
+            f64::mul_add(a, b, c)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            libm::fma(a, b, c)
+        }
+    }
+}
+
+/// Returns `(a + b, e)`, where `e` is the rounding error of the sum.
+#[inline(always)]
+fn two_sum<T: CompensatedScalar>(a: T, b: T) -> (T, T) {
+    let s = a + b;
+    let bb = s - a;
+    (s, (a - (s - bb)) + (b - bb))
+}
+
+/// Returns `(a × b, e)`, where `e` is the rounding error of the product.
+#[inline(always)]
+fn two_prod<T: CompensatedScalar>(a: T, b: T) -> (T, T) {
+    let p = a * b;
+    (p, T::fma(a, b, -p))
+}
+
+/// Accumulators are pairs of a sum and its compensation term.
+type CompensatedKernelFn<T> =
+    unsafe fn(k: usize, acc: *mut (T, T), packed_lhs: *const T, packed_rhs: *const T, first: bool);
+
+/// Adds the products of a depth block to a full `MR × NR` block of sums and compensation terms,
+/// stored in column-major order with a column stride of `MR`, starting from zero if `first` is
+/// set.
+#[inline(always)]
+unsafe fn kernel<T: CompensatedScalar>(
+    k: usize,
+    acc: *mut (T, T),
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    first: bool,
+) {
+    let mut local_sum = [[T::zero(); MR]; NR];
+    let mut local_err = [[T::zero(); MR]; NR];
+    if !first {
+        for j in 0..NR {
+            for i in 0..MR {
+                (local_sum[j][i], local_err[j][i]) = *acc.add(i + j * MR);
+            }
+        }
+    }
+
+    for depth in 0..k {
+        let lhs: [T; MR] = core::array::from_fn(|i| *packed_lhs.add(depth * MR + i));
+        for j in 0..NR {
+            let rhs = *packed_rhs.add(depth * NR + j);
+            for i in 0..MR {
+                let (p, p_err) = two_prod(lhs[i], rhs);
+                let (s, s_err) = two_sum(local_sum[j][i], p);
+                local_sum[j][i] = s;
+                local_err[j][i] = local_err[j][i] + (p_err + s_err);
+            }
+        }
+    }
+
+    for j in 0..NR {
+        for i in 0..MR {
+            *acc.add(i + j * MR) = (local_sum[j][i], local_err[j][i]);
+        }
+    }
+}
+
+/// [`kernel`], compiled with FMA enabled so that [`CompensatedScalar::fma`] is a single
+/// instruction rather than a call into the C library.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2,fma")]
+unsafe fn kernel_fma<T: CompensatedScalar>(
+    k: usize,
+    acc: *mut (T, T),
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    first: bool,
+) {
+    kernel::<T>(k, acc, packed_lhs, packed_rhs, first);
+}
+
+/// dst := alpha×dst + beta×lhs×rhs with compensated sums, computed by
+/// [`driver::gemm_product_generic`]. The compensation terms live in the accumulator tiles, so
+/// they are carried from one depth chunk to the next.
+struct Compensated<T> {
+    dst: Ptr<T>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<T>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<T>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    read_dst: bool,
+    kernel: CompensatedKernelFn<T>,
+}
+
+impl<T: CompensatedScalar> Product for Compensated<T> {
+    type Lhs = T;
+    type Rhs = T;
+    type Acc = (T, T);
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut T,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs::<T, 1, MR, _>(
+            Scalar,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut T,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs::<T, 1, NR, _>(
+            Scalar,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs,
+            self.rhs_rs,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut (T, T),
+        lhs: *const T,
+        rhs: *const T,
+        _: usize,
+        _: usize,
+        k: usize,
+        first: bool,
+    ) {
+        (self.kernel)(k, acc, lhs, rhs, first);
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const (T, T), row: usize, col: usize, nrows: usize, ncols: usize) {
+        let (alpha, beta) = (self.alpha, self.beta);
+        for j in 0..ncols {
+            for i in 0..nrows {
+                let (sum, err) = *acc.add(i + j * MR);
+                let dst = self
+                    .dst
+                    .wrapping_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0;
+
+                let (value, value_err) = two_prod(beta, sum);
+                let value_err = T::fma(beta, err, value_err);
+                *dst = if self.read_dst {
+                    let (scaled, scaled_err) = two_prod(alpha, *dst);
+                    let (total, total_err) = two_sum(scaled, value);
+                    total + (total_err + (scaled_err + value_err))
+                } else {
+                    value + value_err
+                };
+            }
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// The dot products are computed with compensated summation, and `alpha × dst` and
+/// `beta × (lhs × rhs)` are also computed without intermediate rounding, so that each element of
+/// the result is rounded once, up to a relative error proportional to the condition number of the
+/// sum times the square of the unit roundoff.
+///
+/// # Safety
+///
+/// The matrices must be valid for the given dimensions and strides, and `dst` must not overlap
+/// `lhs` or `rhs`.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_compensated<T: CompensatedScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    let mut kernel: CompensatedKernelFn<T> = kernel::<T>;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if crate::feature_detected!("fma") && crate::feature_detected!("avx2") {
+        kernel = kernel_fma::<T>;
+    }
+
+    driver::gemm_product_generic(
+        &Compensated {
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut T),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut T),
+            rhs_cs,
+            rhs_rs,
+            alpha,
+            beta,
+            read_dst,
+            kernel,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
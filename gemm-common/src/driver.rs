@@ -0,0 +1,327 @@
+//! Shared driver for the products that accumulate into scratch tiles of their own accumulator
+//! type, and write each element of the destination once, after the whole depth has been
+//! accumulated. Unlike [`crate::gemm::gemm_basic_generic`], which accumulates into `dst` itself,
+//! this is the entry point for the widening, quantized, exact and compensated products: each of
+//! them only describes its packing, tile kernel and store through [`Product`].
+//!
+//! The product is split into blocks of `nc` columns and `mb` rows. For each block, the depth is
+//! traversed in chunks of `kc`: the `kc × nc` block of `rhs` and the `mb × kc` block of `lhs` are
+//! packed once, then each `MR × NR` tile of the product is accumulated into its own accumulator
+//! tile, which is kept until the last depth chunk and stored right after it.
+
+use crate::{
+    cache::{kernel_params, DivCeil, KernelParams, CACHE_INFO},
+    gemm::{AllocError, CACHELINE_ALIGN},
+    Parallelism, Ptr,
+};
+use core::ops::Range;
+use dyn_stack::{DynStack, MemBuffer, StackReq};
+
+#[cfg(feature = "rayon")]
+use crate::gemm::{get_threading_threshold, par_for_each};
+
+/// A matrix product computed by [`gemm_product_generic`].
+///
+/// The methods are called concurrently from several threads, on disjoint packed panels,
+/// accumulator tiles and destination tiles. They are only called with indices inside the
+/// dimensions passed to [`gemm_product_generic`], with packed panels of `lhs_panel_len` and
+/// `rhs_panel_len` elements, and with accumulator tiles of `MR × NR` elements.
+pub trait Product: Sync {
+    /// Element of the packed `lhs`.
+    type Lhs: Copy;
+    /// Element of the packed `rhs`.
+    type Rhs: Copy;
+    /// Element of the accumulator tiles.
+    type Acc: Copy;
+
+    /// Number of rows of an `lhs` panel.
+    const MR: usize;
+    /// Number of columns of an `rhs` panel.
+    const NR: usize;
+    /// The depth chunks, except the last one, are a multiple of this.
+    const KC_ALIGN: usize = 1;
+
+    /// Number of elements of a packed `lhs` panel of depth `k`.
+    #[inline]
+    fn lhs_panel_len(&self, k: usize) -> usize {
+        k * Self::MR
+    }
+
+    /// Number of elements of a packed `rhs` panel of depth `k`.
+    #[inline]
+    fn rhs_panel_len(&self, k: usize) -> usize {
+        k * Self::NR
+    }
+
+    /// Packs the rows `row..row + nrows` and the depth `depth..depth + k` of `lhs` into
+    /// consecutive panels of `MR` rows and `lhs_panel_len(k)` elements, starting at `packed`.
+    ///
+    /// `first_pass` is `true` if these elements of `lhs` have not been packed before.
+    ///
+    /// # Safety
+    ///
+    /// The arguments must be in range, as described in [`Product`].
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut Self::Lhs,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        first_pass: bool,
+    );
+
+    /// Packs the columns `col..col + ncols` and the depth `depth..depth + k` of `rhs` into
+    /// consecutive panels of `NR` columns and `rhs_panel_len(k)` elements, starting at `packed`.
+    ///
+    /// `first_pass` is `true` if these elements of `rhs` have not been packed before.
+    ///
+    /// # Safety
+    ///
+    /// The arguments must be in range, as described in [`Product`].
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut Self::Rhs,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        first_pass: bool,
+    );
+
+    /// Accumulates the product of a packed `lhs` panel and a packed `rhs` panel of depth `k` into
+    /// the column-major `MR × NR` tile `acc`, of which only the first `nrows` rows and `ncols`
+    /// columns are used.
+    ///
+    /// If `first` is `true`, the tile is overwritten instead, including when `k` is zero.
+    ///
+    /// # Safety
+    ///
+    /// The arguments must be in range, as described in [`Product`].
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut Self::Acc,
+        lhs: *const Self::Lhs,
+        rhs: *const Self::Rhs,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    );
+
+    /// Stores the fully accumulated tile `acc` to the rows `row..row + nrows` and the columns
+    /// `col..col + ncols` of the destination.
+    ///
+    /// # Safety
+    ///
+    /// The arguments must be in range, as described in [`Product`].
+    unsafe fn store(
+        &self,
+        acc: *const Self::Acc,
+        row: usize,
+        col: usize,
+        nrows: usize,
+        ncols: usize,
+    );
+}
+
+/// The `tid`-th of `n_threads` contiguous ranges of nearly equal length that cover `0..n`.
+#[inline]
+pub fn thread_range(tid: usize, n_threads: usize, n: usize) -> Range<usize> {
+    n * tid / n_threads..n * (tid + 1) / n_threads
+}
+
+/// Calls `func(tid)` for each `tid` in `0..n_threads`, in parallel if `n_threads > 1`.
+#[inline]
+pub fn for_each_thread(n_threads: usize, func: impl Fn(usize) + Send + Sync) {
+    if n_threads <= 1 {
+        func(0);
+    } else {
+        #[cfg(feature = "rayon")]
+        par_for_each(n_threads, func);
+        #[cfg(not(feature = "rayon"))]
+        unreachable!();
+    }
+}
+
+/// Number of threads used for a product of the given dimensions.
+#[inline]
+#[cfg_attr(not(feature = "rayon"), allow(unused_variables))]
+pub fn n_threads(m: usize, n: usize, k: usize, parallelism: Parallelism) -> usize {
+    match parallelism {
+        Parallelism::None => 1,
+        #[cfg(feature = "rayon")]
+        Parallelism::Rayon(n_threads) => {
+            if (m * n).saturating_mul(k) < get_threading_threshold() {
+                1
+            } else if n_threads == 0 {
+                rayon::current_num_threads()
+            } else {
+                n_threads
+            }
+        }
+    }
+}
+
+/// Computes the `m × n` product described by `product`, with a depth of `k`, accumulating each
+/// tile in scratch memory and storing it once the whole depth has been accumulated.
+///
+/// # Safety
+///
+/// `product` must be able to read `m × k` elements of `lhs` and `k × n` elements of `rhs`, and
+/// write `m × n` elements of the destination.
+pub unsafe fn gemm_product_generic<P: Product>(
+    product: &P,
+    m: usize,
+    n: usize,
+    k: usize,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    if m == 0 || n == 0 {
+        return Ok(());
+    }
+
+    let mr = P::MR;
+    let nr = P::NR;
+    let sizeof = core::mem::size_of::<P::Acc>().max(1);
+    let l3_bytes = CACHE_INFO[2].cache_bytes.max(CACHE_INFO[1].cache_bytes);
+
+    let KernelParams { kc, mc, nc } = kernel_params(m, n, k, mr, nr, sizeof);
+    let kc = kc.max(1).msrv_next_multiple_of(P::KC_ALIGN);
+    let mc = mc.max(mr).msrv_next_multiple_of(mr);
+    let nc = if nc > 0 {
+        nc.msrv_next_multiple_of(nr)
+    } else {
+        128 * nr
+    };
+
+    // the packed lhs block and the accumulators of the row block should both fit in the last
+    // level cache. the rhs block is packed again for each row block
+    let mb = (l3_bytes / (kc * sizeof) / mc * mc).max(mc);
+    let mb = mb.min(m.msrv_next_multiple_of(mr));
+    let nc = nc.min((l3_bytes / (mb * sizeof) / nr * nr).max(nr));
+    let nc = nc.min(n.msrv_next_multiple_of(nr));
+
+    let n_threads = n_threads(m, n, k, parallelism);
+
+    let lhs_panel_len = product.lhs_panel_len(kc);
+    let rhs_panel_len = product.rhs_panel_len(kc);
+    let mb_panels = mb / mr;
+    let nc_panels = nc / nr;
+    let mc_panels = mc / mr;
+
+    let lhs_req = StackReq::new_aligned::<P::Lhs>(lhs_panel_len * mb_panels, CACHELINE_ALIGN);
+    let rhs_req = StackReq::new_aligned::<P::Rhs>(rhs_panel_len * nc_panels, CACHELINE_ALIGN);
+    let acc_req = StackReq::new_aligned::<P::Acc>(mb * nc, CACHELINE_ALIGN);
+    let mut mem = MemBuffer::try_new(lhs_req.and(rhs_req).and(acc_req))?;
+    let stack = DynStack::new(&mut mem);
+    let (packed_lhs, stack) =
+        stack.make_aligned_uninit::<P::Lhs>(lhs_panel_len * mb_panels, CACHELINE_ALIGN);
+    let (packed_rhs, stack) =
+        stack.make_aligned_uninit::<P::Rhs>(rhs_panel_len * nc_panels, CACHELINE_ALIGN);
+    let (acc, _) = stack.make_aligned_uninit::<P::Acc>(mb * nc, CACHELINE_ALIGN);
+
+    let packed_lhs = Ptr(packed_lhs.as_mut_ptr() as *mut P::Lhs);
+    let packed_rhs = Ptr(packed_rhs.as_mut_ptr() as *mut P::Rhs);
+    let acc = Ptr(acc.as_mut_ptr() as *mut P::Acc);
+
+    let mut col = 0;
+    while col < n {
+        let ncols = nc.min(n - col);
+        let n_col_panels = ncols.msrv_div_ceil(nr);
+
+        let mut row = 0;
+        while row < m {
+            let nrows = mb.min(m - row);
+            let n_row_panels = nrows.msrv_div_ceil(mr);
+
+            let mut depth = 0;
+            loop {
+                let k_chunk = kc.min(k - depth);
+                let first = depth == 0;
+                let last = depth + k_chunk == k;
+
+                let lhs_panel_len = product.lhs_panel_len(k_chunk);
+                let rhs_panel_len = product.rhs_panel_len(k_chunk);
+
+                let n_pack_jobs = n_col_panels + n_row_panels;
+                let pack = |tid: usize| {
+                    let jobs = thread_range(tid, n_threads.min(n_pack_jobs), n_pack_jobs);
+
+                    let panels = jobs.start.min(n_col_panels)..jobs.end.min(n_col_panels);
+                    if !panels.is_empty() {
+                        let start = panels.start * nr;
+                        let end = (panels.end * nr).min(ncols);
+                        product.pack_rhs(
+                            packed_rhs.wrapping_add(panels.start * rhs_panel_len).0,
+                            col + start,
+                            end - start,
+                            depth,
+                            k_chunk,
+                            row == 0,
+                        );
+                    }
+
+                    let panels = jobs.start.max(n_col_panels) - n_col_panels
+                        ..jobs.end.max(n_col_panels) - n_col_panels;
+                    if !panels.is_empty() {
+                        let start = panels.start * mr;
+                        let end = (panels.end * mr).min(nrows);
+                        product.pack_lhs(
+                            packed_lhs.wrapping_add(panels.start * lhs_panel_len).0,
+                            row + start,
+                            end - start,
+                            depth,
+                            k_chunk,
+                            col == 0,
+                        );
+                    }
+                };
+                for_each_thread(n_threads.min(n_pack_jobs), pack);
+
+                // the jobs go through the tiles of each `mc × ncols` sub-block column by column,
+                // so that the packed lhs of the sub-block stays in the l2 cache
+                let sub_block_jobs = mc_panels * n_col_panels;
+                let n_jobs = n_row_panels * n_col_panels;
+                let compute = |tid: usize| {
+                    for job in thread_range(tid, n_threads.min(n_jobs), n_jobs) {
+                        let sub_block = job / sub_block_jobs;
+                        let job = job % sub_block_jobs;
+                        let first_panel = sub_block * mc_panels;
+                        let sub_block_panels = mc_panels.min(n_row_panels - first_panel);
+                        let j = job / sub_block_panels;
+                        let i = first_panel + job % sub_block_panels;
+
+                        let tile_rows = mr.min(nrows - i * mr);
+                        let tile_cols = nr.min(ncols - j * nr);
+                        let acc = acc.wrapping_add((j * n_row_panels + i) * mr * nr).0;
+
+                        product.accumulate(
+                            acc,
+                            packed_lhs.wrapping_add(i * lhs_panel_len).0,
+                            packed_rhs.wrapping_add(j * rhs_panel_len).0,
+                            tile_rows,
+                            tile_cols,
+                            k_chunk,
+                            first,
+                        );
+                        if last {
+                            product.store(acc, row + i * mr, col + j * nr, tile_rows, tile_cols);
+                        }
+                    }
+                };
+                for_each_thread(n_threads.min(n_jobs), compute);
+
+                depth += k_chunk;
+                if depth == k {
+                    break;
+                }
+            }
+            row += nrows;
+        }
+        col += ncols;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,121 @@
+//! 8-bit floating point storage types, following the OCP FP8 formats.
+//!
+//! Both types are only meant for storage: they are widened to `f32` before any arithmetic, and
+//! narrowed back with round to nearest, ties to even. Narrowing saturates to the largest finite
+//! value of the format instead of overflowing to infinity.
+
+/// FP8 with 4 exponent bits and 3 mantissa bits. Has no infinities, its largest finite value is
+/// `448`.
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct f8e4m3(pub u8);
+
+/// FP8 with 5 exponent bits and 2 mantissa bits. Its largest finite value is `57344`.
+#[allow(non_camel_case_types)]
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct f8e5m2(pub u8);
+
+#[inline(always)]
+fn decode<const EXP_BITS: u32, const MAN_BITS: u32>(bits: u8) -> f32 {
+    let bias = (1i32 << (EXP_BITS - 1)) - 1;
+    let sign = ((bits >> 7) as u32) << 31;
+    let exp = ((bits >> MAN_BITS) as i32) & ((1 << EXP_BITS) - 1);
+    let man = (bits as u32) & ((1 << MAN_BITS) - 1);
+
+    let abs = if exp == 0 {
+        // subnormal, `man × 2^(1 - bias - MAN_BITS)`, computed exactly
+        man as f32 * f32::from_bits(((128 - bias - MAN_BITS as i32) as u32) << 23)
+    } else {
+        f32::from_bits((((exp - bias + 127) as u32) << 23) | (man << (23 - MAN_BITS)))
+    };
+    f32::from_bits(abs.to_bits() | sign)
+}
+
+/// Rounds `value` to the nearest code, with ties to even, saturating to `max_code`. `value` must
+/// not be NaN.
+#[inline(always)]
+fn encode<const EXP_BITS: u32, const MAN_BITS: u32>(value: f32, max_code: u8) -> u8 {
+    let bias = (1i32 << (EXP_BITS - 1)) - 1;
+    let bits = value.to_bits();
+    let sign = ((bits >> 31) as u8) << 7;
+    let abs_bits = bits & 0x7FFF_FFFF;
+    let exp = (abs_bits >> 23) as i32 - 127;
+
+    let code = if exp > bias + 1 {
+        // also covers infinities
+        max_code as u32
+    } else if exp < 1 - bias {
+        // subnormal range: scale so that the result is the mantissa, which is exact since the
+        // scale is a power of two
+        let scaled = f32::from_bits(abs_bits)
+            * f32::from_bits(((127 + bias - 1 + MAN_BITS as i32) as u32) << 23);
+        let floor = scaled as u32;
+        let frac = scaled - floor as f32;
+        if frac > 0.5 || (frac == 0.5 && floor & 1 == 1) {
+            floor + 1
+        } else {
+            floor
+        }
+    } else {
+        let shift = 23 - MAN_BITS;
+        let half = 1u32 << (shift - 1);
+        let rem = abs_bits & ((1 << shift) - 1);
+        let mut code = abs_bits >> shift;
+        if rem > half || (rem == half && code & 1 == 1) {
+            code += 1;
+        }
+        code - (((127 - bias) as u32) << MAN_BITS)
+    };
+
+    code.min(max_code as u32) as u8 | sign
+}
+
+impl f8e4m3 {
+    pub const MAX: Self = Self(0x7E);
+    pub const NAN: Self = Self(0x7F);
+
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        if self.0 & 0x7F == 0x7F {
+            f32::NAN
+        } else {
+            decode::<4, 3>(self.0)
+        }
+    }
+
+    #[inline]
+    pub fn from_f32(value: f32) -> Self {
+        if value.is_nan() {
+            Self::NAN
+        } else {
+            Self(encode::<4, 3>(value, Self::MAX.0))
+        }
+    }
+}
+
+impl f8e5m2 {
+    pub const MAX: Self = Self(0x7B);
+    pub const INFINITY: Self = Self(0x7C);
+    pub const NAN: Self = Self(0x7F);
+
+    #[inline]
+    pub fn to_f32(self) -> f32 {
+        match self.0 & 0x7F {
+            0x7C => f32::from_bits(f32::INFINITY.to_bits() | (((self.0 >> 7) as u32) << 31)),
+            0x7D..=0x7F => f32::NAN,
+            _ => decode::<5, 2>(self.0),
+        }
+    }
+
+    /// Converts `value`, saturating infinities and out of range values to `±MAX`.
+    #[inline]
+    pub fn from_f32(value: f32) -> Self {
+        if value.is_nan() {
+            Self::NAN
+        } else {
+            Self(encode::<5, 2>(value, Self::MAX.0))
+        }
+    }
+}
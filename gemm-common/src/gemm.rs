@@ -7,7 +7,10 @@ use crate::{
     Parallelism, Ptr,
 };
 use core::sync::atomic::{AtomicUsize, Ordering};
+pub use dyn_stack::mem::AllocError;
 use dyn_stack::{DynStack, StackReq};
+#[cfg(feature = "bf16")]
+use half::bf16;
 #[cfg(feature = "f16")]
 use half::f16;
 use num_traits::{One, Zero};
@@ -57,9 +60,25 @@ pub const CACHELINE_ALIGN: usize = {
 
 #[cfg(feature = "std")]
 thread_local! {
-    pub static L2_SLAB: core::cell::RefCell<dyn_stack::MemBuffer> = core::cell::RefCell::new(dyn_stack::MemBuffer::new(
-        StackReq::new_aligned::<u8>(CACHE_INFO[1].cache_bytes, CACHELINE_ALIGN)
-    ));
+    static L2_SLAB: core::cell::RefCell<Option<dyn_stack::MemBuffer>> =
+        const { core::cell::RefCell::new(None) };
+}
+
+/// Calls `f` with the scratch memory of the current thread, which holds the packed lhs blocks
+/// and is sized after the L2 cache. The memory is allocated on the first call of each thread.
+#[cfg(feature = "std")]
+pub fn with_l2_slab<R>(f: impl FnOnce(&mut dyn_stack::MemBuffer) -> R) -> Result<R, AllocError> {
+    L2_SLAB.with(|slab| {
+        let mut slab = slab.borrow_mut();
+        let slab = match &mut *slab {
+            Some(slab) => slab,
+            slab => slab.insert(dyn_stack::MemBuffer::try_new(StackReq::new_aligned::<u8>(
+                CACHE_INFO[1].cache_bytes,
+                CACHELINE_ALIGN,
+            ))?),
+        };
+        Ok(f(slab))
+    })
 }
 
 pub trait Conj: Copy {
@@ -74,6 +93,14 @@ impl Conj for f16 {
     }
 }
 
+#[cfg(feature = "bf16")]
+impl Conj for bf16 {
+    #[inline(always)]
+    fn conj(self) -> Self {
+        self
+    }
+}
+
 impl Conj for f32 {
     #[inline(always)]
     fn conj(self) -> Self {
@@ -87,6 +114,13 @@ impl Conj for f64 {
     }
 }
 
+impl Conj for i32 {
+    #[inline(always)]
+    fn conj(self) -> Self {
+        self
+    }
+}
+
 impl Conj for c32 {
     #[inline(always)]
     fn conj(self) -> Self {
@@ -215,9 +249,9 @@ pub unsafe fn gemm_basic_generic<
     horizontal_dispatcher: &[[HMicroKernelFn<T>; H_N]; H_M],
     _requires_row_major_rhs: bool,
     parallelism: Parallelism,
-) {
+) -> Result<(), AllocError> {
     if m == 0 || n == 0 {
-        return;
+        return Ok(());
     }
     if !read_dst {
         alpha.set_zero();
@@ -232,11 +266,11 @@ pub unsafe fn gemm_basic_generic<
                     *dst.offset(i as isize * dst_rs + j as isize * dst_cs) = T::zero();
                 }
             }
-            return;
+            return Ok(());
         }
 
         if alpha.is_one() && !conj_dst {
-            return;
+            return Ok(());
         }
 
         if conj_dst {
@@ -254,7 +288,7 @@ pub unsafe fn gemm_basic_generic<
                 }
             }
         }
-        return;
+        return Ok(());
     }
 
     if (H_M > 0 && H_N > 0) && (!conj_dst && lhs_cs == 1 && rhs_rs == 1 && (m * n) <= 16 * 16) {
@@ -307,7 +341,7 @@ pub unsafe fn gemm_basic_generic<
             depth += kb;
         }
 
-        return;
+        return Ok(());
     }
 
     if !conj_dst && !conj_lhs && !conj_rhs {
@@ -316,7 +350,7 @@ pub unsafe fn gemm_basic_generic<
                 simd, m, n, k, dst, dst_cs, dst_rs, lhs, lhs_cs, lhs_rs, rhs, rhs_cs, rhs_rs,
                 alpha, beta, mul_add,
             );
-            return;
+            return Ok(());
         }
 
         if n <= 1 && lhs_rs == 1 && dst_rs == 1 {
@@ -324,28 +358,28 @@ pub unsafe fn gemm_basic_generic<
                 simd, m, n, k, dst, dst_cs, dst_rs, lhs, lhs_cs, lhs_rs, rhs, rhs_cs, rhs_rs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
         if n <= 1 && lhs_cs == 1 && rhs_rs == 1 {
             gemv::mixed_gemv_rowmajor(
                 simd, m, n, k, dst, dst_cs, dst_rs, lhs, lhs_cs, lhs_rs, rhs, rhs_cs, rhs_rs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
         if m <= 1 && rhs_cs == 1 && dst_cs == 1 {
             gemv::mixed_gemv_colmajor(
                 simd, n, m, k, dst, dst_rs, dst_cs, rhs, rhs_rs, rhs_cs, lhs, lhs_rs, lhs_cs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
         if m <= 1 && rhs_rs == 1 && lhs_cs == 1 {
             gemv::mixed_gemv_rowmajor(
                 simd, n, m, k, dst, dst_rs, dst_cs, rhs, rhs_rs, rhs_cs, lhs, lhs_rs, lhs_cs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
     }
 
@@ -434,16 +468,16 @@ pub unsafe fn gemm_basic_generic<
             },
             simd_align,
         );
-        Some(dyn_stack::MemBuffer::new(rhs_req.and(lhs_req)))
+        Some(dyn_stack::MemBuffer::try_new(rhs_req.and(lhs_req))?)
     } else {
         None
     };
 
     #[cfg(not(feature = "std"))]
-    let mut l2_slab = dyn_stack::MemBuffer::new(StackReq::new_aligned::<T>(
+    let mut l2_slab = dyn_stack::MemBuffer::try_new(StackReq::new_aligned::<T>(
         packed_lhs_stride * (mc / MR),
         simd_align,
-    ));
+    ))?;
 
     let mut packed_storage = mem.as_mut().map(|mem| {
         let stack = DynStack::new(mem);
@@ -804,16 +838,20 @@ pub unsafe fn gemm_basic_generic<
                     }
                 }
             } else {
+                #[cfg(feature = "std")]
+                let alloc_failed = core::sync::atomic::AtomicBool::new(false);
                 #[cfg(feature = "std")]
                 let func = |tid: usize| {
-                    L2_SLAB.with(|mem| {
-                        let mut mem = mem.borrow_mut();
-                        let stack = DynStack::new(&mut mem);
+                    let packed = with_l2_slab(|mem| {
+                        let stack = DynStack::new(mem);
                         let (packed_lhs_storage, _) = stack
                             .make_aligned_uninit::<T>(packed_lhs_stride * (mc / MR), simd_align);
                         let packed_lhs = Ptr(packed_lhs_storage.as_mut_ptr() as *mut T);
                         func(tid, packed_lhs);
                     });
+                    if packed.is_err() {
+                        alloc_failed.store(true, Ordering::Relaxed);
+                    }
                 };
 
                 #[cfg(not(feature = "std"))]
@@ -836,6 +874,11 @@ pub unsafe fn gemm_basic_generic<
                         }
                     }
                 }
+
+                #[cfg(feature = "std")]
+                if alloc_failed.into_inner() {
+                    return Err(AllocError);
+                }
             }
 
             conj_dst = false;
@@ -845,6 +888,8 @@ pub unsafe fn gemm_basic_generic<
         }
         col_outer += n_chunk;
     }
+
+    Ok(())
 }
 
 #[macro_export]
@@ -877,7 +922,7 @@ macro_rules! __inject_mod {
                 conj_lhs: bool,
                 conj_rhs: bool,
                 parallelism: $crate::Parallelism,
-            ) {
+            ) -> Result<(), $crate::gemm::AllocError> {
                 $crate::gemm::gemm_basic_generic::<
                     _,
                     $ty,
@@ -912,7 +957,7 @@ macro_rules! __inject_mod {
                     &H_UKR,
                     $requires_packed_rhs,
                     parallelism,
-                );
+                )
             }
         }
     };
@@ -949,7 +994,7 @@ macro_rules! __inject_mod_cplx {
                     conj_lhs: bool,
                     conj_rhs: bool,
                     parallelism: $crate::Parallelism,
-                    ) {
+                    ) -> Result<(), $crate::gemm::AllocError> {
                     $crate::gemm::gemm_basic_generic::<_, _, N, { CPLX_MR_DIV_N * N }, CPLX_NR, CPLX_MR_DIV_N, H_CPLX_M, H_CPLX_N>(
                         <$crate::simd::$simd as MixedSimd<T, T, T, T>>::try_new().unwrap(),
                         m,
@@ -975,7 +1020,7 @@ macro_rules! __inject_mod_cplx {
                         &H_CPLX_UKR,
                         false,
                         parallelism,
-                        );
+                        )
                 }
             }
         }
@@ -1005,7 +1050,7 @@ macro_rules! gemm_def {
             bool,
             bool,
             $crate::Parallelism,
-        );
+        ) -> Result<(), $crate::gemm::AllocError>;
 
         #[inline]
         fn init_gemm_fn() -> GemmTy {
@@ -1115,7 +1160,7 @@ macro_rules! gemm_cplx_def {
             bool,
             bool,
             $crate::Parallelism,
-        );
+        ) -> Result<(), $crate::gemm::AllocError>;
 
         fn init_gemm_cplx_fn() -> GemmCplxTy {
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -26,7 +26,13 @@ pub fn set_wasm_simd128(enable: bool) {
 
 extern crate alloc;
 
+pub mod abs;
+#[cfg(feature = "f16")]
+pub mod block_quant;
 pub mod cache;
+pub mod compensated;
+pub mod driver;
+pub mod f8;
 
 pub mod gemm;
 pub mod gemv;
@@ -34,9 +40,13 @@ pub mod gevv;
 
 pub mod horizontal_microkernel;
 pub mod microkernel;
+pub mod mixed;
+pub mod modular;
 
 pub mod pack_operands;
+pub mod ring;
 pub mod simd;
+pub mod tropical;
 
 pub use pulp;
 
@@ -69,6 +79,10 @@ impl<T> Ptr<T> {
     pub fn wrapping_add(self, offset: usize) -> Self {
         Ptr::<T>(self.0.wrapping_add(offset))
     }
+    #[inline(always)]
+    pub fn wrapping_byte_offset(self, offset: isize) -> Self {
+        Ptr::<T>(self.0.wrapping_byte_offset(offset))
+    }
 }
 
 #[cfg(not(feature = "std"))]
@@ -397,9 +397,37 @@ macro_rules! microkernel_amx {
 #[macro_export]
 macro_rules! microkernel {
     ($([$target: tt])?, $unroll: tt, $name: ident, $mr_div_n: tt, $nr: tt $(, $nr_div_n: tt, $n: tt)?) => {
+        $crate::microkernel!(
+            zero: ::core::mem::zeroed();
+            dot: mul_add;
+            $([$target])?, $unroll, $name, $mr_div_n, $nr $(, $nr_div_n, $n)?
+        );
+    };
+    // `$zero` is the initial value of the accumulators, for semirings whose additive identity is
+    // not the all-zero bit pattern
+    (zero: $zero: expr; $([$target: tt])?, $unroll: tt, $name: ident, $mr_div_n: tt, $nr: tt $(, $nr_div_n: tt, $n: tt)?) => {
+        $crate::microkernel!(
+            zero: $zero;
+            dot: mul_add;
+            $([$target])?, $unroll, $name, $mr_div_n, $nr $(, $nr_div_n, $n)?
+        );
+    };
+    // `$dot(lhs, rhs, accum)` accumulates the product of the packed lhs and the broadcast rhs
+    // element of one depth step, for kernels whose packed elements hold several depth steps, like
+    // pairs of `i16`. `mul_add` is still used to scale the result by `beta`
+    (dot: $dot: ident; $([$target: tt])?, $unroll: tt, $name: ident, $mr_div_n: tt, $nr: tt $(, $nr_div_n: tt, $n: tt)?) => {
+        $crate::microkernel!(
+            zero: ::core::mem::zeroed();
+            dot: $dot;
+            $([$target])?, $unroll, $name, $mr_div_n, $nr $(, $nr_div_n, $n)?
+        );
+    };
+    // `generic: <T: Bound>;` makes the kernel generic over its scalar type, for user-defined types.
+    // `Pack` and the arithmetic functions of the calling module are then generic over `T` as well
+    ($(generic: <$g: ident: $bound: path>;)? zero: $zero: expr; dot: $dot: ident; $([$target: tt])?, $unroll: tt, $name: ident, $mr_div_n: tt, $nr: tt $(, $nr_div_n: tt, $n: tt)?) => {
         $(#[target_feature(enable = $target)])?
         // 0, 1, or 2 for generic alpha
-        pub unsafe fn $name(
+        pub unsafe fn $name$(<$g: $bound>)?(
             m: usize,
             n: usize,
             k: usize,
@@ -419,26 +447,26 @@ macro_rules! microkernel {
             _conj_rhs: bool,
             mut next_lhs: *const T,
         ) {
-            let mut accum_storage = [[splat(::core::mem::zeroed()); $mr_div_n]; $nr];
-            let accum = accum_storage.as_mut_ptr() as *mut Pack;
+            let mut accum_storage = [[splat($zero); $mr_div_n]; $nr];
+            let accum = accum_storage.as_mut_ptr() as *mut Pack$(<$g>)?;
 
-            let mut lhs = [::core::mem::MaybeUninit::<Pack>::uninit(); $mr_div_n];
-            let mut rhs = ::core::mem::MaybeUninit::<Pack>::uninit();
+            let mut lhs = [::core::mem::MaybeUninit::<Pack$(<$g>)?>::uninit(); $mr_div_n];
+            let mut rhs = ::core::mem::MaybeUninit::<Pack$(<$g>)?>::uninit();
 
             #[derive(Copy, Clone)]
-            struct KernelIter {
+            struct KernelIter$(<$g: $bound>)? {
                 packed_lhs: *const T,
                 packed_rhs: *const T,
                 next_lhs: *const T,
                 lhs_cs: isize,
                 rhs_rs: isize,
                 rhs_cs: isize,
-                accum: *mut Pack,
-                lhs: *mut Pack,
-                rhs: *mut Pack,
+                accum: *mut Pack$(<$g>)?,
+                lhs: *mut Pack$(<$g>)?,
+                rhs: *mut Pack$(<$g>)?,
             }
 
-            impl KernelIter {
+            impl$(<$g: $bound>)? KernelIter$(<$g>)? {
                 #[inline(always)]
                 unsafe fn execute(self, iter: usize) {
                     let packed_lhs = self.packed_lhs.wrapping_offset(iter as isize * self.lhs_cs);
@@ -446,7 +474,7 @@ macro_rules! microkernel {
                     let next_lhs = self.next_lhs.wrapping_offset(iter as isize * self.lhs_cs);
 
                     seq_macro::seq!(M_ITER in 0..$mr_div_n {{
-                        *self.lhs.add(M_ITER) = *(packed_lhs.add(M_ITER * N) as *const Pack);
+                        *self.lhs.add(M_ITER) = *(packed_lhs.add(M_ITER * N) as *const Pack$(<$g>)?);
                     }});
 
                     seq_macro::seq!(N_ITER in 0..$nr {{
@@ -454,7 +482,7 @@ macro_rules! microkernel {
                         let accum = self.accum.add(N_ITER * $mr_div_n);
                         seq_macro::seq!(M_ITER in 0..$mr_div_n {{
                             let accum = &mut *accum.add(M_ITER);
-                            *accum = mul_add(
+                            *accum = $dot(
                                 *self.lhs.add(M_ITER),
                                 *self.rhs,
                                 *accum,
@@ -475,7 +503,7 @@ macro_rules! microkernel {
                         load::<$mr_div_n>(self.lhs, packed_lhs);
 
                         seq_macro::seq!(N_ITER0 in 0..$nr_div_n {{
-                            *self.rhs = *(packed_rhs.wrapping_offset(N_ITER0 * $n) as *const Pack);
+                            *self.rhs = *(packed_rhs.wrapping_offset(N_ITER0 * $n) as *const _);
 
                             seq_macro::seq!(N_ITER1 in 0..$n {{
                                 const N_ITER: usize = N_ITER0 * $n + N_ITER1;
@@ -635,7 +663,7 @@ macro_rules! microkernel {
                 if alpha_status == 2 {
                     seq_macro::seq!(N_ITER in 0..$nr {{
                         seq_macro::seq!(M_ITER in 0..$mr_div_n {{
-                            let dst = dst.offset(M_ITER * N as isize + N_ITER * dst_cs) as *mut Pack;
+                            let dst = dst.offset(M_ITER * N as isize + N_ITER * dst_cs) as *mut Pack$(<$g>)?;
                             dst.write_unaligned(add(
                                     mul(alpha, *dst),
                                     mul(beta, *accum.offset(M_ITER + $mr_div_n * N_ITER)),
@@ -645,7 +673,7 @@ macro_rules! microkernel {
                 } else if alpha_status == 1 {
                     seq_macro::seq!(N_ITER in 0..$nr {{
                         seq_macro::seq!(M_ITER in 0..$mr_div_n {{
-                            let dst = dst.offset(M_ITER * N as isize + N_ITER * dst_cs) as *mut Pack;
+                            let dst = dst.offset(M_ITER * N as isize + N_ITER * dst_cs) as *mut Pack$(<$g>)?;
                             dst.write_unaligned(mul_add(
                                     beta,
                                     *accum.offset(M_ITER + $mr_div_n * N_ITER),
@@ -656,7 +684,7 @@ macro_rules! microkernel {
                 } else {
                     seq_macro::seq!(N_ITER in 0..$nr {{
                         seq_macro::seq!(M_ITER in 0..$mr_div_n {{
-                            let dst = dst.offset(M_ITER * N as isize + N_ITER * dst_cs) as *mut Pack;
+                            let dst = dst.offset(M_ITER * N as isize + N_ITER * dst_cs) as *mut Pack$(<$g>)?;
                             dst.write_unaligned(mul(beta, *accum.offset(M_ITER + $mr_div_n * N_ITER)));
                         }});
                     }});
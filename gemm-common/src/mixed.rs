@@ -0,0 +1,692 @@
+//! Driver for products whose operands are widened to the accumulator type while they are packed,
+//! so that the microkernels of the wider type can be reused as is.
+
+use crate::{
+    cache::DivCeil,
+    driver::{self, Product},
+    gemm::{AllocError, Conj},
+    microkernel::MicroKernelFn,
+    pack_operands::{pack_lhs_bytes, pack_rhs_bytes, Convert},
+    simd::{Boilerplate, MixedSimd},
+    Parallelism, Ptr,
+};
+use num_traits::{One, Zero};
+
+type Marker<Lhs, Rhs, Dst, Acc> = core::marker::PhantomData<fn() -> (Lhs, Rhs, Dst, Acc)>;
+
+/// Widens the elements of `rhs` if `RHS` is `true`, and of `lhs` otherwise, to the accumulator
+/// type while they are packed.
+struct Widen<const RHS: bool, S, Lhs, Rhs, Dst, Acc>(S, Marker<Lhs, Rhs, Dst, Acc>);
+
+impl<const RHS: bool, S: Copy, Lhs, Rhs, Dst, Acc> Clone for Widen<RHS, S, Lhs, Rhs, Dst, Acc> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<const RHS: bool, S: Copy, Lhs, Rhs, Dst, Acc> Copy for Widen<RHS, S, Lhs, Rhs, Dst, Acc> {}
+
+impl<Lhs: Copy, Rhs: Copy, Dst, Acc: Copy, S: MixedSimd<Lhs, Rhs, Dst, Acc>> Convert<Lhs, Acc>
+    for Widen<false, S, Lhs, Rhs, Dst, Acc>
+{
+    #[inline(always)]
+    fn convert(self, src: Lhs) -> Acc {
+        self.0.from_lhs(src)
+    }
+
+    #[inline(always)]
+    unsafe fn convert_n<const N: usize>(self, dst: *mut Acc, src: *const Lhs) {
+        if N.msrv_is_multiple_of(S::SIMD_WIDTH) {
+            for j in 0..N / S::SIMD_WIDTH {
+                let j = j * S::SIMD_WIDTH;
+                let src = (src.add(j) as *const S::LhsN).read_unaligned();
+                (dst.add(j) as *mut S::AccN).write_unaligned(self.0.simd_from_lhs(src));
+            }
+        } else {
+            self.convert_slice(dst, src, N);
+        }
+    }
+}
+
+impl<Lhs: Copy, Rhs: Copy, Dst, Acc: Copy, S: MixedSimd<Lhs, Rhs, Dst, Acc>> Convert<Rhs, Acc>
+    for Widen<true, S, Lhs, Rhs, Dst, Acc>
+{
+    #[inline(always)]
+    fn convert(self, src: Rhs) -> Acc {
+        self.0.from_rhs(src)
+    }
+
+    #[inline(always)]
+    unsafe fn convert_n<const N: usize>(self, dst: *mut Acc, src: *const Rhs) {
+        if N.msrv_is_multiple_of(S::SIMD_WIDTH) {
+            for j in 0..N / S::SIMD_WIDTH {
+                let j = j * S::SIMD_WIDTH;
+                let src = (src.add(j) as *const S::RhsN).read_unaligned();
+                (dst.add(j) as *mut S::AccN).write_unaligned(self.0.simd_from_rhs(src));
+            }
+        } else {
+            self.convert_slice(dst, src, N);
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, computed by [`driver::gemm_product_generic`], with the
+/// operands widened to `Acc` during packing and the product accumulated by the microkernels of
+/// `Acc`. The strides are in bytes, and the elements do not need to be aligned.
+struct Mixed<
+    'a,
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    Lhs,
+    Rhs,
+    Dst,
+    Acc,
+    S,
+> {
+    simd: S,
+    dst: Ptr<Dst>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<Lhs>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<Rhs>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: Acc,
+    beta: Acc,
+    alpha_status: u8,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    dispatcher: &'a [[MicroKernelFn<Acc>; NR]; MR_DIV_N],
+}
+
+impl<
+        const N: usize,
+        const MR: usize,
+        const NR: usize,
+        const MR_DIV_N: usize,
+        Lhs: Boilerplate,
+        Rhs: Boilerplate,
+        Dst: Boilerplate,
+        Acc: Boilerplate
+            + Conj
+            + Zero
+            + One
+            + core::ops::Mul<Output = Acc>
+            + core::ops::Add<Output = Acc>,
+        S: MixedSimd<Lhs, Rhs, Dst, Acc>,
+    > Product for Mixed<'_, N, MR, NR, MR_DIV_N, Lhs, Rhs, Dst, Acc, S>
+{
+    type Lhs = Acc;
+    type Rhs = Acc;
+    type Acc = Acc;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut Acc,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs_bytes::<Lhs, Acc, _, N, MR, _>(
+            self.simd,
+            Widen::<false, S, Lhs, Rhs, Dst, Acc>(self.simd, core::marker::PhantomData),
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_byte_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut Acc,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs_bytes::<Rhs, Acc, _, N, NR, _>(
+            self.simd,
+            Widen::<true, S, Lhs, Rhs, Dst, Acc>(self.simd, core::marker::PhantomData),
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_byte_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs,
+            self.rhs_rs,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut Acc,
+        lhs: *const Acc,
+        rhs: *const Acc,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        self.dispatcher[nrows.msrv_div_ceil(N) - 1][ncols - 1](
+            nrows,
+            ncols,
+            k,
+            acc,
+            lhs,
+            rhs,
+            MR as isize,
+            1,
+            MR as isize,
+            NR as isize,
+            1,
+            Acc::one(),
+            self.beta,
+            if first { 0 } else { 1 },
+            false,
+            self.conj_lhs,
+            self.conj_rhs,
+            lhs.wrapping_add(self.lhs_panel_len(k)),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const Acc, row: usize, col: usize, nrows: usize, ncols: usize) {
+        let simd = self.simd;
+        for j in 0..ncols {
+            for i in 0..nrows {
+                let acc = *acc.add(i + j * MR);
+                let dst = self
+                    .dst
+                    .wrapping_byte_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0;
+                let value = match self.alpha_status {
+                    0 => simd.into_dst(acc),
+                    1 => simd.into_dst(simd.from_dst(dst.read_unaligned()) + acc),
+                    _ => {
+                        let dst = simd.from_dst(dst.read_unaligned());
+                        let dst = if self.conj_dst { dst.conj() } else { dst };
+                        simd.into_dst(self.alpha * dst + acc)
+                    }
+                };
+                dst.write_unaligned(value);
+            }
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, with the operands widened to `Acc` during packing and the
+/// product accumulated by the microkernels of `Acc`.
+///
+/// The product is accumulated in `Acc` over the whole depth, then combined with `dst` and
+/// rounded to `Dst` once. `alpha` and `beta` are given in `Acc`.
+///
+/// # Safety
+///
+/// `dst`, `lhs` and `rhs` must be valid for the given dimensions and strides, and `dst` must not
+/// alias the operands.
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_basic_generic<
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    Lhs: Boilerplate,
+    Rhs: Boilerplate,
+    Dst: Boilerplate,
+    Acc: Boilerplate + Conj + Zero + One + core::ops::Mul<Output = Acc> + core::ops::Add<Output = Acc>,
+    S: MixedSimd<Lhs, Rhs, Dst, Acc>,
+>(
+    simd: S,
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut Dst,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const Lhs,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const Rhs,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: Acc,
+    beta: Acc,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    dispatcher: &[[MicroKernelFn<Acc>; NR]; MR_DIV_N],
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    // the strides of a valid matrix fit in an `isize` once converted to bytes, unless the
+    // corresponding dimension is at most one, in which case the stride is never used
+    let dst_size = core::mem::size_of::<Dst>() as isize;
+    let lhs_size = core::mem::size_of::<Lhs>() as isize;
+    let rhs_size = core::mem::size_of::<Rhs>() as isize;
+    gemm_basic_bytes_generic::<N, MR, NR, MR_DIV_N, Lhs, Rhs, Dst, Acc, S>(
+        simd,
+        m,
+        n,
+        k,
+        dst,
+        dst_cs.wrapping_mul(dst_size),
+        dst_rs.wrapping_mul(dst_size),
+        read_dst,
+        lhs,
+        lhs_cs.wrapping_mul(lhs_size),
+        lhs_rs.wrapping_mul(lhs_size),
+        rhs,
+        rhs_cs.wrapping_mul(rhs_size),
+        rhs_rs.wrapping_mul(rhs_size),
+        alpha,
+        beta,
+        conj_dst,
+        conj_lhs,
+        conj_rhs,
+        dispatcher,
+        parallelism,
+    )
+}
+
+/// Same as [`gemm_basic_generic`], with the strides in bytes. The elements of `dst`, `lhs` and
+/// `rhs` do not need to be aligned.
+///
+/// # Safety
+///
+/// Same as [`gemm_basic_generic`], with the element at `(i, j)` of each matrix located
+/// `i * rs + j * cs` bytes from its pointer.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_basic_bytes_generic<
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    Lhs: Boilerplate,
+    Rhs: Boilerplate,
+    Dst: Boilerplate,
+    Acc: Boilerplate + Conj + Zero + One + core::ops::Mul<Output = Acc> + core::ops::Add<Output = Acc>,
+    S: MixedSimd<Lhs, Rhs, Dst, Acc>,
+>(
+    simd: S,
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut Dst,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const Lhs,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const Rhs,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: Acc,
+    beta: Acc,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    dispatcher: &[[MicroKernelFn<Acc>; NR]; MR_DIV_N],
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    let alpha = if read_dst { alpha } else { Acc::zero() };
+    let alpha_status = if alpha == Acc::zero() {
+        0
+    } else if alpha == Acc::one() && !conj_dst {
+        if k == 0 {
+            return Ok(());
+        }
+        1
+    } else {
+        2
+    };
+
+    driver::gemm_product_generic(
+        &Mixed::<N, MR, NR, MR_DIV_N, Lhs, Rhs, Dst, Acc, S> {
+            simd,
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut Lhs),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut Rhs),
+            rhs_cs,
+            rhs_rs,
+            alpha,
+            // the product is zero, even if `beta` is not finite
+            beta: if k == 0 { Acc::zero() } else { beta },
+            alpha_status,
+            conj_dst,
+            conj_lhs,
+            conj_rhs,
+            dispatcher,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __mixed_scale {
+    // `alpha` and `beta` have the destination type, unless another type is given
+    (@ty [], $dst: ty) => {
+        $dst
+    };
+    (@ty [$scale: ty], $dst: ty) => {
+        $scale
+    };
+    (@into_acc [], $from_dst: expr, $simd: expr, $value: expr) => {
+        $from_dst($simd, $value)
+    };
+    (@into_acc [$scale: ty], $from_dst: expr, $simd: expr, $value: expr) => {
+        $value
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+// the microkernels are those of the calling crate
+#[allow(clippy::crate_in_macro_def)]
+macro_rules! __inject_mod_mixed {
+    ($module: ident, $simd: ident, $N: expr, $lhs: ty, $rhs: ty, $dst: ty, $acc: ty, $scale: tt, $ukr_mod: ident, $ukr: ident, $mr_div_n: ident, $nr: ident) => {
+        mod $module {
+            #[allow(unused_imports)]
+            use super::*;
+            use crate::microkernel::$module::$ukr_mod::*;
+            use $crate::simd::MixedSimd;
+            const N: usize = $N;
+
+            #[inline(never)]
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn gemm_basic(
+                m: usize,
+                n: usize,
+                k: usize,
+                dst: *mut $dst,
+                dst_cs: isize,
+                dst_rs: isize,
+                read_dst: bool,
+                lhs: *const $lhs,
+                lhs_cs: isize,
+                lhs_rs: isize,
+                rhs: *const $rhs,
+                rhs_cs: isize,
+                rhs_rs: isize,
+                alpha: $crate::__mixed_scale!(@ty $scale, $dst),
+                beta: $crate::__mixed_scale!(@ty $scale, $dst),
+                conj_dst: bool,
+                conj_lhs: bool,
+                conj_rhs: bool,
+                parallelism: $crate::Parallelism,
+            ) -> Result<(), $crate::gemm::AllocError> {
+                let simd =
+                    <$crate::simd::$simd as MixedSimd<$lhs, $rhs, $dst, $acc>>::try_new().unwrap();
+                $crate::mixed::gemm_basic_generic::<
+                    N,
+                    { $mr_div_n * N },
+                    $nr,
+                    $mr_div_n,
+                    $lhs,
+                    $rhs,
+                    $dst,
+                    $acc,
+                    _,
+                >(
+                    simd,
+                    m,
+                    n,
+                    k,
+                    dst,
+                    dst_cs,
+                    dst_rs,
+                    read_dst,
+                    lhs,
+                    lhs_cs,
+                    lhs_rs,
+                    rhs,
+                    rhs_cs,
+                    rhs_rs,
+                    $crate::__mixed_scale!(
+                        @into_acc $scale,
+                        <$crate::simd::$simd as MixedSimd<$lhs, $rhs, $dst, $acc>>::from_dst,
+                        simd,
+                        alpha
+                    ),
+                    $crate::__mixed_scale!(
+                        @into_acc $scale,
+                        <$crate::simd::$simd as MixedSimd<$lhs, $rhs, $dst, $acc>>::from_dst,
+                        simd,
+                        beta
+                    ),
+                    conj_dst,
+                    conj_lhs,
+                    conj_rhs,
+                    &$ukr,
+                    parallelism,
+                )
+            }
+
+            #[inline(never)]
+            #[allow(clippy::too_many_arguments)]
+            pub unsafe fn gemm_basic_bytes(
+                m: usize,
+                n: usize,
+                k: usize,
+                dst: *mut $dst,
+                dst_cs: isize,
+                dst_rs: isize,
+                read_dst: bool,
+                lhs: *const $lhs,
+                lhs_cs: isize,
+                lhs_rs: isize,
+                rhs: *const $rhs,
+                rhs_cs: isize,
+                rhs_rs: isize,
+                alpha: $crate::__mixed_scale!(@ty $scale, $dst),
+                beta: $crate::__mixed_scale!(@ty $scale, $dst),
+                conj_dst: bool,
+                conj_lhs: bool,
+                conj_rhs: bool,
+                parallelism: $crate::Parallelism,
+            ) -> Result<(), $crate::gemm::AllocError> {
+                let simd =
+                    <$crate::simd::$simd as MixedSimd<$lhs, $rhs, $dst, $acc>>::try_new().unwrap();
+                $crate::mixed::gemm_basic_bytes_generic::<
+                    N,
+                    { $mr_div_n * N },
+                    $nr,
+                    $mr_div_n,
+                    $lhs,
+                    $rhs,
+                    $dst,
+                    $acc,
+                    _,
+                >(
+                    simd,
+                    m,
+                    n,
+                    k,
+                    dst,
+                    dst_cs,
+                    dst_rs,
+                    read_dst,
+                    lhs,
+                    lhs_cs,
+                    lhs_rs,
+                    rhs,
+                    rhs_cs,
+                    rhs_rs,
+                    $crate::__mixed_scale!(
+                        @into_acc $scale,
+                        <$crate::simd::$simd as MixedSimd<$lhs, $rhs, $dst, $acc>>::from_dst,
+                        simd,
+                        alpha
+                    ),
+                    $crate::__mixed_scale!(
+                        @into_acc $scale,
+                        <$crate::simd::$simd as MixedSimd<$lhs, $rhs, $dst, $acc>>::from_dst,
+                        simd,
+                        beta
+                    ),
+                    conj_dst,
+                    conj_lhs,
+                    conj_rhs,
+                    &$ukr,
+                    parallelism,
+                )
+            }
+        }
+    };
+}
+
+/// Defines `get_gemm_fn` for a product of a `$lhs` and a `$rhs` operand into a `$dst` destination, accumulated
+/// in `$acc` by the `crate::microkernel::<arch>::$ukr_mod` microkernels of the calling crate, and
+/// `get_gemm_bytes_fn` for the same product with the strides in bytes.
+///
+/// The arguments after the semicolon are the SIMD width of each architecture module, `avx512f`
+/// and `neon` can be omitted if the calling crate does not define them. `alpha` and `beta` have
+/// the type `$dst`, unless a trailing `; scale: $acc` is given, for destinations such as the FP8
+/// types that cannot represent them.
+#[macro_export]
+macro_rules! gemm_mixed_def {
+    (@select $gemm: ident $(, avx512f: $avx512f_n: expr)? $(, neon: $neon_n: expr)?) => {{
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            $(
+                const _: usize = $avx512f_n;
+                #[cfg(feature = "nightly")]
+                if $crate::feature_detected!("avx512f") {
+                    return avx512f::$gemm;
+                }
+            )?
+            if $crate::feature_detected!("fma") && $crate::feature_detected!("avx2") {
+                return fma::$gemm;
+            }
+        }
+
+        $(
+            const _: usize = $neon_n;
+            #[cfg(target_arch = "aarch64")]
+            if $crate::feature_detected!("neon") {
+                return neon::$gemm;
+            }
+        )?
+
+        scalar::$gemm
+    }};
+
+    (@cached $ptr: ident, $init: ident) => {{
+        let mut gemm_fn = $ptr.load(::core::sync::atomic::Ordering::Relaxed);
+        if gemm_fn.is_null() {
+            let init = $init();
+            $ptr.store(init as *mut (), ::core::sync::atomic::Ordering::Relaxed);
+            gemm_fn = init as *mut ();
+        }
+        unsafe { ::core::mem::transmute::<*mut (), GemmTy>(gemm_fn) }
+    }};
+
+    (
+        $lhs: ty, $rhs: ty, $dst: ty, $acc: ty, $ukr_mod: ident, $ukr: ident, $mr_div_n: ident, $nr: ident;
+        scalar: $scalar_n: expr, fma: $fma_n: expr $(, avx512f: $avx512f_n: expr)? $(, neon: $neon_n: expr)? $(,)?
+        $(; scale: $scale: ty $(,)?)?
+    ) => {
+        $crate::gemm_mixed_def!(
+            @def [$($scale)?], $lhs, $rhs, $dst, $acc, $ukr_mod, $ukr, $mr_div_n, $nr;
+            scalar: $scalar_n, fma: $fma_n $(, avx512f: $avx512f_n)? $(, neon: $neon_n)?
+        );
+    };
+
+    (
+        @def $scale: tt, $lhs: ty, $rhs: ty, $dst: ty, $acc: ty, $ukr_mod: ident, $ukr: ident, $mr_div_n: ident, $nr: ident;
+        scalar: $scalar_n: expr, fma: $fma_n: expr $(, avx512f: $avx512f_n: expr)? $(, neon: $neon_n: expr)?
+    ) => {
+        type GemmTy = unsafe fn(
+            usize,
+            usize,
+            usize,
+            *mut $dst,
+            isize,
+            isize,
+            bool,
+            *const $lhs,
+            isize,
+            isize,
+            *const $rhs,
+            isize,
+            isize,
+            $crate::__mixed_scale!(@ty $scale, $dst),
+            $crate::__mixed_scale!(@ty $scale, $dst),
+            bool,
+            bool,
+            bool,
+            $crate::Parallelism,
+        ) -> Result<(), $crate::gemm::AllocError>;
+
+        fn init_gemm_fn() -> GemmTy {
+            $crate::gemm_mixed_def!(@select gemm_basic $(, avx512f: $avx512f_n)? $(, neon: $neon_n)?)
+        }
+
+        fn init_gemm_bytes_fn() -> GemmTy {
+            $crate::gemm_mixed_def!(@select gemm_basic_bytes $(, avx512f: $avx512f_n)? $(, neon: $neon_n)?)
+        }
+
+        static GEMM_PTR: ::core::sync::atomic::AtomicPtr<()> =
+            ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut());
+        static GEMM_BYTES_PTR: ::core::sync::atomic::AtomicPtr<()> =
+            ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut());
+
+        #[inline(always)]
+        pub fn get_gemm_fn() -> GemmTy {
+            $crate::gemm_mixed_def!(@cached GEMM_PTR, init_gemm_fn)
+        }
+
+        /// Same as `get_gemm_fn`, with the strides of the returned function in bytes. The
+        /// elements do not need to be aligned.
+        #[inline(always)]
+        pub fn get_gemm_bytes_fn() -> GemmTy {
+            $crate::gemm_mixed_def!(@cached GEMM_BYTES_PTR, init_gemm_bytes_fn)
+        }
+
+        $crate::__inject_mod_mixed!(scalar, Scalar, $scalar_n, $lhs, $rhs, $dst, $acc, $scale, $ukr_mod, $ukr, $mr_div_n, $nr);
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        $crate::__inject_mod_mixed!(fma, V3, $fma_n, $lhs, $rhs, $dst, $acc, $scale, $ukr_mod, $ukr, $mr_div_n, $nr);
+
+        $(
+            #[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+            $crate::__inject_mod_mixed!(avx512f, V4, $avx512f_n, $lhs, $rhs, $dst, $acc, $scale, $ukr_mod, $ukr, $mr_div_n, $nr);
+        )?
+
+        $(
+            #[cfg(target_arch = "aarch64")]
+            $crate::__inject_mod_mixed!(neon, Neon, $neon_n, $lhs, $rhs, $dst, $acc, $scale, $ukr_mod, $ukr, $mr_div_n, $nr);
+        )?
+    };
+}
@@ -0,0 +1,434 @@
+//! Exact products of matrices of residues modulo a word-size integer `p`.
+//!
+//! The products are accumulated in integers twice as wide as the residues, over depth blocks that
+//! are short enough for the sums not to overflow, and the accumulators are reduced at the end of
+//! each block with a precomputed reciprocal of `p`, so that no division is needed.
+
+use crate::{
+    driver::{self, Product},
+    gemm::AllocError,
+    pack_operands::{pack_lhs, pack_rhs},
+    simd::Scalar,
+    Parallelism, Ptr,
+};
+use num_traits::Zero;
+
+/// Register block of the microkernel.
+const MR: usize = 4;
+const NR: usize = 4;
+
+/// Residue types that [`gemm_mod`] can multiply, implemented for `u32` and `u64`.
+pub trait ModScalar: Copy + Zero + PartialOrd + Send + Sync + 'static {
+    /// Accumulator type, twice as wide as `Self`.
+    #[doc(hidden)]
+    type Acc: Copy + Zero + Send + Sync + 'static;
+    /// Modulus, along with its precomputed reciprocal.
+    #[doc(hidden)]
+    type Modulus: Copy + Send + Sync;
+
+    #[doc(hidden)]
+    fn modulus(p: Self) -> Self::Modulus;
+    /// Number of products that can be added to a reduced accumulator without overflowing.
+    #[doc(hidden)]
+    fn max_terms(p: &Self::Modulus) -> usize;
+    #[doc(hidden)]
+    fn mul_add(acc: Self::Acc, lhs: Self, rhs: Self) -> Self::Acc;
+    #[doc(hidden)]
+    fn reduce(p: &Self::Modulus, acc: Self::Acc) -> Self;
+    #[doc(hidden)]
+    fn widen(self) -> Self::Acc;
+    #[doc(hidden)]
+    fn add_mod(p: &Self::Modulus, lhs: Self, rhs: Self) -> Self;
+}
+
+/// `p`, with `r = ⌊(2^64 - 1) / p⌋`.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug)]
+pub struct Modulus32 {
+    p: u64,
+    r: u64,
+}
+
+/// `p`, normalized as `d = p << shift` so that the top bit of `d` is set, with
+/// `v = ⌊(2^128 - 1) / d⌋ - 2^64`.
+#[doc(hidden)]
+#[derive(Copy, Clone, Debug)]
+pub struct Modulus64 {
+    p: u64,
+    d: u64,
+    v: u64,
+    shift: u32,
+}
+
+impl Modulus64 {
+    /// Remainder of `u1 × 2^64 + u0` divided by `d`, where `u1 < d`.
+    ///
+    /// See Möller and Granlund, "Improved division by invariant integers".
+    #[inline(always)]
+    fn rem_2by1(&self, u1: u64, u0: u64) -> u64 {
+        let q = (self.v as u128 * u1 as u128).wrapping_add((((u1 + 1) as u128) << 64) | u0 as u128);
+        let (q1, q0) = ((q >> 64) as u64, q as u64);
+        let mut r = u0.wrapping_sub(q1.wrapping_mul(self.d));
+        if r > q0 {
+            r = r.wrapping_add(self.d);
+        }
+        if r >= self.d {
+            r -= self.d;
+        }
+        r
+    }
+}
+
+impl ModScalar for u32 {
+    type Acc = u64;
+    type Modulus = Modulus32;
+
+    #[inline]
+    fn modulus(p: Self) -> Modulus32 {
+        assert!(p >= 2);
+        let p = p as u64;
+        Modulus32 { p, r: u64::MAX / p }
+    }
+
+    #[inline]
+    fn max_terms(p: &Modulus32) -> usize {
+        let max = p.p - 1;
+        ((u64::MAX - max) / (max * max))
+            .try_into()
+            .unwrap_or(usize::MAX)
+    }
+
+    #[inline(always)]
+    fn mul_add(acc: u64, lhs: Self, rhs: Self) -> u64 {
+        acc.wrapping_add(lhs as u64 * rhs as u64)
+    }
+
+    #[inline(always)]
+    fn reduce(p: &Modulus32, acc: u64) -> Self {
+        // the estimated quotient is off by at most one
+        let q = ((acc as u128 * p.r as u128) >> 64) as u64;
+        let r = acc - q * p.p;
+        (if r >= p.p { r - p.p } else { r }) as u32
+    }
+
+    #[inline(always)]
+    fn widen(self) -> u64 {
+        self as u64
+    }
+
+    #[inline(always)]
+    fn add_mod(p: &Modulus32, lhs: Self, rhs: Self) -> Self {
+        let sum = lhs as u64 + rhs as u64;
+        (if sum >= p.p { sum - p.p } else { sum }) as u32
+    }
+}
+
+impl ModScalar for u64 {
+    type Acc = u128;
+    type Modulus = Modulus64;
+
+    #[inline]
+    fn modulus(p: Self) -> Modulus64 {
+        assert!(p >= 2);
+        let shift = p.leading_zeros();
+        let d = p << shift;
+        let v = (u128::MAX / d as u128 - (1u128 << 64)) as u64;
+        Modulus64 { p, d, v, shift }
+    }
+
+    #[inline]
+    fn max_terms(p: &Modulus64) -> usize {
+        let max = (p.p - 1) as u128;
+        ((u128::MAX - max) / (max * max))
+            .try_into()
+            .unwrap_or(usize::MAX)
+    }
+
+    #[inline(always)]
+    fn mul_add(acc: u128, lhs: Self, rhs: Self) -> u128 {
+        acc.wrapping_add(lhs as u128 * rhs as u128)
+    }
+
+    #[inline(always)]
+    fn reduce(p: &Modulus64, acc: u128) -> Self {
+        let s = p.shift;
+        let (hi, lo) = ((acc >> 64) as u64, acc as u64);
+        // `hi` is reduced first so that the high word of the normalized numerator is below `d`.
+        // `x >> 1 >> (63 - s)` is `x >> (64 - s)`, and is also valid for `s == 0`
+        let hi = p.rem_2by1(hi >> 1 >> (63 - s), hi << s);
+        let r = p.rem_2by1(hi | (lo >> 1 >> (63 - s)), lo << s);
+        r >> s
+    }
+
+    #[inline(always)]
+    fn widen(self) -> u128 {
+        self as u128
+    }
+
+    #[inline(always)]
+    fn add_mod(p: &Modulus64, lhs: Self, rhs: Self) -> Self {
+        let (sum, overflow) = lhs.overflowing_add(rhs);
+        if overflow || sum >= p.p {
+            sum.wrapping_sub(p.p)
+        } else {
+            sum
+        }
+    }
+}
+
+#[inline(always)]
+fn mul_mod<T: ModScalar>(p: &T::Modulus, lhs: T, rhs: T) -> T {
+    T::reduce(p, T::mul_add(T::Acc::zero(), lhs, rhs))
+}
+
+type ModKernelFn<T> = unsafe fn(
+    k: usize,
+    acc: *mut <T as ModScalar>::Acc,
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    first: bool,
+);
+
+/// Adds the products of a depth block to a full `MR × NR` block of accumulators, stored in
+/// column-major order with a column stride of `MR`, starting from zero if `first` is set.
+#[inline(always)]
+unsafe fn kernel<T: ModScalar>(
+    k: usize,
+    acc: *mut T::Acc,
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    first: bool,
+) {
+    let mut local = [[T::Acc::zero(); MR]; NR];
+    if !first {
+        for (j, local) in local.iter_mut().enumerate() {
+            for (i, local) in local.iter_mut().enumerate() {
+                *local = *acc.add(i + j * MR);
+            }
+        }
+    }
+
+    for depth in 0..k {
+        let lhs: [T; MR] = core::array::from_fn(|i| *packed_lhs.add(depth * MR + i));
+        for (j, local) in local.iter_mut().enumerate() {
+            let rhs = *packed_rhs.add(depth * NR + j);
+            for (local, &lhs) in local.iter_mut().zip(&lhs) {
+                *local = T::mul_add(*local, lhs, rhs);
+            }
+        }
+    }
+
+    for (j, local) in local.iter().enumerate() {
+        for (i, &local) in local.iter().enumerate() {
+            *acc.add(i + j * MR) = local;
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn kernel_avx2<T: ModScalar>(
+    k: usize,
+    acc: *mut T::Acc,
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    first: bool,
+) {
+    kernel::<T>(k, acc, packed_lhs, packed_rhs, first);
+}
+
+/// dst := alpha×dst + beta×lhs×rhs modulo `p`, computed by [`driver::gemm_product_generic`].
+struct Modular<T: ModScalar> {
+    dst: Ptr<T>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<T>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<T>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    read_dst: bool,
+    p: T::Modulus,
+    max_terms: usize,
+    kernel: ModKernelFn<T>,
+}
+
+impl<T: ModScalar> Product for Modular<T> {
+    type Lhs = T;
+    type Rhs = T;
+    type Acc = T::Acc;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut T,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs::<T, 1, MR, _>(
+            Scalar,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut T,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs::<T, 1, NR, _>(
+            Scalar,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs,
+            self.rhs_rs,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut T::Acc,
+        lhs: *const T,
+        rhs: *const T,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        // the chunks of the driver may be longer than the accumulators allow, so the tile is
+        // reduced after every `max_terms` products
+        let mut first = first;
+        let mut depth = 0;
+        loop {
+            let k_chunk = self.max_terms.min(k - depth);
+            (self.kernel)(
+                k_chunk,
+                acc,
+                lhs.add(depth * MR),
+                rhs.add(depth * NR),
+                first,
+            );
+            for j in 0..ncols {
+                for i in 0..nrows {
+                    let acc = acc.add(i + j * MR);
+                    *acc = T::reduce(&self.p, *acc).widen();
+                }
+            }
+
+            first = false;
+            depth += k_chunk;
+            if depth == k {
+                break;
+            }
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const T::Acc, row: usize, col: usize, nrows: usize, ncols: usize) {
+        let p = &self.p;
+        for j in 0..ncols {
+            for i in 0..nrows {
+                let value = mul_mod(p, self.beta, T::reduce(p, *acc.add(i + j * MR)));
+                let dst = self
+                    .dst
+                    .wrapping_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0;
+                *dst = if self.read_dst {
+                    T::add_mod(p, mul_mod(p, self.alpha, *dst), value)
+                } else {
+                    value
+                };
+            }
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, modulo `p`
+///
+/// The inputs, including `alpha`, `beta` and `dst` if `read_dst` is set, must be residues in
+/// `0..p`, and so are the outputs. `p` does not need to be prime, but must be at least `2`.
+///
+/// # Safety
+///
+/// The matrices must be valid for the given dimensions and strides, and `dst` must not overlap
+/// `lhs` or `rhs`.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_mod<T: ModScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    p: T,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    let p = T::modulus(p);
+
+    let mut kernel: ModKernelFn<T> = kernel::<T>;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if crate::feature_detected!("avx2") {
+        kernel = kernel_avx2::<T>;
+    }
+
+    driver::gemm_product_generic(
+        &Modular {
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut T),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut T),
+            rhs_cs,
+            rhs_rs,
+            alpha,
+            beta,
+            read_dst,
+            max_terms: T::max_terms(&p).max(1),
+            p,
+            kernel,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
@@ -1,76 +1,77 @@
-use crate::simd::Simd;
+use crate::{cache::DivCeil, simd::Simd};
+use num_traits::Zero;
 
 #[inline(always)]
-pub fn quick_zero<T: Copy>(slice: &mut [core::mem::MaybeUninit<T>]) {
+pub fn quick_zero<T: Copy + Zero>(slice: &mut [core::mem::MaybeUninit<T>]) {
     let n = slice.len();
     match n {
-        1 => unsafe { *(slice.as_mut_ptr() as *mut [T; 1]) = core::mem::zeroed() },
-        2 => unsafe { *(slice.as_mut_ptr() as *mut [T; 2]) = core::mem::zeroed() },
-        3 => unsafe { *(slice.as_mut_ptr() as *mut [T; 3]) = core::mem::zeroed() },
-        4 => unsafe { *(slice.as_mut_ptr() as *mut [T; 4]) = core::mem::zeroed() },
-        5 => unsafe { *(slice.as_mut_ptr() as *mut [T; 5]) = core::mem::zeroed() },
-        6 => unsafe { *(slice.as_mut_ptr() as *mut [T; 6]) = core::mem::zeroed() },
-        7 => unsafe { *(slice.as_mut_ptr() as *mut [T; 7]) = core::mem::zeroed() },
-        8 => unsafe { *(slice.as_mut_ptr() as *mut [T; 8]) = core::mem::zeroed() },
-        9 => unsafe { *(slice.as_mut_ptr() as *mut [T; 9]) = core::mem::zeroed() },
-        10 => unsafe { *(slice.as_mut_ptr() as *mut [T; 10]) = core::mem::zeroed() },
-        11 => unsafe { *(slice.as_mut_ptr() as *mut [T; 11]) = core::mem::zeroed() },
-        12 => unsafe { *(slice.as_mut_ptr() as *mut [T; 12]) = core::mem::zeroed() },
-        13 => unsafe { *(slice.as_mut_ptr() as *mut [T; 13]) = core::mem::zeroed() },
-        14 => unsafe { *(slice.as_mut_ptr() as *mut [T; 14]) = core::mem::zeroed() },
-        15 => unsafe { *(slice.as_mut_ptr() as *mut [T; 15]) = core::mem::zeroed() },
-        16 => unsafe { *(slice.as_mut_ptr() as *mut [T; 16]) = core::mem::zeroed() },
-        17 => unsafe { *(slice.as_mut_ptr() as *mut [T; 17]) = core::mem::zeroed() },
-        18 => unsafe { *(slice.as_mut_ptr() as *mut [T; 18]) = core::mem::zeroed() },
-        19 => unsafe { *(slice.as_mut_ptr() as *mut [T; 19]) = core::mem::zeroed() },
-        20 => unsafe { *(slice.as_mut_ptr() as *mut [T; 20]) = core::mem::zeroed() },
-        21 => unsafe { *(slice.as_mut_ptr() as *mut [T; 21]) = core::mem::zeroed() },
-        22 => unsafe { *(slice.as_mut_ptr() as *mut [T; 22]) = core::mem::zeroed() },
-        23 => unsafe { *(slice.as_mut_ptr() as *mut [T; 23]) = core::mem::zeroed() },
-        24 => unsafe { *(slice.as_mut_ptr() as *mut [T; 24]) = core::mem::zeroed() },
-        25 => unsafe { *(slice.as_mut_ptr() as *mut [T; 25]) = core::mem::zeroed() },
-        26 => unsafe { *(slice.as_mut_ptr() as *mut [T; 26]) = core::mem::zeroed() },
-        27 => unsafe { *(slice.as_mut_ptr() as *mut [T; 27]) = core::mem::zeroed() },
-        28 => unsafe { *(slice.as_mut_ptr() as *mut [T; 28]) = core::mem::zeroed() },
-        29 => unsafe { *(slice.as_mut_ptr() as *mut [T; 29]) = core::mem::zeroed() },
-        30 => unsafe { *(slice.as_mut_ptr() as *mut [T; 30]) = core::mem::zeroed() },
-        31 => unsafe { *(slice.as_mut_ptr() as *mut [T; 31]) = core::mem::zeroed() },
-        32 => unsafe { *(slice.as_mut_ptr() as *mut [T; 32]) = core::mem::zeroed() },
-        33 => unsafe { *(slice.as_mut_ptr() as *mut [T; 33]) = core::mem::zeroed() },
-        34 => unsafe { *(slice.as_mut_ptr() as *mut [T; 34]) = core::mem::zeroed() },
-        35 => unsafe { *(slice.as_mut_ptr() as *mut [T; 35]) = core::mem::zeroed() },
-        36 => unsafe { *(slice.as_mut_ptr() as *mut [T; 36]) = core::mem::zeroed() },
-        37 => unsafe { *(slice.as_mut_ptr() as *mut [T; 37]) = core::mem::zeroed() },
-        38 => unsafe { *(slice.as_mut_ptr() as *mut [T; 38]) = core::mem::zeroed() },
-        39 => unsafe { *(slice.as_mut_ptr() as *mut [T; 39]) = core::mem::zeroed() },
-        40 => unsafe { *(slice.as_mut_ptr() as *mut [T; 40]) = core::mem::zeroed() },
-        41 => unsafe { *(slice.as_mut_ptr() as *mut [T; 41]) = core::mem::zeroed() },
-        42 => unsafe { *(slice.as_mut_ptr() as *mut [T; 42]) = core::mem::zeroed() },
-        43 => unsafe { *(slice.as_mut_ptr() as *mut [T; 43]) = core::mem::zeroed() },
-        44 => unsafe { *(slice.as_mut_ptr() as *mut [T; 44]) = core::mem::zeroed() },
-        45 => unsafe { *(slice.as_mut_ptr() as *mut [T; 45]) = core::mem::zeroed() },
-        46 => unsafe { *(slice.as_mut_ptr() as *mut [T; 46]) = core::mem::zeroed() },
-        47 => unsafe { *(slice.as_mut_ptr() as *mut [T; 47]) = core::mem::zeroed() },
-        48 => unsafe { *(slice.as_mut_ptr() as *mut [T; 48]) = core::mem::zeroed() },
-        49 => unsafe { *(slice.as_mut_ptr() as *mut [T; 49]) = core::mem::zeroed() },
-        50 => unsafe { *(slice.as_mut_ptr() as *mut [T; 50]) = core::mem::zeroed() },
-        51 => unsafe { *(slice.as_mut_ptr() as *mut [T; 51]) = core::mem::zeroed() },
-        52 => unsafe { *(slice.as_mut_ptr() as *mut [T; 52]) = core::mem::zeroed() },
-        53 => unsafe { *(slice.as_mut_ptr() as *mut [T; 53]) = core::mem::zeroed() },
-        54 => unsafe { *(slice.as_mut_ptr() as *mut [T; 54]) = core::mem::zeroed() },
-        55 => unsafe { *(slice.as_mut_ptr() as *mut [T; 55]) = core::mem::zeroed() },
-        56 => unsafe { *(slice.as_mut_ptr() as *mut [T; 56]) = core::mem::zeroed() },
-        57 => unsafe { *(slice.as_mut_ptr() as *mut [T; 57]) = core::mem::zeroed() },
-        58 => unsafe { *(slice.as_mut_ptr() as *mut [T; 58]) = core::mem::zeroed() },
-        59 => unsafe { *(slice.as_mut_ptr() as *mut [T; 59]) = core::mem::zeroed() },
-        60 => unsafe { *(slice.as_mut_ptr() as *mut [T; 60]) = core::mem::zeroed() },
-        61 => unsafe { *(slice.as_mut_ptr() as *mut [T; 61]) = core::mem::zeroed() },
-        62 => unsafe { *(slice.as_mut_ptr() as *mut [T; 62]) = core::mem::zeroed() },
-        63 => unsafe { *(slice.as_mut_ptr() as *mut [T; 63]) = core::mem::zeroed() },
-        64 => unsafe { *(slice.as_mut_ptr() as *mut [T; 64]) = core::mem::zeroed() },
+        1 => unsafe { *(slice.as_mut_ptr() as *mut [T; 1]) = [T::zero(); 1] },
+        2 => unsafe { *(slice.as_mut_ptr() as *mut [T; 2]) = [T::zero(); 2] },
+        3 => unsafe { *(slice.as_mut_ptr() as *mut [T; 3]) = [T::zero(); 3] },
+        4 => unsafe { *(slice.as_mut_ptr() as *mut [T; 4]) = [T::zero(); 4] },
+        5 => unsafe { *(slice.as_mut_ptr() as *mut [T; 5]) = [T::zero(); 5] },
+        6 => unsafe { *(slice.as_mut_ptr() as *mut [T; 6]) = [T::zero(); 6] },
+        7 => unsafe { *(slice.as_mut_ptr() as *mut [T; 7]) = [T::zero(); 7] },
+        8 => unsafe { *(slice.as_mut_ptr() as *mut [T; 8]) = [T::zero(); 8] },
+        9 => unsafe { *(slice.as_mut_ptr() as *mut [T; 9]) = [T::zero(); 9] },
+        10 => unsafe { *(slice.as_mut_ptr() as *mut [T; 10]) = [T::zero(); 10] },
+        11 => unsafe { *(slice.as_mut_ptr() as *mut [T; 11]) = [T::zero(); 11] },
+        12 => unsafe { *(slice.as_mut_ptr() as *mut [T; 12]) = [T::zero(); 12] },
+        13 => unsafe { *(slice.as_mut_ptr() as *mut [T; 13]) = [T::zero(); 13] },
+        14 => unsafe { *(slice.as_mut_ptr() as *mut [T; 14]) = [T::zero(); 14] },
+        15 => unsafe { *(slice.as_mut_ptr() as *mut [T; 15]) = [T::zero(); 15] },
+        16 => unsafe { *(slice.as_mut_ptr() as *mut [T; 16]) = [T::zero(); 16] },
+        17 => unsafe { *(slice.as_mut_ptr() as *mut [T; 17]) = [T::zero(); 17] },
+        18 => unsafe { *(slice.as_mut_ptr() as *mut [T; 18]) = [T::zero(); 18] },
+        19 => unsafe { *(slice.as_mut_ptr() as *mut [T; 19]) = [T::zero(); 19] },
+        20 => unsafe { *(slice.as_mut_ptr() as *mut [T; 20]) = [T::zero(); 20] },
+        21 => unsafe { *(slice.as_mut_ptr() as *mut [T; 21]) = [T::zero(); 21] },
+        22 => unsafe { *(slice.as_mut_ptr() as *mut [T; 22]) = [T::zero(); 22] },
+        23 => unsafe { *(slice.as_mut_ptr() as *mut [T; 23]) = [T::zero(); 23] },
+        24 => unsafe { *(slice.as_mut_ptr() as *mut [T; 24]) = [T::zero(); 24] },
+        25 => unsafe { *(slice.as_mut_ptr() as *mut [T; 25]) = [T::zero(); 25] },
+        26 => unsafe { *(slice.as_mut_ptr() as *mut [T; 26]) = [T::zero(); 26] },
+        27 => unsafe { *(slice.as_mut_ptr() as *mut [T; 27]) = [T::zero(); 27] },
+        28 => unsafe { *(slice.as_mut_ptr() as *mut [T; 28]) = [T::zero(); 28] },
+        29 => unsafe { *(slice.as_mut_ptr() as *mut [T; 29]) = [T::zero(); 29] },
+        30 => unsafe { *(slice.as_mut_ptr() as *mut [T; 30]) = [T::zero(); 30] },
+        31 => unsafe { *(slice.as_mut_ptr() as *mut [T; 31]) = [T::zero(); 31] },
+        32 => unsafe { *(slice.as_mut_ptr() as *mut [T; 32]) = [T::zero(); 32] },
+        33 => unsafe { *(slice.as_mut_ptr() as *mut [T; 33]) = [T::zero(); 33] },
+        34 => unsafe { *(slice.as_mut_ptr() as *mut [T; 34]) = [T::zero(); 34] },
+        35 => unsafe { *(slice.as_mut_ptr() as *mut [T; 35]) = [T::zero(); 35] },
+        36 => unsafe { *(slice.as_mut_ptr() as *mut [T; 36]) = [T::zero(); 36] },
+        37 => unsafe { *(slice.as_mut_ptr() as *mut [T; 37]) = [T::zero(); 37] },
+        38 => unsafe { *(slice.as_mut_ptr() as *mut [T; 38]) = [T::zero(); 38] },
+        39 => unsafe { *(slice.as_mut_ptr() as *mut [T; 39]) = [T::zero(); 39] },
+        40 => unsafe { *(slice.as_mut_ptr() as *mut [T; 40]) = [T::zero(); 40] },
+        41 => unsafe { *(slice.as_mut_ptr() as *mut [T; 41]) = [T::zero(); 41] },
+        42 => unsafe { *(slice.as_mut_ptr() as *mut [T; 42]) = [T::zero(); 42] },
+        43 => unsafe { *(slice.as_mut_ptr() as *mut [T; 43]) = [T::zero(); 43] },
+        44 => unsafe { *(slice.as_mut_ptr() as *mut [T; 44]) = [T::zero(); 44] },
+        45 => unsafe { *(slice.as_mut_ptr() as *mut [T; 45]) = [T::zero(); 45] },
+        46 => unsafe { *(slice.as_mut_ptr() as *mut [T; 46]) = [T::zero(); 46] },
+        47 => unsafe { *(slice.as_mut_ptr() as *mut [T; 47]) = [T::zero(); 47] },
+        48 => unsafe { *(slice.as_mut_ptr() as *mut [T; 48]) = [T::zero(); 48] },
+        49 => unsafe { *(slice.as_mut_ptr() as *mut [T; 49]) = [T::zero(); 49] },
+        50 => unsafe { *(slice.as_mut_ptr() as *mut [T; 50]) = [T::zero(); 50] },
+        51 => unsafe { *(slice.as_mut_ptr() as *mut [T; 51]) = [T::zero(); 51] },
+        52 => unsafe { *(slice.as_mut_ptr() as *mut [T; 52]) = [T::zero(); 52] },
+        53 => unsafe { *(slice.as_mut_ptr() as *mut [T; 53]) = [T::zero(); 53] },
+        54 => unsafe { *(slice.as_mut_ptr() as *mut [T; 54]) = [T::zero(); 54] },
+        55 => unsafe { *(slice.as_mut_ptr() as *mut [T; 55]) = [T::zero(); 55] },
+        56 => unsafe { *(slice.as_mut_ptr() as *mut [T; 56]) = [T::zero(); 56] },
+        57 => unsafe { *(slice.as_mut_ptr() as *mut [T; 57]) = [T::zero(); 57] },
+        58 => unsafe { *(slice.as_mut_ptr() as *mut [T; 58]) = [T::zero(); 58] },
+        59 => unsafe { *(slice.as_mut_ptr() as *mut [T; 59]) = [T::zero(); 59] },
+        60 => unsafe { *(slice.as_mut_ptr() as *mut [T; 60]) = [T::zero(); 60] },
+        61 => unsafe { *(slice.as_mut_ptr() as *mut [T; 61]) = [T::zero(); 61] },
+        62 => unsafe { *(slice.as_mut_ptr() as *mut [T; 62]) = [T::zero(); 62] },
+        63 => unsafe { *(slice.as_mut_ptr() as *mut [T; 63]) = [T::zero(); 63] },
+        64 => unsafe { *(slice.as_mut_ptr() as *mut [T; 64]) = [T::zero(); 64] },
         _ => {
             for value in slice {
-                *value = unsafe { core::mem::zeroed() };
+                value.write(T::zero());
             }
         }
     }
@@ -79,88 +80,156 @@ pub fn quick_zero<T: Copy>(slice: &mut [core::mem::MaybeUninit<T>]) {
 #[inline(always)]
 unsafe fn quick_copy<T: Copy>(dst: *mut T, src: *const T, n: usize) {
     match n {
-        1 => unsafe { *(dst as *mut [T; 1]) = *(src as *const [T; 1]) },
-        2 => unsafe { *(dst as *mut [T; 2]) = *(src as *const [T; 2]) },
-        3 => unsafe { *(dst as *mut [T; 3]) = *(src as *const [T; 3]) },
-        4 => unsafe { *(dst as *mut [T; 4]) = *(src as *const [T; 4]) },
-        5 => unsafe { *(dst as *mut [T; 5]) = *(src as *const [T; 5]) },
-        6 => unsafe { *(dst as *mut [T; 6]) = *(src as *const [T; 6]) },
-        7 => unsafe { *(dst as *mut [T; 7]) = *(src as *const [T; 7]) },
-        8 => unsafe { *(dst as *mut [T; 8]) = *(src as *const [T; 8]) },
-        9 => unsafe { *(dst as *mut [T; 9]) = *(src as *const [T; 9]) },
-        10 => unsafe { *(dst as *mut [T; 10]) = *(src as *const [T; 10]) },
-        11 => unsafe { *(dst as *mut [T; 11]) = *(src as *const [T; 11]) },
-        12 => unsafe { *(dst as *mut [T; 12]) = *(src as *const [T; 12]) },
-        13 => unsafe { *(dst as *mut [T; 13]) = *(src as *const [T; 13]) },
-        14 => unsafe { *(dst as *mut [T; 14]) = *(src as *const [T; 14]) },
-        15 => unsafe { *(dst as *mut [T; 15]) = *(src as *const [T; 15]) },
-        16 => unsafe { *(dst as *mut [T; 16]) = *(src as *const [T; 16]) },
-        17 => unsafe { *(dst as *mut [T; 17]) = *(src as *const [T; 17]) },
-        18 => unsafe { *(dst as *mut [T; 18]) = *(src as *const [T; 18]) },
-        19 => unsafe { *(dst as *mut [T; 19]) = *(src as *const [T; 19]) },
-        20 => unsafe { *(dst as *mut [T; 20]) = *(src as *const [T; 20]) },
-        21 => unsafe { *(dst as *mut [T; 21]) = *(src as *const [T; 21]) },
-        22 => unsafe { *(dst as *mut [T; 22]) = *(src as *const [T; 22]) },
-        23 => unsafe { *(dst as *mut [T; 23]) = *(src as *const [T; 23]) },
-        24 => unsafe { *(dst as *mut [T; 24]) = *(src as *const [T; 24]) },
-        25 => unsafe { *(dst as *mut [T; 25]) = *(src as *const [T; 25]) },
-        26 => unsafe { *(dst as *mut [T; 26]) = *(src as *const [T; 26]) },
-        27 => unsafe { *(dst as *mut [T; 27]) = *(src as *const [T; 27]) },
-        28 => unsafe { *(dst as *mut [T; 28]) = *(src as *const [T; 28]) },
-        29 => unsafe { *(dst as *mut [T; 29]) = *(src as *const [T; 29]) },
-        30 => unsafe { *(dst as *mut [T; 30]) = *(src as *const [T; 30]) },
-        31 => unsafe { *(dst as *mut [T; 31]) = *(src as *const [T; 31]) },
-        32 => unsafe { *(dst as *mut [T; 32]) = *(src as *const [T; 32]) },
-        33 => unsafe { *(dst as *mut [T; 33]) = *(src as *const [T; 33]) },
-        34 => unsafe { *(dst as *mut [T; 34]) = *(src as *const [T; 34]) },
-        35 => unsafe { *(dst as *mut [T; 35]) = *(src as *const [T; 35]) },
-        36 => unsafe { *(dst as *mut [T; 36]) = *(src as *const [T; 36]) },
-        37 => unsafe { *(dst as *mut [T; 37]) = *(src as *const [T; 37]) },
-        38 => unsafe { *(dst as *mut [T; 38]) = *(src as *const [T; 38]) },
-        39 => unsafe { *(dst as *mut [T; 39]) = *(src as *const [T; 39]) },
-        40 => unsafe { *(dst as *mut [T; 40]) = *(src as *const [T; 40]) },
-        41 => unsafe { *(dst as *mut [T; 41]) = *(src as *const [T; 41]) },
-        42 => unsafe { *(dst as *mut [T; 42]) = *(src as *const [T; 42]) },
-        43 => unsafe { *(dst as *mut [T; 43]) = *(src as *const [T; 43]) },
-        44 => unsafe { *(dst as *mut [T; 44]) = *(src as *const [T; 44]) },
-        45 => unsafe { *(dst as *mut [T; 45]) = *(src as *const [T; 45]) },
-        46 => unsafe { *(dst as *mut [T; 46]) = *(src as *const [T; 46]) },
-        47 => unsafe { *(dst as *mut [T; 47]) = *(src as *const [T; 47]) },
-        48 => unsafe { *(dst as *mut [T; 48]) = *(src as *const [T; 48]) },
-        49 => unsafe { *(dst as *mut [T; 49]) = *(src as *const [T; 49]) },
-        50 => unsafe { *(dst as *mut [T; 50]) = *(src as *const [T; 50]) },
-        51 => unsafe { *(dst as *mut [T; 51]) = *(src as *const [T; 51]) },
-        52 => unsafe { *(dst as *mut [T; 52]) = *(src as *const [T; 52]) },
-        53 => unsafe { *(dst as *mut [T; 53]) = *(src as *const [T; 53]) },
-        54 => unsafe { *(dst as *mut [T; 54]) = *(src as *const [T; 54]) },
-        55 => unsafe { *(dst as *mut [T; 55]) = *(src as *const [T; 55]) },
-        56 => unsafe { *(dst as *mut [T; 56]) = *(src as *const [T; 56]) },
-        57 => unsafe { *(dst as *mut [T; 57]) = *(src as *const [T; 57]) },
-        58 => unsafe { *(dst as *mut [T; 58]) = *(src as *const [T; 58]) },
-        59 => unsafe { *(dst as *mut [T; 59]) = *(src as *const [T; 59]) },
-        60 => unsafe { *(dst as *mut [T; 60]) = *(src as *const [T; 60]) },
-        61 => unsafe { *(dst as *mut [T; 61]) = *(src as *const [T; 61]) },
-        62 => unsafe { *(dst as *mut [T; 62]) = *(src as *const [T; 62]) },
-        63 => unsafe { *(dst as *mut [T; 63]) = *(src as *const [T; 63]) },
-        64 => unsafe { *(dst as *mut [T; 64]) = *(src as *const [T; 64]) },
-        _ => core::ptr::copy_nonoverlapping(src, dst, n),
+        1 => unsafe { *(dst as *mut [T; 1]) = (src as *const [T; 1]).read_unaligned() },
+        2 => unsafe { *(dst as *mut [T; 2]) = (src as *const [T; 2]).read_unaligned() },
+        3 => unsafe { *(dst as *mut [T; 3]) = (src as *const [T; 3]).read_unaligned() },
+        4 => unsafe { *(dst as *mut [T; 4]) = (src as *const [T; 4]).read_unaligned() },
+        5 => unsafe { *(dst as *mut [T; 5]) = (src as *const [T; 5]).read_unaligned() },
+        6 => unsafe { *(dst as *mut [T; 6]) = (src as *const [T; 6]).read_unaligned() },
+        7 => unsafe { *(dst as *mut [T; 7]) = (src as *const [T; 7]).read_unaligned() },
+        8 => unsafe { *(dst as *mut [T; 8]) = (src as *const [T; 8]).read_unaligned() },
+        9 => unsafe { *(dst as *mut [T; 9]) = (src as *const [T; 9]).read_unaligned() },
+        10 => unsafe { *(dst as *mut [T; 10]) = (src as *const [T; 10]).read_unaligned() },
+        11 => unsafe { *(dst as *mut [T; 11]) = (src as *const [T; 11]).read_unaligned() },
+        12 => unsafe { *(dst as *mut [T; 12]) = (src as *const [T; 12]).read_unaligned() },
+        13 => unsafe { *(dst as *mut [T; 13]) = (src as *const [T; 13]).read_unaligned() },
+        14 => unsafe { *(dst as *mut [T; 14]) = (src as *const [T; 14]).read_unaligned() },
+        15 => unsafe { *(dst as *mut [T; 15]) = (src as *const [T; 15]).read_unaligned() },
+        16 => unsafe { *(dst as *mut [T; 16]) = (src as *const [T; 16]).read_unaligned() },
+        17 => unsafe { *(dst as *mut [T; 17]) = (src as *const [T; 17]).read_unaligned() },
+        18 => unsafe { *(dst as *mut [T; 18]) = (src as *const [T; 18]).read_unaligned() },
+        19 => unsafe { *(dst as *mut [T; 19]) = (src as *const [T; 19]).read_unaligned() },
+        20 => unsafe { *(dst as *mut [T; 20]) = (src as *const [T; 20]).read_unaligned() },
+        21 => unsafe { *(dst as *mut [T; 21]) = (src as *const [T; 21]).read_unaligned() },
+        22 => unsafe { *(dst as *mut [T; 22]) = (src as *const [T; 22]).read_unaligned() },
+        23 => unsafe { *(dst as *mut [T; 23]) = (src as *const [T; 23]).read_unaligned() },
+        24 => unsafe { *(dst as *mut [T; 24]) = (src as *const [T; 24]).read_unaligned() },
+        25 => unsafe { *(dst as *mut [T; 25]) = (src as *const [T; 25]).read_unaligned() },
+        26 => unsafe { *(dst as *mut [T; 26]) = (src as *const [T; 26]).read_unaligned() },
+        27 => unsafe { *(dst as *mut [T; 27]) = (src as *const [T; 27]).read_unaligned() },
+        28 => unsafe { *(dst as *mut [T; 28]) = (src as *const [T; 28]).read_unaligned() },
+        29 => unsafe { *(dst as *mut [T; 29]) = (src as *const [T; 29]).read_unaligned() },
+        30 => unsafe { *(dst as *mut [T; 30]) = (src as *const [T; 30]).read_unaligned() },
+        31 => unsafe { *(dst as *mut [T; 31]) = (src as *const [T; 31]).read_unaligned() },
+        32 => unsafe { *(dst as *mut [T; 32]) = (src as *const [T; 32]).read_unaligned() },
+        33 => unsafe { *(dst as *mut [T; 33]) = (src as *const [T; 33]).read_unaligned() },
+        34 => unsafe { *(dst as *mut [T; 34]) = (src as *const [T; 34]).read_unaligned() },
+        35 => unsafe { *(dst as *mut [T; 35]) = (src as *const [T; 35]).read_unaligned() },
+        36 => unsafe { *(dst as *mut [T; 36]) = (src as *const [T; 36]).read_unaligned() },
+        37 => unsafe { *(dst as *mut [T; 37]) = (src as *const [T; 37]).read_unaligned() },
+        38 => unsafe { *(dst as *mut [T; 38]) = (src as *const [T; 38]).read_unaligned() },
+        39 => unsafe { *(dst as *mut [T; 39]) = (src as *const [T; 39]).read_unaligned() },
+        40 => unsafe { *(dst as *mut [T; 40]) = (src as *const [T; 40]).read_unaligned() },
+        41 => unsafe { *(dst as *mut [T; 41]) = (src as *const [T; 41]).read_unaligned() },
+        42 => unsafe { *(dst as *mut [T; 42]) = (src as *const [T; 42]).read_unaligned() },
+        43 => unsafe { *(dst as *mut [T; 43]) = (src as *const [T; 43]).read_unaligned() },
+        44 => unsafe { *(dst as *mut [T; 44]) = (src as *const [T; 44]).read_unaligned() },
+        45 => unsafe { *(dst as *mut [T; 45]) = (src as *const [T; 45]).read_unaligned() },
+        46 => unsafe { *(dst as *mut [T; 46]) = (src as *const [T; 46]).read_unaligned() },
+        47 => unsafe { *(dst as *mut [T; 47]) = (src as *const [T; 47]).read_unaligned() },
+        48 => unsafe { *(dst as *mut [T; 48]) = (src as *const [T; 48]).read_unaligned() },
+        49 => unsafe { *(dst as *mut [T; 49]) = (src as *const [T; 49]).read_unaligned() },
+        50 => unsafe { *(dst as *mut [T; 50]) = (src as *const [T; 50]).read_unaligned() },
+        51 => unsafe { *(dst as *mut [T; 51]) = (src as *const [T; 51]).read_unaligned() },
+        52 => unsafe { *(dst as *mut [T; 52]) = (src as *const [T; 52]).read_unaligned() },
+        53 => unsafe { *(dst as *mut [T; 53]) = (src as *const [T; 53]).read_unaligned() },
+        54 => unsafe { *(dst as *mut [T; 54]) = (src as *const [T; 54]).read_unaligned() },
+        55 => unsafe { *(dst as *mut [T; 55]) = (src as *const [T; 55]).read_unaligned() },
+        56 => unsafe { *(dst as *mut [T; 56]) = (src as *const [T; 56]).read_unaligned() },
+        57 => unsafe { *(dst as *mut [T; 57]) = (src as *const [T; 57]).read_unaligned() },
+        58 => unsafe { *(dst as *mut [T; 58]) = (src as *const [T; 58]).read_unaligned() },
+        59 => unsafe { *(dst as *mut [T; 59]) = (src as *const [T; 59]).read_unaligned() },
+        60 => unsafe { *(dst as *mut [T; 60]) = (src as *const [T; 60]).read_unaligned() },
+        61 => unsafe { *(dst as *mut [T; 61]) = (src as *const [T; 61]).read_unaligned() },
+        62 => unsafe { *(dst as *mut [T; 62]) = (src as *const [T; 62]).read_unaligned() },
+        63 => unsafe { *(dst as *mut [T; 63]) = (src as *const [T; 63]).read_unaligned() },
+        64 => unsafe { *(dst as *mut [T; 64]) = (src as *const [T; 64]).read_unaligned() },
+        // `src` may be unaligned
+        _ => core::ptr::copy_nonoverlapping(
+            src as *const u8,
+            dst as *mut u8,
+            n * core::mem::size_of::<T>(),
+        ),
     }
 }
 
+/// Conversion applied to the elements of an operand while it is packed.
+pub trait Convert<Src, T>: Copy {
+    /// Converts one element.
+    fn convert(self, src: Src) -> T;
+
+    /// Converts the `N` consecutive elements at `src` and writes them to `dst`.
+    ///
+    /// # Safety
+    ///
+    /// `src` must be valid for unaligned reads of `N` elements, and `dst` for writes of `N`
+    /// elements.
+    #[inline(always)]
+    unsafe fn convert_n<const N: usize>(self, dst: *mut T, src: *const Src) {
+        for j in 0..N {
+            *dst.add(j) = self.convert(src.add(j).read_unaligned());
+        }
+    }
+
+    /// Converts the `n` consecutive elements at `src` and writes them to `dst`.
+    ///
+    /// # Safety
+    ///
+    /// `src` must be valid for unaligned reads of `n` elements, and `dst` for writes of `n`
+    /// elements.
+    #[inline(always)]
+    unsafe fn convert_slice(self, dst: *mut T, src: *const Src, n: usize) {
+        for j in 0..n {
+            *dst.add(j) = self.convert(src.add(j).read_unaligned());
+        }
+    }
+}
+
+/// Packs the elements as they are.
+#[derive(Copy, Clone, Debug)]
+pub struct Identity;
+
+impl<T: Copy> Convert<T, T> for Identity {
+    #[inline(always)]
+    fn convert(self, src: T) -> T {
+        src
+    }
+
+    #[inline(always)]
+    unsafe fn convert_n<const N: usize>(self, dst: *mut T, src: *const T) {
+        (dst as *mut [T; N]).write((src as *const [T; N]).read_unaligned());
+    }
+
+    #[inline(always)]
+    unsafe fn convert_slice(self, dst: *mut T, src: *const T, n: usize) {
+        quick_copy(dst, src, n);
+    }
+}
+
+// `src` may be unaligned, and its strides are in bytes
 #[inline(always)]
-unsafe fn pack_generic_inner_loop<T: Copy, const N: usize, const DST_WIDTH: usize>(
+unsafe fn pack_generic_inner_loop<
+    Src: Copy,
+    T: Copy + Zero,
+    C: Convert<Src, T>,
+    const N: usize,
+    const DST_WIDTH: usize,
+>(
+    convert: C,
     mut dst: *mut T,
-    mut src: *const T,
+    mut src: *const u8,
     src_rs: isize,
     src_cs: isize,
     src_width: usize,
     k: usize,
 ) {
+    let contiguous = src_rs == core::mem::size_of::<Src>() as isize;
+    let src_at = |src: *const u8, j: usize| src.offset(j as isize * src_rs) as *const Src;
+
     if src_width == DST_WIDTH {
-        if src_rs == 1 {
+        if contiguous {
             for _ in 0..k {
-                let val = (src as *const [T; DST_WIDTH]).read();
-                (dst as *mut [T; DST_WIDTH]).write(val);
+                convert.convert_n::<DST_WIDTH>(dst, src as *const Src);
 
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
@@ -168,17 +237,20 @@ unsafe fn pack_generic_inner_loop<T: Copy, const N: usize, const DST_WIDTH: usiz
         } else {
             for _ in 0..k {
                 for j in 0..DST_WIDTH {
-                    *dst.add(j) = *src.offset(j as isize * src_rs);
+                    *dst.add(j) = convert.convert(src_at(src, j).read_unaligned());
                 }
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
             }
         }
     } else if src_width == N {
-        if src_rs == 1 {
+        if contiguous {
             for _ in 0..k {
-                let val = (src as *const [T; N]).read();
-                (dst as *mut [T; N]).write(val);
+                convert.convert_n::<N>(dst, src as *const Src);
+                quick_zero::<T>(core::slice::from_raw_parts_mut(
+                    dst.add(N) as _,
+                    DST_WIDTH - N,
+                ));
 
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
@@ -186,19 +258,25 @@ unsafe fn pack_generic_inner_loop<T: Copy, const N: usize, const DST_WIDTH: usiz
         } else {
             for _ in 0..k {
                 for j in 0..N {
-                    *dst.add(j) = *src.offset(j as isize * src_rs);
+                    *dst.add(j) = convert.convert(src_at(src, j).read_unaligned());
                 }
+                quick_zero::<T>(core::slice::from_raw_parts_mut(
+                    dst.add(N) as _,
+                    DST_WIDTH - N,
+                ));
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
             }
         }
     } else if src_width == 2 * N {
-        if src_rs == 1 {
+        if contiguous {
             for _ in 0..k {
-                let val0 = (src as *const [T; N]).read();
-                let val1 = (src.add(N) as *const [T; N]).read();
-                (dst as *mut [T; N]).write(val0);
-                (dst.add(N) as *mut [T; N]).write(val1);
+                convert.convert_n::<N>(dst, src as *const Src);
+                convert.convert_n::<N>(dst.add(N), src_at(src, N));
+                quick_zero::<T>(core::slice::from_raw_parts_mut(
+                    dst.add(2 * N) as _,
+                    DST_WIDTH - 2 * N,
+                ));
 
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
@@ -206,16 +284,20 @@ unsafe fn pack_generic_inner_loop<T: Copy, const N: usize, const DST_WIDTH: usiz
         } else {
             for _ in 0..k {
                 for j in 0..2 * N {
-                    *dst.add(j) = *src.offset(j as isize * src_rs);
+                    *dst.add(j) = convert.convert(src_at(src, j).read_unaligned());
                 }
+                quick_zero::<T>(core::slice::from_raw_parts_mut(
+                    dst.add(2 * N) as _,
+                    DST_WIDTH - 2 * N,
+                ));
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
             }
         }
     } else {
-        if src_rs == 1 {
+        if contiguous {
             for _ in 0..k {
-                quick_copy(dst, src, src_width);
+                convert.convert_slice(dst, src as *const Src, src_width);
                 quick_zero::<T>(core::slice::from_raw_parts_mut(
                     dst.add(src_width) as _,
                     DST_WIDTH - src_width,
@@ -226,7 +308,7 @@ unsafe fn pack_generic_inner_loop<T: Copy, const N: usize, const DST_WIDTH: usiz
         } else {
             for _ in 0..k {
                 for j in 0..src_width {
-                    *dst.add(j) = *src.offset(j as isize * src_rs);
+                    *dst.add(j) = convert.convert(src_at(src, j).read_unaligned());
                 }
                 quick_zero::<T>(core::slice::from_raw_parts_mut(
                     dst.add(src_width) as _,
@@ -240,11 +322,19 @@ unsafe fn pack_generic_inner_loop<T: Copy, const N: usize, const DST_WIDTH: usiz
 }
 
 #[inline(always)]
-unsafe fn pack_generic<T: Copy, const N: usize, const DST_WIDTH: usize>(
+#[allow(clippy::too_many_arguments)]
+unsafe fn pack_generic<
+    Src: Copy,
+    T: Copy + Zero,
+    C: Convert<Src, T>,
+    const N: usize,
+    const DST_WIDTH: usize,
+>(
+    convert: C,
     m: usize,
     k: usize,
     mut dst: *mut T,
-    mut src: *const T,
+    mut src: *const u8,
     src_cs: isize,
     src_rs: isize,
     dst_stride: usize,
@@ -253,20 +343,38 @@ unsafe fn pack_generic<T: Copy, const N: usize, const DST_WIDTH: usize>(
 
     let mut i = 0;
     while i < m_width {
-        pack_generic_inner_loop::<_, N, DST_WIDTH>(dst, src, src_rs, src_cs, DST_WIDTH, k);
+        pack_generic_inner_loop::<Src, T, C, N, DST_WIDTH>(
+            convert, dst, src, src_rs, src_cs, DST_WIDTH, k,
+        );
         src = src.wrapping_offset(src_rs * DST_WIDTH as isize);
         dst = dst.add(dst_stride);
 
         i += DST_WIDTH;
     }
     if i < m {
-        pack_generic_inner_loop::<_, N, DST_WIDTH>(dst, src, src_rs, src_cs, m - i, k);
+        pack_generic_inner_loop::<Src, T, C, N, DST_WIDTH>(
+            convert,
+            dst,
+            src,
+            src_rs,
+            src_cs,
+            m - i,
+            k,
+        );
     }
 }
 
+/// Packs the `m × k` matrix `src` into panels of `MR` rows, each of `dst_stride` elements, with
+/// the rows of a panel padded to `MR` elements.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `m × k` elements with the given strides, and `dst` for writes
+/// of `m.div_ceil(MR)` panels of `dst_stride` elements.
 #[inline(never)]
-pub unsafe fn pack_lhs<T: Copy, const N: usize, const MR: usize, S: Simd>(
-    _: S,
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pack_lhs<T: Copy + Zero, const N: usize, const MR: usize, S: Simd>(
+    simd: S,
     m: usize,
     k: usize,
     dst: crate::Ptr<T>,
@@ -274,30 +382,228 @@ pub unsafe fn pack_lhs<T: Copy, const N: usize, const MR: usize, S: Simd>(
     src_cs: isize,
     src_rs: isize,
     dst_stride: usize,
+) {
+    let size = core::mem::size_of::<T>() as isize;
+    pack_lhs_bytes::<T, T, _, N, MR, S>(
+        simd,
+        Identity,
+        m,
+        k,
+        dst,
+        src,
+        src_cs * size,
+        src_rs * size,
+        dst_stride,
+    );
+}
+
+/// Packs the `k × n` matrix `src` into panels of `NR` columns, each of `dst_stride` elements, with
+/// the columns of a panel padded to `NR` elements.
+///
+/// # Safety
+///
+/// `src` must be valid for reads of `k × n` elements with the given strides, and `dst` for writes
+/// of `n.div_ceil(NR)` panels of `dst_stride` elements.
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pack_rhs<T: Copy + Zero, const N: usize, const NR: usize, S: Simd>(
+    simd: S,
+    n: usize,
+    k: usize,
+    dst: crate::Ptr<T>,
+    src: crate::Ptr<T>,
+    src_cs: isize,
+    src_rs: isize,
+    dst_stride: usize,
+) {
+    let size = core::mem::size_of::<T>() as isize;
+    pack_rhs_bytes::<T, T, _, N, NR, S>(
+        simd,
+        Identity,
+        n,
+        k,
+        dst,
+        src,
+        src_cs * size,
+        src_rs * size,
+        dst_stride,
+    );
+}
+
+/// Same as [`pack_lhs`], with the strides of `src` in bytes and each element converted by
+/// `convert`. `src` does not need to be aligned.
+///
+/// # Safety
+///
+/// Same as [`pack_lhs`].
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pack_lhs_bytes<
+    Src: Copy,
+    T: Copy + Zero,
+    C: Convert<Src, T>,
+    const N: usize,
+    const MR: usize,
+    S: Simd,
+>(
+    _: S,
+    convert: C,
+    m: usize,
+    k: usize,
+    dst: crate::Ptr<T>,
+    src: crate::Ptr<Src>,
+    src_cs: isize,
+    src_rs: isize,
+    dst_stride: usize,
 ) {
     let dst = dst.0;
-    let src = src.0;
+    let src = src.0 as *const u8;
     S::vectorize(
         #[inline(always)]
-        || pack_generic::<T, N, MR>(m, k, dst, src, src_cs, src_rs, dst_stride),
+        || pack_generic::<Src, T, C, N, MR>(convert, m, k, dst, src, src_cs, src_rs, dst_stride),
     );
 }
 
+/// Same as [`pack_rhs`], with the strides of `src` in bytes and each element converted by
+/// `convert`. `src` does not need to be aligned.
+///
+/// # Safety
+///
+/// Same as [`pack_rhs`].
 #[inline(never)]
-pub unsafe fn pack_rhs<T: Copy, const N: usize, const NR: usize, S: Simd>(
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pack_rhs_bytes<
+    Src: Copy,
+    T: Copy + Zero,
+    C: Convert<Src, T>,
+    const N: usize,
+    const NR: usize,
+    S: Simd,
+>(
     _: S,
+    convert: C,
     n: usize,
     k: usize,
     dst: crate::Ptr<T>,
+    src: crate::Ptr<Src>,
+    src_cs: isize,
+    src_rs: isize,
+    dst_stride: usize,
+) {
+    let dst = dst.0;
+    let src = src.0 as *const u8;
+    S::vectorize(
+        #[inline(always)]
+        || pack_generic::<Src, T, C, N, NR>(convert, n, k, dst, src, src_rs, src_cs, dst_stride),
+    );
+}
+
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+unsafe fn pack_with_sums<T: Copy + Into<i16>, const DST_WIDTH: usize>(
+    m: usize,
+    k: usize,
+    dst: *mut i32,
+    src: *const T,
+    src_cs: isize,
+    src_rs: isize,
+    dst_stride: usize,
+    sums: *mut i32,
+) {
+    let gather_sums = !sums.is_null();
+
+    let mut i = 0;
+    while i < m {
+        let src_width = DST_WIDTH.min(m - i);
+        let src = src.wrapping_offset(i as isize * src_rs);
+        let dst = dst.add(i / DST_WIDTH * dst_stride);
+        let sums = sums.wrapping_add(i);
+
+        for pair in 0..k.msrv_div_ceil(2) {
+            let depth = 2 * pair;
+            let src = src.wrapping_offset(depth as isize * src_cs);
+            let dst = dst.add(pair * DST_WIDTH);
+            for j in 0..src_width {
+                let lo: i16 = (*src.offset(j as isize * src_rs)).into();
+                let hi: i16 = if depth + 1 < k {
+                    (*src.offset(j as isize * src_rs + src_cs)).into()
+                } else {
+                    0
+                };
+                *dst.add(j) = (lo as u16 as u32 | ((hi as u16 as u32) << 16)) as i32;
+                if gather_sums {
+                    *sums.add(j) = (*sums.add(j)).wrapping_add(lo as i32 + hi as i32);
+                }
+            }
+            for j in src_width..DST_WIDTH {
+                *dst.add(j) = 0;
+            }
+        }
+
+        i += DST_WIDTH;
+    }
+}
+
+/// Same as [`pack_lhs`] for integer operands, with each pair of consecutive depth elements packed
+/// as two `i16` in an `i32`, the first one in the low half, and the last pair padded with zero if
+/// `k` is odd. Each panel holds `k.div_ceil(2) × MR` elements. The sum of each packed row of
+/// `src` is added to the corresponding element of `row_sums`, which has `m` elements, unless it is
+/// null.
+///
+/// The sums are used to apply the zero-point corrections of quantized products after the fact.
+///
+/// # Safety
+///
+/// Same as [`pack_lhs`], and `row_sums` must either be null or valid for reads and writes of `m`
+/// elements.
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pack_lhs_with_sums<T: Copy + Into<i16>, const MR: usize, S: Simd>(
+    _: S,
+    m: usize,
+    k: usize,
+    dst: crate::Ptr<i32>,
+    src: crate::Ptr<T>,
+    src_cs: isize,
+    src_rs: isize,
+    dst_stride: usize,
+    row_sums: crate::Ptr<i32>,
+) {
+    let dst = dst.0;
+    let src = src.0 as *const T;
+    let row_sums = row_sums.0;
+    S::vectorize(
+        #[inline(always)]
+        || pack_with_sums::<T, MR>(m, k, dst, src, src_cs, src_rs, dst_stride, row_sums),
+    );
+}
+
+/// Same as [`pack_lhs_with_sums`] for the right hand side, with the sum of each packed column of
+/// `src` added to the corresponding element of `col_sums`, which has `n` elements, unless it is
+/// null.
+///
+/// # Safety
+///
+/// Same as [`pack_rhs`], and `col_sums` must either be null or valid for reads and writes of `n`
+/// elements.
+#[inline(never)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn pack_rhs_with_sums<T: Copy + Into<i16>, const NR: usize, S: Simd>(
+    _: S,
+    n: usize,
+    k: usize,
+    dst: crate::Ptr<i32>,
     src: crate::Ptr<T>,
     src_cs: isize,
     src_rs: isize,
     dst_stride: usize,
+    col_sums: crate::Ptr<i32>,
 ) {
     let dst = dst.0;
-    let src = src.0;
+    let src = src.0 as *const T;
+    let col_sums = col_sums.0;
     S::vectorize(
         #[inline(always)]
-        || pack_generic::<T, N, NR>(n, k, dst, src, src_rs, src_cs, dst_stride),
+        || pack_with_sums::<T, NR>(n, k, dst, src, src_rs, src_cs, dst_stride, col_sums),
     );
 }
@@ -0,0 +1,310 @@
+//! Generic path through [`gemm_basic_generic`] for user-defined scalar types, such as modular
+//! integers, dual numbers or rationals, using a portable microkernel.
+
+use crate::{
+    gemm::{gemm_basic_generic, AllocError, Conj},
+    microkernel::{HMicroKernelFn, MicroKernelFn},
+    simd::{MixedSimd, NullaryFnOnce, Scalar},
+    Parallelism,
+};
+use core::ops::{Add, Mul};
+use num_traits::{One, Zero};
+
+/// Scalar types that [`gemm_basic`] can multiply.
+///
+/// The operations must form a commutative ring, up to the usual rounding for floating point
+/// types, since the products and sums may be evaluated in any order.
+///
+/// The packed operands are padded with [`Zero::zero`], which never contributes to the result.
+pub trait RingScalar:
+    Copy + Zero + One + Add<Output = Self> + Mul<Output = Self> + Send + Sync + 'static
+{
+}
+
+impl<T: Copy + Zero + One + Add<Output = T> + Mul<Output = T> + Send + Sync + 'static> RingScalar
+    for T
+{
+}
+
+/// Register block of the microkernel.
+const MR: usize = 2;
+const NR: usize = 4;
+
+// the driver requires a few traits that make no sense for a user type, which are provided by this
+// wrapper
+#[derive(Copy, Clone)]
+#[repr(transparent)]
+struct Ring<T>(T);
+
+// the driver only compares its scalars to skip the scaling by `alpha` when it is one, so no two
+// values are ever equal, which always takes the general path
+impl<T> PartialEq for Ring<T> {
+    #[inline(always)]
+    fn eq(&self, _: &Self) -> bool {
+        false
+    }
+}
+
+// `T` is not required to implement `Debug`, and the driver never formats its scalars, so the value
+// is not printed
+impl<T> core::fmt::Debug for Ring<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("Ring")
+    }
+}
+
+impl<T: RingScalar> Add for Ring<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn add(self, rhs: Self) -> Self {
+        Ring(self.0 + rhs.0)
+    }
+}
+
+impl<T: RingScalar> Mul for Ring<T> {
+    type Output = Self;
+
+    #[inline(always)]
+    fn mul(self, rhs: Self) -> Self {
+        Ring(self.0 * rhs.0)
+    }
+}
+
+impl<T: RingScalar> Zero for Ring<T> {
+    #[inline(always)]
+    fn zero() -> Self {
+        Ring(T::zero())
+    }
+
+    #[inline(always)]
+    fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl<T: RingScalar> One for Ring<T> {
+    #[inline(always)]
+    fn one() -> Self {
+        Ring(T::one())
+    }
+}
+
+impl<T: RingScalar> Conj for Ring<T> {
+    #[inline(always)]
+    fn conj(self) -> Self {
+        self
+    }
+}
+
+unsafe impl<T: RingScalar> MixedSimd<Ring<T>, Ring<T>, Ring<T>, Ring<T>> for Scalar {
+    const SIMD_WIDTH: usize = 1;
+
+    type LhsN = Ring<T>;
+    type RhsN = Ring<T>;
+    type DstN = Ring<T>;
+    type AccN = Ring<T>;
+
+    #[inline]
+    fn try_new() -> Option<Self> {
+        Some(Self)
+    }
+
+    #[inline(always)]
+    fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+        f.call()
+    }
+
+    #[inline(always)]
+    fn add(self, lhs: Ring<T>, rhs: Ring<T>) -> Ring<T> {
+        lhs + rhs
+    }
+
+    #[inline(always)]
+    fn mult(self, lhs: Ring<T>, rhs: Ring<T>) -> Ring<T> {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    fn mult_add(self, lhs: Ring<T>, rhs: Ring<T>, acc: Ring<T>) -> Ring<T> {
+        lhs * rhs + acc
+    }
+
+    #[inline(always)]
+    fn from_lhs(self, lhs: Ring<T>) -> Ring<T> {
+        lhs
+    }
+
+    #[inline(always)]
+    fn from_rhs(self, rhs: Ring<T>) -> Ring<T> {
+        rhs
+    }
+
+    #[inline(always)]
+    fn from_dst(self, dst: Ring<T>) -> Ring<T> {
+        dst
+    }
+
+    #[inline(always)]
+    fn into_dst(self, acc: Ring<T>) -> Ring<T> {
+        acc
+    }
+
+    #[inline(always)]
+    fn simd_mul(self, lhs: Ring<T>, rhs: Ring<T>) -> Ring<T> {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    fn simd_add(self, lhs: Ring<T>, rhs: Ring<T>) -> Ring<T> {
+        lhs + rhs
+    }
+
+    #[inline(always)]
+    fn simd_mult_add(self, lhs: Ring<T>, rhs: Ring<T>, acc: Ring<T>) -> Ring<T> {
+        lhs * rhs + acc
+    }
+
+    #[inline(always)]
+    fn simd_from_lhs(self, lhs: Ring<T>) -> Ring<T> {
+        lhs
+    }
+
+    #[inline(always)]
+    fn simd_from_rhs(self, rhs: Ring<T>) -> Ring<T> {
+        rhs
+    }
+
+    #[inline(always)]
+    fn simd_splat(self, lhs: Ring<T>) -> Ring<T> {
+        lhs
+    }
+
+    #[inline(always)]
+    fn simd_from_dst(self, dst: Ring<T>) -> Ring<T> {
+        dst
+    }
+
+    #[inline(always)]
+    fn simd_into_dst(self, acc: Ring<T>) -> Ring<T> {
+        acc
+    }
+}
+
+// the kernels of `microkernel!`, generic over the scalar type, which is a `Ring<T>` in practice
+#[allow(clippy::too_many_arguments)]
+mod kernels {
+    use super::RingScalar;
+
+    const N: usize = 1;
+    type Pack<T> = [T; N];
+
+    #[inline(always)]
+    unsafe fn splat<T: RingScalar>(value: T) -> Pack<T> {
+        [value]
+    }
+
+    #[inline(always)]
+    unsafe fn mul<T: RingScalar>(lhs: Pack<T>, rhs: Pack<T>) -> Pack<T> {
+        [lhs[0] * rhs[0]]
+    }
+
+    #[inline(always)]
+    unsafe fn add<T: RingScalar>(lhs: Pack<T>, rhs: Pack<T>) -> Pack<T> {
+        [lhs[0] + rhs[0]]
+    }
+
+    #[inline(always)]
+    unsafe fn mul_add<T: RingScalar>(a: Pack<T>, b: Pack<T>, c: Pack<T>) -> Pack<T> {
+        add(mul(a, b), c)
+    }
+
+    #[inline(always)]
+    unsafe fn scalar_mul<T: RingScalar>(lhs: T, rhs: T) -> T {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    unsafe fn scalar_add<T: RingScalar>(lhs: T, rhs: T) -> T {
+        lhs + rhs
+    }
+
+    #[inline(always)]
+    unsafe fn scalar_mul_add<T: RingScalar>(a: T, b: T, c: T) -> T {
+        a * b + c
+    }
+
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x1x1, 1, 1);
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x1x2, 1, 2);
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x1x3, 1, 3);
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x1x4, 1, 4);
+
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x2x1, 2, 1);
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x2x2, 2, 2);
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x2x3, 2, 3);
+    crate::microkernel!(generic: <T: RingScalar>; zero: T::zero(); dot: mul_add; , 2, x2x4, 2, 4);
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as the `gemm_basic` functions of the type-specific crates, for any [`RingScalar`]. The
+/// conjugation flags are ignored.
+///
+/// # Safety
+///
+/// The matrices must be valid for the given dimensions and strides, and `dst` must not overlap
+/// `lhs` or `rhs`.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_basic<T: RingScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    _conj_dst: bool,
+    _conj_lhs: bool,
+    _conj_rhs: bool,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    use kernels::*;
+    let ukr: [[MicroKernelFn<Ring<T>>; NR]; MR] =
+        [[x1x1, x1x2, x1x3, x1x4], [x2x1, x2x2, x2x3, x2x4]];
+    let h_ukr: [[HMicroKernelFn<Ring<T>>; 0]; 0] = [];
+
+    gemm_basic_generic::<_, Ring<T>, 1, MR, NR, MR, 0, 0>(
+        Scalar,
+        m,
+        n,
+        k,
+        dst as *mut Ring<T>,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs as *const Ring<T>,
+        lhs_cs,
+        lhs_rs,
+        rhs as *const Ring<T>,
+        rhs_cs,
+        rhs_rs,
+        Ring(alpha),
+        Ring(beta),
+        false,
+        false,
+        false,
+        |a, b, c| a * b + c,
+        &ukr,
+        &h_ukr,
+        false,
+        parallelism,
+    )
+}
@@ -1,4 +1,6 @@
 pub use bytemuck::Pod;
+#[cfg(feature = "bf16")]
+use half::bf16;
 #[cfg(feature = "f16")]
 use half::f16;
 pub use pulp::{cast, NullaryFnOnce};
@@ -9,7 +11,11 @@ pub use x86::*;
 #[cfg(target_arch = "aarch64")]
 pub use aarch64::*;
 
-use crate::gemm::{c32, c64};
+use crate::{
+    f8::{f8e4m3, f8e5m2},
+    gemm::{c32, c64},
+    tropical::{MaxPlus, MinPlus},
+};
 
 pub trait Simd: Copy + Send + Sync + 'static {
     unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output;
@@ -120,6 +126,196 @@ unsafe impl MixedSimd<f16, f16, f16, f32> for Scalar {
     }
 }
 
+#[cfg(feature = "f16")]
+unsafe impl MixedSimd<f16, f16, f32, f32> for Scalar {
+    const SIMD_WIDTH: usize = 1;
+
+    type LhsN = f16;
+    type RhsN = f16;
+    type DstN = f32;
+    type AccN = f32;
+
+    #[inline]
+    fn try_new() -> Option<Self> {
+        Some(Self)
+    }
+
+    #[inline(always)]
+    fn add(self, lhs: f32, rhs: f32) -> f32 {
+        lhs + rhs
+    }
+
+    #[inline(always)]
+    fn mult(self, lhs: f32, rhs: f32) -> f32 {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+        lhs * rhs + acc
+    }
+
+    #[inline(always)]
+    fn from_lhs(self, lhs: f16) -> f32 {
+        lhs.into()
+    }
+
+    #[inline(always)]
+    fn from_rhs(self, rhs: f16) -> f32 {
+        rhs.into()
+    }
+
+    #[inline(always)]
+    fn from_dst(self, dst: f32) -> f32 {
+        dst
+    }
+
+    #[inline(always)]
+    fn into_dst(self, acc: f32) -> f32 {
+        acc
+    }
+
+    #[inline(always)]
+    fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+        lhs * rhs + acc
+    }
+
+    #[inline(always)]
+    fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+        lhs.into()
+    }
+
+    #[inline(always)]
+    fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+        rhs.into()
+    }
+
+    #[inline(always)]
+    fn simd_splat(self, lhs: f32) -> Self::AccN {
+        lhs
+    }
+
+    #[inline(always)]
+    fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+        dst
+    }
+
+    #[inline(always)]
+    fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+        acc
+    }
+
+    #[inline(always)]
+    fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+        f.call()
+    }
+
+    #[inline(always)]
+    fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+        lhs + rhs
+    }
+}
+
+#[cfg(feature = "bf16")]
+unsafe impl MixedSimd<bf16, bf16, bf16, f32> for Scalar {
+    const SIMD_WIDTH: usize = 1;
+
+    type LhsN = bf16;
+    type RhsN = bf16;
+    type DstN = bf16;
+    type AccN = f32;
+
+    #[inline]
+    fn try_new() -> Option<Self> {
+        Some(Self)
+    }
+
+    #[inline(always)]
+    fn add(self, lhs: f32, rhs: f32) -> f32 {
+        lhs + rhs
+    }
+
+    #[inline(always)]
+    fn mult(self, lhs: f32, rhs: f32) -> f32 {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+        lhs * rhs + acc
+    }
+
+    #[inline(always)]
+    fn from_lhs(self, lhs: bf16) -> f32 {
+        lhs.into()
+    }
+
+    #[inline(always)]
+    fn from_rhs(self, rhs: bf16) -> f32 {
+        rhs.into()
+    }
+
+    #[inline(always)]
+    fn from_dst(self, dst: bf16) -> f32 {
+        dst.into()
+    }
+
+    #[inline(always)]
+    fn into_dst(self, acc: f32) -> bf16 {
+        bf16::from_f32(acc)
+    }
+
+    #[inline(always)]
+    fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+        lhs * rhs + acc
+    }
+
+    #[inline(always)]
+    fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+        lhs.into()
+    }
+
+    #[inline(always)]
+    fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+        rhs.into()
+    }
+
+    #[inline(always)]
+    fn simd_splat(self, lhs: f32) -> Self::AccN {
+        lhs
+    }
+
+    #[inline(always)]
+    fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+        dst.into()
+    }
+
+    #[inline(always)]
+    fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+        bf16::from_f32(acc)
+    }
+
+    #[inline(always)]
+    fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+        f.call()
+    }
+
+    #[inline(always)]
+    fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+        lhs * rhs
+    }
+
+    #[inline(always)]
+    fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+        lhs + rhs
+    }
+}
+
 unsafe impl MixedSimd<f32, f32, f32, f32> for Scalar {
     const SIMD_WIDTH: usize = 1;
 
@@ -496,85 +692,611 @@ unsafe impl MixedSimd<c64, c64, c64, c64> for Scalar {
     }
 }
 
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-mod x86 {
-    use super::*;
-    #[cfg(target_arch = "x86")]
-    use core::arch::x86::*;
-    #[cfg(target_arch = "x86_64")]
-    use core::arch::x86_64::*;
+/// Conversion between an operand or destination type and the wider accumulator type.
+trait Widen<Acc>: Copy {
+    fn widen(self) -> Acc;
+    fn narrow(acc: Acc) -> Self;
+}
 
+impl Widen<f64> for f32 {
     #[inline(always)]
-    pub unsafe fn v3_fmaf(a: f32, b: f32, c: f32) -> f32 {
-        #[cfg(feature = "std")]
-        {
-            f32::mul_add(a, b, c)
-        }
-        #[cfg(not(feature = "std"))]
-        {
-            libm::fmaf(a, b, c)
-        }
+    fn widen(self) -> f64 {
+        self as f64
     }
 
     #[inline(always)]
-    pub unsafe fn v3_fma(a: f64, b: f64, c: f64) -> f64 {
-        #[cfg(feature = "std")]
-        {
-            f64::mul_add(a, b, c)
-        }
-        #[cfg(not(feature = "std"))]
-        {
-            libm::fma(a, b, c)
-        }
+    fn narrow(acc: f64) -> Self {
+        acc as f32
     }
+}
 
-    #[derive(Copy, Clone)]
-    pub struct Sse;
-    #[derive(Copy, Clone)]
-    pub struct Avx;
-    #[derive(Copy, Clone)]
-    pub struct Fma;
+impl Widen<f64> for f64 {
+    #[inline(always)]
+    fn widen(self) -> f64 {
+        self
+    }
 
-    #[cfg(feature = "nightly")]
-    #[derive(Copy, Clone)]
-    pub struct Avx512f;
+    #[inline(always)]
+    fn narrow(acc: f64) -> Self {
+        acc
+    }
+}
 
-    impl Simd for Sse {
-        #[inline]
-        #[target_feature(enable = "sse,sse2")]
-        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
-            f.call()
-        }
+impl Widen<i32> for i8 {
+    #[inline(always)]
+    fn widen(self) -> i32 {
+        self as i32
     }
 
-    impl Simd for Avx {
-        #[inline]
-        #[target_feature(enable = "avx")]
-        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
-            f.call()
-        }
+    #[inline(always)]
+    fn narrow(acc: i32) -> Self {
+        acc as i8
     }
+}
 
-    impl Simd for Fma {
-        #[inline]
-        #[target_feature(enable = "fma")]
-        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
-            f.call()
-        }
+impl Widen<i32> for i32 {
+    #[inline(always)]
+    fn widen(self) -> i32 {
+        self
     }
 
-    #[cfg(feature = "nightly")]
-    impl Simd for Avx512f {
-        #[inline]
-        #[target_feature(enable = "avx512f")]
-        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
-            f.call()
-        }
+    #[inline(always)]
+    fn narrow(acc: i32) -> Self {
+        acc
     }
+}
 
-    #[derive(Debug, Copy, Clone)]
-    pub struct V3Half {
-        __private: (),
+impl Widen<c64> for c32 {
+    #[inline(always)]
+    fn widen(self) -> c64 {
+        c64::new(self.re as f64, self.im as f64)
+    }
+
+    #[inline(always)]
+    fn narrow(acc: c64) -> Self {
+        c32::new(acc.re as f32, acc.im as f32)
+    }
+}
+
+impl Widen<c64> for c64 {
+    #[inline(always)]
+    fn widen(self) -> c64 {
+        self
+    }
+
+    #[inline(always)]
+    fn narrow(acc: c64) -> Self {
+        acc
+    }
+}
+
+impl Widen<f32> for f32 {
+    #[inline(always)]
+    fn widen(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn narrow(acc: f32) -> Self {
+        acc
+    }
+}
+
+impl Widen<f32> for f8e4m3 {
+    #[inline(always)]
+    fn widen(self) -> f32 {
+        self.to_f32()
+    }
+
+    #[inline(always)]
+    fn narrow(acc: f32) -> Self {
+        f8e4m3::from_f32(acc)
+    }
+}
+
+impl Widen<f32> for f8e5m2 {
+    #[inline(always)]
+    fn widen(self) -> f32 {
+        self.to_f32()
+    }
+
+    #[inline(always)]
+    fn narrow(acc: f32) -> Self {
+        f8e5m2::from_f32(acc)
+    }
+}
+
+#[cfg(feature = "f16")]
+impl Widen<f32> for f16 {
+    #[inline(always)]
+    fn widen(self) -> f32 {
+        self.to_f32()
+    }
+
+    #[inline(always)]
+    fn narrow(acc: f32) -> Self {
+        f16::from_f32(acc)
+    }
+}
+
+#[cfg(feature = "f16")]
+impl Widen<f64> for f16 {
+    #[inline(always)]
+    fn widen(self) -> f64 {
+        self.to_f64()
+    }
+
+    #[inline(always)]
+    fn narrow(acc: f64) -> Self {
+        // `f16::from_f64` does not always round to nearest. rounding to odd when converting to
+        // `f32` keeps enough bits for the second rounding, to `f16`, to be correct
+        let narrow = acc as f32;
+        let bits = narrow.to_bits();
+        let narrow = if narrow as f64 == acc || !narrow.is_finite() || bits & 1 == 1 {
+            narrow
+        } else if (narrow as f64).abs() > acc.abs() {
+            f32::from_bits(bits - 1)
+        } else {
+            f32::from_bits(bits + 1)
+        };
+        f16::from_f32(narrow)
+    }
+}
+
+macro_rules! impl_widen_identity {
+    ($($ty: ty),* $(,)?) => {
+        $(
+            impl Widen<$ty> for $ty {
+                #[inline(always)]
+                fn widen(self) -> $ty {
+                    self
+                }
+
+                #[inline(always)]
+                fn narrow(acc: $ty) -> Self {
+                    acc
+                }
+            }
+        )*
+    };
+}
+
+impl_widen_identity!(MinPlus<f32>, MinPlus<f64>, MaxPlus<f32>, MaxPlus<f64>);
+
+// lanewise implementation, the conversions and arithmetic are left to the autovectorizer inside of
+// `vectorize`
+macro_rules! impl_widening_simd {
+    ($simd: ty, $width: expr, $lhs: ty, $dst: ty, $acc: ty, $try_new: expr) => {
+        impl_widening_simd!($simd, $width, $lhs, $lhs, $dst, $acc, $try_new);
+    };
+    ($simd: ty, $width: expr, $lhs: ty, $rhs: ty, $dst: ty, $acc: ty, $try_new: expr) => {
+        unsafe impl MixedSimd<$lhs, $rhs, $dst, $acc> for $simd {
+            const SIMD_WIDTH: usize = $width;
+
+            type LhsN = [$lhs; $width];
+            type RhsN = [$rhs; $width];
+            type DstN = [$dst; $width];
+            type AccN = [$acc; $width];
+
+            #[inline]
+            fn try_new() -> Option<Self> {
+                $try_new
+            }
+
+            #[inline(always)]
+            fn mult(self, lhs: $acc, rhs: $acc) -> $acc {
+                lhs * rhs
+            }
+
+            #[inline(always)]
+            fn mult_add(self, lhs: $acc, rhs: $acc, acc: $acc) -> $acc {
+                lhs * rhs + acc
+            }
+
+            #[inline(always)]
+            fn from_lhs(self, lhs: $lhs) -> $acc {
+                lhs.widen()
+            }
+
+            #[inline(always)]
+            fn from_rhs(self, rhs: $rhs) -> $acc {
+                rhs.widen()
+            }
+
+            #[inline(always)]
+            fn from_dst(self, dst: $dst) -> $acc {
+                dst.widen()
+            }
+
+            #[inline(always)]
+            fn into_dst(self, acc: $acc) -> $dst {
+                <$dst>::narrow(acc)
+            }
+
+            #[inline(always)]
+            fn simd_mult_add(
+                self,
+                lhs: Self::AccN,
+                rhs: Self::AccN,
+                acc: Self::AccN,
+            ) -> Self::AccN {
+                core::array::from_fn(|i| lhs[i] * rhs[i] + acc[i])
+            }
+
+            #[inline(always)]
+            fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+                lhs.map(Widen::widen)
+            }
+
+            #[inline(always)]
+            fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+                rhs.map(Widen::widen)
+            }
+
+            #[inline(always)]
+            fn simd_splat(self, lhs: $acc) -> Self::AccN {
+                [lhs; $width]
+            }
+
+            #[inline(always)]
+            fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+                dst.map(Widen::widen)
+            }
+
+            #[inline(always)]
+            fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+                acc.map(<$dst>::narrow)
+            }
+
+            #[inline(always)]
+            fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+                // SAFETY: `self` can only be created if the instruction set is available
+                unsafe { <Self as Simd>::vectorize(f) }
+            }
+
+            #[inline(always)]
+            fn add(self, lhs: $acc, rhs: $acc) -> $acc {
+                lhs + rhs
+            }
+
+            #[inline(always)]
+            fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+                core::array::from_fn(|i| lhs[i] * rhs[i])
+            }
+
+            #[inline(always)]
+            fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+                core::array::from_fn(|i| lhs[i] + rhs[i])
+            }
+        }
+    };
+}
+
+// the combinations of `f16`, `f32` and `f64` operands and destination that are not covered by
+// another implementation, accumulated in `f64` if any of the three types is `f64` and in `f32`
+// otherwise
+macro_rules! impl_mixed_float_simd {
+    ($simd: ty, $f32_width: expr, $f64_width: expr, $try_new: expr) => {
+        impl_widening_simd!($simd, $f64_width, f64, f32, f64, f64, $try_new);
+        impl_widening_simd!($simd, $f64_width, f32, f64, f64, f64, $try_new);
+        impl_widening_simd!($simd, $f64_width, f64, f64, f32, f64, $try_new);
+        impl_widening_simd!($simd, $f64_width, f64, f32, f32, f64, $try_new);
+        impl_widening_simd!($simd, $f64_width, f32, f64, f32, f64, $try_new);
+
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f32_width, f32, f16, f32, f32, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f32_width, f16, f32, f32, f32, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f32_width, f32, f32, f16, f32, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f32_width, f32, f16, f16, f32, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f32_width, f16, f32, f16, f32, $try_new);
+
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f16, f16, f64, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f16, f64, f16, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f64, f16, f16, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f16, f64, f64, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f64, f16, f64, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f64, f64, f16, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f16, f32, f64, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f16, f64, f32, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f32, f16, f64, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f32, f64, f16, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f64, f16, f32, f64, $try_new);
+        #[cfg(feature = "f16")]
+        impl_widening_simd!($simd, $f64_width, f64, f32, f16, f64, $try_new);
+    };
+}
+
+// the FP8 products: any combination of `f8e4m3` and `f8e5m2` operands, into an `f32`, `f8e4m3` or
+// `f8e5m2` destination, accumulated in `f32`
+macro_rules! impl_fp8_simd {
+    ($simd: ty, $width: expr, $try_new: expr) => {
+        impl_fp8_simd!(@dst $simd, $width, f32, $try_new);
+        impl_fp8_simd!(@dst $simd, $width, f8e4m3, $try_new);
+        impl_fp8_simd!(@dst $simd, $width, f8e5m2, $try_new);
+    };
+    (@dst $simd: ty, $width: expr, $dst: ty, $try_new: expr) => {
+        impl_widening_simd!($simd, $width, f8e4m3, f8e4m3, $dst, f32, $try_new);
+        impl_widening_simd!($simd, $width, f8e4m3, f8e5m2, $dst, f32, $try_new);
+        impl_widening_simd!($simd, $width, f8e5m2, f8e4m3, $dst, f32, $try_new);
+        impl_widening_simd!($simd, $width, f8e5m2, f8e5m2, $dst, f32, $try_new);
+    };
+}
+
+impl_widening_simd!(Scalar, 1, f32, f32, f64, Some(Self));
+impl_widening_simd!(Scalar, 1, f32, f64, f64, Some(Self));
+impl_widening_simd!(Scalar, 1, c32, c32, c64, Some(Self));
+impl_widening_simd!(Scalar, 1, c32, c64, c64, Some(Self));
+impl_widening_simd!(Scalar, 1, i8, i32, i32, Some(Self));
+impl_fp8_simd!(Scalar, 1, Some(Self));
+impl_mixed_float_simd!(Scalar, 1, 1, Some(Self));
+impl_widening_simd!(
+    Scalar,
+    1,
+    MinPlus<f32>,
+    MinPlus<f32>,
+    MinPlus<f32>,
+    Some(Self)
+);
+impl_widening_simd!(
+    Scalar,
+    1,
+    MinPlus<f64>,
+    MinPlus<f64>,
+    MinPlus<f64>,
+    Some(Self)
+);
+impl_widening_simd!(
+    Scalar,
+    1,
+    MaxPlus<f32>,
+    MaxPlus<f32>,
+    MaxPlus<f32>,
+    Some(Self)
+);
+impl_widening_simd!(
+    Scalar,
+    1,
+    MaxPlus<f64>,
+    MaxPlus<f64>,
+    MaxPlus<f64>,
+    Some(Self)
+);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(V3, 4, f32, f32, f64, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(V3, 4, f32, f64, f64, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(V3, 2, c32, c32, c64, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(V3, 2, c32, c64, c64, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(V3, 8, i8, i32, i32, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_fp8_simd!(V3, 8, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_mixed_float_simd!(V3, 8, 4, V3::try_new());
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(
+    V3,
+    8,
+    MinPlus<f32>,
+    MinPlus<f32>,
+    MinPlus<f32>,
+    V3::try_new()
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(
+    V3,
+    4,
+    MinPlus<f64>,
+    MinPlus<f64>,
+    MinPlus<f64>,
+    V3::try_new()
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(
+    V3,
+    8,
+    MaxPlus<f32>,
+    MaxPlus<f32>,
+    MaxPlus<f32>,
+    V3::try_new()
+);
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+impl_widening_simd!(
+    V3,
+    4,
+    MaxPlus<f64>,
+    MaxPlus<f64>,
+    MaxPlus<f64>,
+    V3::try_new()
+);
+
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(V4, 8, f32, f32, f64, V4::try_new());
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(V4, 8, f32, f64, f64, V4::try_new());
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(V4, 4, c32, c32, c64, V4::try_new());
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(V4, 4, c32, c64, c64, V4::try_new());
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_fp8_simd!(V4, 16, V4::try_new());
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_mixed_float_simd!(V4, 16, 8, V4::try_new());
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(
+    V4,
+    16,
+    MinPlus<f32>,
+    MinPlus<f32>,
+    MinPlus<f32>,
+    V4::try_new()
+);
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(
+    V4,
+    8,
+    MinPlus<f64>,
+    MinPlus<f64>,
+    MinPlus<f64>,
+    V4::try_new()
+);
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(
+    V4,
+    16,
+    MaxPlus<f32>,
+    MaxPlus<f32>,
+    MaxPlus<f32>,
+    V4::try_new()
+);
+#[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+impl_widening_simd!(
+    V4,
+    8,
+    MaxPlus<f64>,
+    MaxPlus<f64>,
+    MaxPlus<f64>,
+    V4::try_new()
+);
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+mod x86 {
+    use super::*;
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+    // `core::arch` also exports a `bf16` type
+    #[cfg(feature = "bf16")]
+    use half::bf16;
+
+    #[inline(always)]
+    pub unsafe fn v3_fmaf(a: f32, b: f32, c: f32) -> f32 {
+        #[cfg(feature = "std")]
+        {
+            f32::mul_add(a, b, c)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            libm::fmaf(a, b, c)
+        }
+    }
+
+    #[cfg(feature = "bf16")]
+    #[inline(always)]
+    unsafe fn bf16x8_to_f32x8(x: __m128i) -> __m256 {
+        _mm256_castsi256_ps(_mm256_slli_epi32::<16>(_mm256_cvtepu16_epi32(x)))
+    }
+
+    // rounds to nearest, ties to even, and keeps nans quiet, like `bf16::from_f32`
+    #[cfg(feature = "bf16")]
+    #[inline(always)]
+    unsafe fn f32x8_to_bf16x8(x: __m256) -> __m128i {
+        let bits = _mm256_castps_si256(x);
+        let lsb = _mm256_and_si256(_mm256_srli_epi32::<16>(bits), _mm256_set1_epi32(1));
+        let rounded = _mm256_add_epi32(bits, _mm256_add_epi32(lsb, _mm256_set1_epi32(0x7fff)));
+        let quiet_nan = _mm256_or_si256(bits, _mm256_set1_epi32(0x0040_0000));
+        let is_nan = _mm256_castps_si256(_mm256_cmp_ps::<_CMP_UNORD_Q>(x, x));
+        let bits = _mm256_srli_epi32::<16>(_mm256_blendv_epi8(rounded, quiet_nan, is_nan));
+        let packed = _mm256_packus_epi32(bits, bits);
+        _mm256_castsi256_si128(_mm256_permute4x64_epi64::<0b1000>(packed))
+    }
+
+    #[cfg(all(feature = "nightly", feature = "bf16"))]
+    #[inline(always)]
+    unsafe fn bf16x16_to_f32x16(x: __m256i) -> __m512 {
+        _mm512_castsi512_ps(_mm512_slli_epi32::<16>(_mm512_cvtepu16_epi32(x)))
+    }
+
+    #[cfg(all(feature = "nightly", feature = "bf16"))]
+    #[inline(always)]
+    unsafe fn f32x16_to_bf16x16(x: __m512) -> __m256i {
+        let bits = _mm512_castps_si512(x);
+        let lsb = _mm512_and_si512(_mm512_srli_epi32::<16>(bits), _mm512_set1_epi32(1));
+        let rounded = _mm512_add_epi32(bits, _mm512_add_epi32(lsb, _mm512_set1_epi32(0x7fff)));
+        let quiet_nan = _mm512_or_si512(bits, _mm512_set1_epi32(0x0040_0000));
+        let is_nan = _mm512_cmp_ps_mask::<_CMP_UNORD_Q>(x, x);
+        let bits = _mm512_mask_blend_epi32(is_nan, rounded, quiet_nan);
+        _mm512_cvtepi32_epi16(_mm512_srli_epi32::<16>(bits))
+    }
+
+    #[inline(always)]
+    pub unsafe fn v3_fma(a: f64, b: f64, c: f64) -> f64 {
+        #[cfg(feature = "std")]
+        {
+            f64::mul_add(a, b, c)
+        }
+        #[cfg(not(feature = "std"))]
+        {
+            libm::fma(a, b, c)
+        }
+    }
+
+    #[derive(Copy, Clone)]
+    pub struct Sse;
+    #[derive(Copy, Clone)]
+    pub struct Avx;
+    #[derive(Copy, Clone)]
+    pub struct Fma;
+
+    #[cfg(feature = "nightly")]
+    #[derive(Copy, Clone)]
+    pub struct Avx512f;
+
+    impl Simd for Sse {
+        #[inline]
+        #[target_feature(enable = "sse,sse2")]
+        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
+            f.call()
+        }
+    }
+
+    impl Simd for Avx {
+        #[inline]
+        #[target_feature(enable = "avx")]
+        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
+            f.call()
+        }
+    }
+
+    impl Simd for Fma {
+        #[inline]
+        #[target_feature(enable = "fma")]
+        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
+            f.call()
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    impl Simd for Avx512f {
+        #[inline]
+        #[target_feature(enable = "avx512f")]
+        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
+            f.call()
+        }
+    }
+
+    #[derive(Debug, Copy, Clone)]
+    pub struct V3Half {
+        __private: (),
     }
 
     #[cfg(feature = "f16")]
@@ -728,33 +1450,227 @@ mod x86 {
         }
 
         #[inline(always)]
-        fn vectorize<F: NullaryFnOnce>(self, _f: F) -> F::Output {
-            todo!()
+        fn vectorize<F: NullaryFnOnce>(self, _f: F) -> F::Output {
+            todo!()
+        }
+
+        #[inline(always)]
+        fn add(self, _lhs: f32, _rhs: f32) -> f32 {
+            todo!()
+        }
+
+        #[inline(always)]
+        fn simd_mul(self, _lhs: Self::AccN, _rhs: Self::AccN) -> Self::AccN {
+            todo!()
+        }
+
+        #[inline(always)]
+        fn simd_add(self, _lhs: Self::AccN, _rhs: Self::AccN) -> Self::AccN {
+            todo!()
+        }
+    }
+
+    #[cfg(feature = "f16")]
+    unsafe impl MixedSimd<f16, f16, f16, f32> for V3 {
+        const SIMD_WIDTH: usize = 8;
+
+        type LhsN = [f16; 8];
+        type RhsN = [f16; 8];
+        type DstN = [f16; 8];
+        type AccN = [f32; 8];
+
+        #[inline]
+        fn try_new() -> Option<Self> {
+            Self::try_new()
+        }
+
+        #[inline(always)]
+        fn mult(self, lhs: f32, rhs: f32) -> f32 {
+            lhs * rhs
+        }
+
+        #[inline(always)]
+        fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+            unsafe { v3_fmaf(lhs, rhs, acc) }
+        }
+
+        #[inline(always)]
+        fn from_lhs(self, lhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(lhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_rhs(self, rhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(rhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_dst(self, dst: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(dst)))) }
+        }
+
+        #[inline(always)]
+        fn into_dst(self, acc: f32) -> f16 {
+            unsafe {
+                pulp::cast_lossy(_mm_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(
+                    self.sse._mm_load_ss(&acc),
+                ))
+            }
+        }
+
+        #[inline(always)]
+        fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+            cast(self.fma._mm256_fmadd_ps(cast(lhs), cast(rhs), cast(acc)))
+        }
+
+        #[inline(always)]
+        fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+            unsafe { cast(_mm256_cvtph_ps(cast(lhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+            unsafe { cast(_mm256_cvtph_ps(cast(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_splat(self, lhs: f32) -> Self::AccN {
+            cast(self.avx._mm256_set1_ps(lhs))
+        }
+
+        #[inline(always)]
+        fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+            unsafe { cast(_mm256_cvtph_ps(cast(dst))) }
+        }
+
+        #[inline(always)]
+        fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+            unsafe { cast(_mm256_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(cast(acc))) }
+        }
+
+        #[inline(always)]
+        fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+            self.vectorize(f)
+        }
+
+        #[inline(always)]
+        fn add(self, lhs: f32, rhs: f32) -> f32 {
+            lhs + rhs
+        }
+
+        #[inline(always)]
+        fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx._mm256_mul_ps(cast(lhs), cast(rhs)))
+        }
+
+        #[inline(always)]
+        fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx._mm256_add_ps(cast(lhs), cast(rhs)))
+        }
+    }
+
+    #[cfg(feature = "f16")]
+    unsafe impl MixedSimd<f16, f16, f32, f32> for V3 {
+        const SIMD_WIDTH: usize = 8;
+
+        type LhsN = [f16; 8];
+        type RhsN = [f16; 8];
+        type DstN = [f32; 8];
+        type AccN = [f32; 8];
+
+        #[inline]
+        fn try_new() -> Option<Self> {
+            Self::try_new()
+        }
+
+        #[inline(always)]
+        fn mult(self, lhs: f32, rhs: f32) -> f32 {
+            lhs * rhs
+        }
+
+        #[inline(always)]
+        fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+            unsafe { v3_fmaf(lhs, rhs, acc) }
+        }
+
+        #[inline(always)]
+        fn from_lhs(self, lhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(lhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_rhs(self, rhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(rhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_dst(self, dst: f32) -> f32 {
+            dst
+        }
+
+        #[inline(always)]
+        fn into_dst(self, acc: f32) -> f32 {
+            acc
+        }
+
+        #[inline(always)]
+        fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+            cast(self.fma._mm256_fmadd_ps(cast(lhs), cast(rhs), cast(acc)))
+        }
+
+        #[inline(always)]
+        fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+            unsafe { cast(_mm256_cvtph_ps(cast(lhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+            unsafe { cast(_mm256_cvtph_ps(cast(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_splat(self, lhs: f32) -> Self::AccN {
+            cast(self.avx._mm256_set1_ps(lhs))
+        }
+
+        #[inline(always)]
+        fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+            dst
+        }
+
+        #[inline(always)]
+        fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+            acc
+        }
+
+        #[inline(always)]
+        fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+            self.vectorize(f)
         }
 
         #[inline(always)]
-        fn add(self, _lhs: f32, _rhs: f32) -> f32 {
-            todo!()
+        fn add(self, lhs: f32, rhs: f32) -> f32 {
+            lhs + rhs
         }
 
         #[inline(always)]
-        fn simd_mul(self, _lhs: Self::AccN, _rhs: Self::AccN) -> Self::AccN {
-            todo!()
+        fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx._mm256_mul_ps(cast(lhs), cast(rhs)))
         }
 
         #[inline(always)]
-        fn simd_add(self, _lhs: Self::AccN, _rhs: Self::AccN) -> Self::AccN {
-            todo!()
+        fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx._mm256_add_ps(cast(lhs), cast(rhs)))
         }
     }
 
-    #[cfg(feature = "f16")]
-    unsafe impl MixedSimd<f16, f16, f16, f32> for V3 {
+    #[cfg(feature = "bf16")]
+    unsafe impl MixedSimd<bf16, bf16, bf16, f32> for V3 {
         const SIMD_WIDTH: usize = 8;
 
-        type LhsN = [f16; 8];
-        type RhsN = [f16; 8];
-        type DstN = [f16; 8];
+        type LhsN = [bf16; 8];
+        type RhsN = [bf16; 8];
+        type DstN = [bf16; 8];
         type AccN = [f32; 8];
 
         #[inline]
@@ -773,27 +1689,23 @@ mod x86 {
         }
 
         #[inline(always)]
-        fn from_lhs(self, lhs: f16) -> f32 {
-            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(lhs)))) }
+        fn from_lhs(self, lhs: bf16) -> f32 {
+            lhs.to_f32()
         }
 
         #[inline(always)]
-        fn from_rhs(self, rhs: f16) -> f32 {
-            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(rhs)))) }
+        fn from_rhs(self, rhs: bf16) -> f32 {
+            rhs.to_f32()
         }
 
         #[inline(always)]
-        fn from_dst(self, dst: f16) -> f32 {
-            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(dst)))) }
+        fn from_dst(self, dst: bf16) -> f32 {
+            dst.to_f32()
         }
 
         #[inline(always)]
-        fn into_dst(self, acc: f32) -> f16 {
-            unsafe {
-                pulp::cast_lossy(_mm_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(
-                    self.sse._mm_load_ss(&acc),
-                ))
-            }
+        fn into_dst(self, acc: f32) -> bf16 {
+            bf16::from_f32(acc)
         }
 
         #[inline(always)]
@@ -803,12 +1715,12 @@ mod x86 {
 
         #[inline(always)]
         fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
-            unsafe { cast(_mm256_cvtph_ps(cast(lhs))) }
+            unsafe { cast(bf16x8_to_f32x8(cast(lhs))) }
         }
 
         #[inline(always)]
         fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
-            unsafe { cast(_mm256_cvtph_ps(cast(rhs))) }
+            unsafe { cast(bf16x8_to_f32x8(cast(rhs))) }
         }
 
         #[inline(always)]
@@ -818,12 +1730,12 @@ mod x86 {
 
         #[inline(always)]
         fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
-            unsafe { cast(_mm256_cvtph_ps(cast(dst))) }
+            unsafe { cast(bf16x8_to_f32x8(cast(dst))) }
         }
 
         #[inline(always)]
         fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
-            unsafe { cast(_mm256_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(cast(acc))) }
+            unsafe { cast(f32x8_to_bf16x8(cast(acc))) }
         }
 
         #[inline(always)]
@@ -1462,12 +2374,229 @@ mod x86 {
 
         #[inline(always)]
         fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
-            rhs
+            rhs
+        }
+
+        #[inline(always)]
+        fn simd_splat(self, lhs: f64) -> Self::AccN {
+            cast(self.avx._mm256_set1_pd(lhs))
+        }
+
+        #[inline(always)]
+        fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+            dst
+        }
+
+        #[inline(always)]
+        fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+            acc
+        }
+
+        #[inline(always)]
+        fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+            self.vectorize(f)
+        }
+
+        #[inline(always)]
+        fn add(self, lhs: f64, rhs: f64) -> f64 {
+            lhs + rhs
+        }
+
+        #[inline(always)]
+        fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx._mm256_mul_pd(cast(lhs), cast(rhs)))
+        }
+
+        #[inline(always)]
+        fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx._mm256_add_pd(cast(lhs), cast(rhs)))
+        }
+    }
+
+    impl Simd for V3 {
+        #[inline(always)]
+        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
+            Self::new_unchecked().vectorize(f)
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    impl Simd for V4 {
+        #[inline(always)]
+        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
+            Self::new_unchecked().vectorize(f)
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    #[cfg(feature = "f16")]
+    unsafe impl MixedSimd<f16, f16, f16, f32> for V4 {
+        const SIMD_WIDTH: usize = 16;
+
+        type LhsN = [f16; 16];
+        type RhsN = [f16; 16];
+        type DstN = [f16; 16];
+        type AccN = [f32; 16];
+
+        #[inline]
+        fn try_new() -> Option<Self> {
+            Self::try_new()
+        }
+
+        #[inline(always)]
+        fn mult(self, lhs: f32, rhs: f32) -> f32 {
+            lhs * rhs
+        }
+
+        #[inline(always)]
+        fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+            unsafe { v3_fmaf(lhs, rhs, acc) }
+        }
+
+        #[inline(always)]
+        fn from_lhs(self, lhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(lhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_rhs(self, rhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(rhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_dst(self, dst: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(dst)))) }
+        }
+
+        #[inline(always)]
+        fn into_dst(self, acc: f32) -> f16 {
+            unsafe {
+                pulp::cast_lossy(_mm_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(
+                    self.sse._mm_load_ss(&acc),
+                ))
+            }
+        }
+
+        #[inline(always)]
+        fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+            cast(
+                self.avx512f
+                    ._mm512_fmadd_ps(cast(lhs), cast(rhs), cast(acc)),
+            )
+        }
+
+        #[inline(always)]
+        fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+            unsafe { cast(_mm512_cvtph_ps(cast(lhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+            unsafe { cast(_mm512_cvtph_ps(cast(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_splat(self, lhs: f32) -> Self::AccN {
+            cast(self.avx512f._mm512_set1_ps(lhs))
+        }
+
+        #[inline(always)]
+        fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+            unsafe { cast(_mm512_cvtph_ps(cast(dst))) }
+        }
+
+        #[inline(always)]
+        fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+            unsafe { cast(_mm512_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(cast(acc))) }
+        }
+
+        #[inline(always)]
+        fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+            self.vectorize(f)
+        }
+
+        #[inline(always)]
+        fn add(self, lhs: f32, rhs: f32) -> f32 {
+            lhs + rhs
+        }
+
+        #[inline(always)]
+        fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx512f._mm512_mul_ps(cast(lhs), cast(rhs)))
+        }
+
+        #[inline(always)]
+        fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            cast(self.avx512f._mm512_add_ps(cast(lhs), cast(rhs)))
+        }
+    }
+
+    #[cfg(feature = "nightly")]
+    #[cfg(feature = "f16")]
+    unsafe impl MixedSimd<f16, f16, f32, f32> for V4 {
+        const SIMD_WIDTH: usize = 16;
+
+        type LhsN = [f16; 16];
+        type RhsN = [f16; 16];
+        type DstN = [f32; 16];
+        type AccN = [f32; 16];
+
+        #[inline]
+        fn try_new() -> Option<Self> {
+            Self::try_new()
+        }
+
+        #[inline(always)]
+        fn mult(self, lhs: f32, rhs: f32) -> f32 {
+            lhs * rhs
+        }
+
+        #[inline(always)]
+        fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+            unsafe { v3_fmaf(lhs, rhs, acc) }
+        }
+
+        #[inline(always)]
+        fn from_lhs(self, lhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(lhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_rhs(self, rhs: f16) -> f32 {
+            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(rhs)))) }
+        }
+
+        #[inline(always)]
+        fn from_dst(self, dst: f32) -> f32 {
+            dst
+        }
+
+        #[inline(always)]
+        fn into_dst(self, acc: f32) -> f32 {
+            acc
+        }
+
+        #[inline(always)]
+        fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+            cast(
+                self.avx512f
+                    ._mm512_fmadd_ps(cast(lhs), cast(rhs), cast(acc)),
+            )
+        }
+
+        #[inline(always)]
+        fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+            unsafe { cast(_mm512_cvtph_ps(cast(lhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+            unsafe { cast(_mm512_cvtph_ps(cast(rhs))) }
         }
 
         #[inline(always)]
-        fn simd_splat(self, lhs: f64) -> Self::AccN {
-            cast(self.avx._mm256_set1_pd(lhs))
+        fn simd_splat(self, lhs: f32) -> Self::AccN {
+            cast(self.avx512f._mm512_set1_ps(lhs))
         }
 
         #[inline(always)]
@@ -1486,44 +2615,29 @@ mod x86 {
         }
 
         #[inline(always)]
-        fn add(self, lhs: f64, rhs: f64) -> f64 {
+        fn add(self, lhs: f32, rhs: f32) -> f32 {
             lhs + rhs
         }
 
         #[inline(always)]
         fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
-            cast(self.avx._mm256_mul_pd(cast(lhs), cast(rhs)))
+            cast(self.avx512f._mm512_mul_ps(cast(lhs), cast(rhs)))
         }
 
         #[inline(always)]
         fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
-            cast(self.avx._mm256_add_pd(cast(lhs), cast(rhs)))
-        }
-    }
-
-    impl Simd for V3 {
-        #[inline(always)]
-        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
-            Self::new_unchecked().vectorize(f)
-        }
-    }
-
-    #[cfg(feature = "nightly")]
-    impl Simd for V4 {
-        #[inline(always)]
-        unsafe fn vectorize<F: NullaryFnOnce>(f: F) -> F::Output {
-            Self::new_unchecked().vectorize(f)
+            cast(self.avx512f._mm512_add_ps(cast(lhs), cast(rhs)))
         }
     }
 
     #[cfg(feature = "nightly")]
-    #[cfg(feature = "f16")]
-    unsafe impl MixedSimd<f16, f16, f16, f32> for V4 {
+    #[cfg(feature = "bf16")]
+    unsafe impl MixedSimd<bf16, bf16, bf16, f32> for V4 {
         const SIMD_WIDTH: usize = 16;
 
-        type LhsN = [f16; 16];
-        type RhsN = [f16; 16];
-        type DstN = [f16; 16];
+        type LhsN = [bf16; 16];
+        type RhsN = [bf16; 16];
+        type DstN = [bf16; 16];
         type AccN = [f32; 16];
 
         #[inline]
@@ -1542,27 +2656,23 @@ mod x86 {
         }
 
         #[inline(always)]
-        fn from_lhs(self, lhs: f16) -> f32 {
-            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(lhs)))) }
+        fn from_lhs(self, lhs: bf16) -> f32 {
+            lhs.to_f32()
         }
 
         #[inline(always)]
-        fn from_rhs(self, rhs: f16) -> f32 {
-            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(rhs)))) }
+        fn from_rhs(self, rhs: bf16) -> f32 {
+            rhs.to_f32()
         }
 
         #[inline(always)]
-        fn from_dst(self, dst: f16) -> f32 {
-            unsafe { pulp::cast_lossy(_mm_cvtph_ps(self.sse2._mm_set1_epi16(cast(dst)))) }
+        fn from_dst(self, dst: bf16) -> f32 {
+            dst.to_f32()
         }
 
         #[inline(always)]
-        fn into_dst(self, acc: f32) -> f16 {
-            unsafe {
-                pulp::cast_lossy(_mm_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(
-                    self.sse._mm_load_ss(&acc),
-                ))
-            }
+        fn into_dst(self, acc: f32) -> bf16 {
+            bf16::from_f32(acc)
         }
 
         #[inline(always)]
@@ -1575,12 +2685,12 @@ mod x86 {
 
         #[inline(always)]
         fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
-            unsafe { cast(_mm512_cvtph_ps(cast(lhs))) }
+            unsafe { cast(bf16x16_to_f32x16(cast(lhs))) }
         }
 
         #[inline(always)]
         fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
-            unsafe { cast(_mm512_cvtph_ps(cast(rhs))) }
+            unsafe { cast(bf16x16_to_f32x16(cast(rhs))) }
         }
 
         #[inline(always)]
@@ -1590,12 +2700,12 @@ mod x86 {
 
         #[inline(always)]
         fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
-            unsafe { cast(_mm512_cvtph_ps(cast(dst))) }
+            unsafe { cast(bf16x16_to_f32x16(cast(dst))) }
         }
 
         #[inline(always)]
         fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
-            unsafe { cast(_mm512_cvtps_ph::<_MM_FROUND_CUR_DIRECTION>(cast(acc))) }
+            unsafe { cast(f32x16_to_bf16x16(cast(acc))) }
         }
 
         #[inline(always)]
@@ -2066,6 +3176,86 @@ pub mod aarch64 {
         }
     }
 
+    impl_widening_simd!(
+        Neon,
+        2,
+        f32,
+        f32,
+        f64,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+    impl_widening_simd!(
+        Neon,
+        2,
+        f32,
+        f64,
+        f64,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+    impl_widening_simd!(
+        Neon,
+        4,
+        f32,
+        f32,
+        f32,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+    impl_widening_simd!(
+        Neon,
+        2,
+        f64,
+        f64,
+        f64,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+    impl_mixed_float_simd!(
+        Neon,
+        4,
+        2,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+    impl_widening_simd!(
+        Neon,
+        4,
+        i8,
+        i32,
+        i32,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+    impl_fp8_simd!(
+        Neon,
+        4,
+        if crate::feature_detected!("neon") {
+            Some(Self { __private: () })
+        } else {
+            None
+        }
+    );
+
     impl Simd for NeonFp16 {
         #[inline]
         #[target_feature(enable = "neon,fp16")]
@@ -2393,11 +3583,234 @@ pub mod aarch64 {
             unsafe { transmute(vaddq_f32(transmute(lhs), transmute(rhs))) }
         }
     }
+
+    #[cfg(feature = "f16")]
+    unsafe impl MixedSimd<f16, f16, f32, f32> for Neon {
+        const SIMD_WIDTH: usize = 4;
+
+        type LhsN = [f16; 4];
+        type RhsN = [f16; 4];
+        type DstN = [f32; 4];
+        type AccN = [f32; 4];
+
+        #[inline]
+        fn try_new() -> Option<Self> {
+            if crate::feature_detected!("neon") {
+                Some(Self { __private: () })
+            } else {
+                None
+            }
+        }
+
+        #[inline(always)]
+        fn mult(self, lhs: f32, rhs: f32) -> f32 {
+            lhs * rhs
+        }
+
+        #[inline(always)]
+        fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+            unsafe { neon_fmaf(lhs, rhs, acc) }
+        }
+
+        #[inline(always)]
+        fn from_lhs(self, lhs: f16) -> f32 {
+            lhs.into()
+        }
+
+        #[inline(always)]
+        fn from_rhs(self, rhs: f16) -> f32 {
+            rhs.into()
+        }
+
+        #[inline(always)]
+        fn from_dst(self, dst: f32) -> f32 {
+            dst
+        }
+
+        #[inline(always)]
+        fn into_dst(self, acc: f32) -> f32 {
+            acc
+        }
+
+        #[inline(always)]
+        fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+            unsafe { transmute(vfmaq_f32(transmute(acc), transmute(lhs), transmute(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+            [lhs[0].into(), lhs[1].into(), lhs[2].into(), lhs[3].into()]
+        }
+
+        #[inline(always)]
+        fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+            [rhs[0].into(), rhs[1].into(), rhs[2].into(), rhs[3].into()]
+        }
+
+        #[inline(always)]
+        fn simd_splat(self, lhs: f32) -> Self::AccN {
+            [lhs, lhs, lhs, lhs]
+        }
+
+        #[inline(always)]
+        fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+            dst
+        }
+
+        #[inline(always)]
+        fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+            acc
+        }
+
+        #[inline(always)]
+        fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+            #[inline]
+            #[target_feature(enable = "neon")]
+            unsafe fn implementation<F: NullaryFnOnce>(f: F) -> F::Output {
+                f.call()
+            }
+
+            unsafe { implementation(f) }
+        }
+
+        #[inline(always)]
+        fn add(self, lhs: f32, rhs: f32) -> f32 {
+            lhs + rhs
+        }
+
+        #[inline(always)]
+        fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            unsafe { transmute(vmulq_f32(transmute(lhs), transmute(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            unsafe { transmute(vaddq_f32(transmute(lhs), transmute(rhs))) }
+        }
+    }
+
+    #[cfg(feature = "bf16")]
+    unsafe impl MixedSimd<bf16, bf16, bf16, f32> for Neon {
+        const SIMD_WIDTH: usize = 4;
+
+        type LhsN = [bf16; 4];
+        type RhsN = [bf16; 4];
+        type DstN = [bf16; 4];
+        type AccN = [f32; 4];
+
+        #[inline]
+        fn try_new() -> Option<Self> {
+            if crate::feature_detected!("neon") {
+                Some(Self { __private: () })
+            } else {
+                None
+            }
+        }
+
+        #[inline(always)]
+        fn mult(self, lhs: f32, rhs: f32) -> f32 {
+            lhs * rhs
+        }
+
+        #[inline(always)]
+        fn mult_add(self, lhs: f32, rhs: f32, acc: f32) -> f32 {
+            unsafe { neon_fmaf(lhs, rhs, acc) }
+        }
+
+        #[inline(always)]
+        fn from_lhs(self, lhs: bf16) -> f32 {
+            lhs.into()
+        }
+
+        #[inline(always)]
+        fn from_rhs(self, rhs: bf16) -> f32 {
+            rhs.into()
+        }
+
+        #[inline(always)]
+        fn from_dst(self, dst: bf16) -> f32 {
+            dst.into()
+        }
+
+        #[inline(always)]
+        fn into_dst(self, acc: f32) -> bf16 {
+            bf16::from_f32(acc)
+        }
+
+        #[inline(always)]
+        fn simd_mult_add(self, lhs: Self::AccN, rhs: Self::AccN, acc: Self::AccN) -> Self::AccN {
+            unsafe { transmute(vfmaq_f32(transmute(acc), transmute(lhs), transmute(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_from_lhs(self, lhs: Self::LhsN) -> Self::AccN {
+            [lhs[0].into(), lhs[1].into(), lhs[2].into(), lhs[3].into()]
+        }
+
+        #[inline(always)]
+        fn simd_from_rhs(self, rhs: Self::RhsN) -> Self::AccN {
+            [rhs[0].into(), rhs[1].into(), rhs[2].into(), rhs[3].into()]
+        }
+
+        #[inline(always)]
+        fn simd_splat(self, lhs: f32) -> Self::AccN {
+            [lhs, lhs, lhs, lhs]
+        }
+
+        #[inline(always)]
+        fn simd_from_dst(self, dst: Self::DstN) -> Self::AccN {
+            [dst[0].into(), dst[1].into(), dst[2].into(), dst[3].into()]
+        }
+
+        #[inline(always)]
+        fn simd_into_dst(self, acc: Self::AccN) -> Self::DstN {
+            [
+                bf16::from_f32(acc[0]),
+                bf16::from_f32(acc[1]),
+                bf16::from_f32(acc[2]),
+                bf16::from_f32(acc[3]),
+            ]
+        }
+
+        #[inline(always)]
+        fn vectorize<F: NullaryFnOnce>(self, f: F) -> F::Output {
+            #[inline]
+            #[target_feature(enable = "neon")]
+            unsafe fn implementation<F: NullaryFnOnce>(f: F) -> F::Output {
+                f.call()
+            }
+
+            unsafe { implementation(f) }
+        }
+
+        #[inline(always)]
+        fn add(self, lhs: f32, rhs: f32) -> f32 {
+            lhs + rhs
+        }
+
+        #[inline(always)]
+        fn simd_mul(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            unsafe { transmute(vmulq_f32(transmute(lhs), transmute(rhs))) }
+        }
+
+        #[inline(always)]
+        fn simd_add(self, lhs: Self::AccN, rhs: Self::AccN) -> Self::AccN {
+            unsafe { transmute(vaddq_f32(transmute(lhs), transmute(rhs))) }
+        }
+    }
 }
 
 pub trait Boilerplate: Copy + Send + Sync + core::fmt::Debug + 'static + PartialEq {}
 impl<T: Copy + Send + Sync + core::fmt::Debug + PartialEq + 'static> Boilerplate for T {}
 
+/// Conversions between the operands, the destination and the accumulator of a mixed precision
+/// product.
+///
+/// # Safety
+///
+/// `LhsN`, `RhsN`, `DstN` and `AccN` must have the layout of `[T; SIMD_WIDTH]` for `T` equal to
+/// `Lhs`, `Rhs`, `Dst` and `Acc` respectively, since the packing routines read and write them
+/// through pointers to their elements.
 pub unsafe trait MixedSimd<Lhs, Rhs, Dst, Acc>: Simd {
     const SIMD_WIDTH: usize;
 
@@ -0,0 +1,430 @@
+//! Tropical semirings over `f32` and `f64`, where the sum is replaced by the minimum (or the
+//! maximum), and the product by the sum.
+//!
+//! The additive identity of [`MinPlus`] is `+∞`, and that of [`MaxPlus`] is `-∞`. Their
+//! multiplicative identity is `0`. Products involving both infinities, such as `+∞ + -∞` in
+//! [`MinPlus`], give NaN. A NaN operand makes both the sum and the product NaN, whatever the
+//! order of the operands, so a NaN reaching an accumulator is never dropped.
+
+use crate::gemm::Conj;
+use core::ops::{Add, Mul};
+use num_traits::{One, Zero};
+
+/// Element of the min-plus semiring: `a + b` is `min(a, b)`, and `a * b` is `a + b`. Both are NaN
+/// if either operand is NaN.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct MinPlus<T>(pub T);
+
+/// Element of the max-plus semiring: `a + b` is `max(a, b)`, and `a * b` is `a + b`. Both are NaN
+/// if either operand is NaN.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[repr(transparent)]
+pub struct MaxPlus<T>(pub T);
+
+macro_rules! impl_tropical {
+    ($tropical: ident, $float: ty, $zero: expr, $cmp: tt) => {
+        impl $tropical<$float> {
+            /// Additive identity, absorbing for the product.
+            pub const ZERO: Self = Self($zero);
+            /// Multiplicative identity.
+            pub const ONE: Self = Self(0.0);
+        }
+
+        impl Add for $tropical<$float> {
+            type Output = Self;
+
+            #[inline(always)]
+            fn add(self, rhs: Self) -> Self {
+                // `self` is kept if it is NaN, since no comparison with NaN holds
+                if rhs.0 $cmp self.0 || rhs.0.is_nan() {
+                    rhs
+                } else {
+                    self
+                }
+            }
+        }
+
+        impl Mul for $tropical<$float> {
+            type Output = Self;
+
+            #[inline(always)]
+            #[allow(clippy::suspicious_arithmetic_impl)]
+            fn mul(self, rhs: Self) -> Self {
+                Self(self.0 + rhs.0)
+            }
+        }
+
+        impl Zero for $tropical<$float> {
+            #[inline(always)]
+            fn zero() -> Self {
+                Self::ZERO
+            }
+
+            #[inline(always)]
+            fn is_zero(&self) -> bool {
+                *self == Self::ZERO
+            }
+        }
+
+        impl One for $tropical<$float> {
+            #[inline(always)]
+            fn one() -> Self {
+                Self::ONE
+            }
+        }
+
+        impl Conj for $tropical<$float> {
+            #[inline(always)]
+            fn conj(self) -> Self {
+                self
+            }
+        }
+    };
+}
+
+impl_tropical!(MinPlus, f32, f32::INFINITY, <);
+impl_tropical!(MinPlus, f64, f64::INFINITY, <);
+impl_tropical!(MaxPlus, f32, f32::NEG_INFINITY, >);
+impl_tropical!(MaxPlus, f64, f64::NEG_INFINITY, >);
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __tropical_pack_ops {
+    (scalar, $tropical: ident, $float: ident) => {
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value; N]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            ::core::array::from_fn(|i| lhs[i] * rhs[i])
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            ::core::array::from_fn(|i| lhs[i] + rhs[i])
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+    };
+
+    (fma, MinPlus, f32) => {
+        $crate::__tropical_pack_ops!(@avx __m256, _mm256_min_ps, _mm256_add_ps, _mm256_cmp_ps, _mm256_blendv_ps);
+    };
+    (fma, MaxPlus, f32) => {
+        $crate::__tropical_pack_ops!(@avx __m256, _mm256_max_ps, _mm256_add_ps, _mm256_cmp_ps, _mm256_blendv_ps);
+    };
+    (fma, MinPlus, f64) => {
+        $crate::__tropical_pack_ops!(@avx __m256d, _mm256_min_pd, _mm256_add_pd, _mm256_cmp_pd, _mm256_blendv_pd);
+    };
+    (fma, MaxPlus, f64) => {
+        $crate::__tropical_pack_ops!(@avx __m256d, _mm256_max_pd, _mm256_add_pd, _mm256_cmp_pd, _mm256_blendv_pd);
+    };
+
+    (avx512f, MinPlus, f32) => {
+        $crate::__tropical_pack_ops!(@avx512 __m512, _mm512_min_ps, _mm512_add_ps, _mm512_cmp_ps_mask, _mm512_mask_blend_ps);
+    };
+    (avx512f, MaxPlus, f32) => {
+        $crate::__tropical_pack_ops!(@avx512 __m512, _mm512_max_ps, _mm512_add_ps, _mm512_cmp_ps_mask, _mm512_mask_blend_ps);
+    };
+    (avx512f, MinPlus, f64) => {
+        $crate::__tropical_pack_ops!(@avx512 __m512d, _mm512_min_pd, _mm512_add_pd, _mm512_cmp_pd_mask, _mm512_mask_blend_pd);
+    };
+    (avx512f, MaxPlus, f64) => {
+        $crate::__tropical_pack_ops!(@avx512 __m512d, _mm512_max_pd, _mm512_add_pd, _mm512_cmp_pd_mask, _mm512_mask_blend_pd);
+    };
+
+    (neon, MinPlus, f32) => {
+        $crate::__tropical_pack_ops!(@neon float32x4_t, vminq_f32, vaddq_f32);
+    };
+    (neon, MaxPlus, f32) => {
+        $crate::__tropical_pack_ops!(@neon float32x4_t, vmaxq_f32, vaddq_f32);
+    };
+    (neon, MinPlus, f64) => {
+        $crate::__tropical_pack_ops!(@neon float64x2_t, vminq_f64, vaddq_f64);
+    };
+    (neon, MaxPlus, f64) => {
+        $crate::__tropical_pack_ops!(@neon float64x2_t, vmaxq_f64, vaddq_f64);
+    };
+
+    // the vector minimum (or maximum) returns its second operand if either of them is NaN, so a
+    // NaN in `lhs` is selected separately
+    (@avx $vec: ident, $min_max: ident, $add: ident, $cmp: ident, $blend: ident) => {
+        #[cfg(target_arch = "x86")]
+        use ::core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use ::core::arch::x86_64::*;
+        use ::core::mem::transmute;
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value; N]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute($add(transmute(lhs), transmute(rhs)))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            let (lhs, rhs): ($vec, $vec) = (transmute(lhs), transmute(rhs));
+            transmute($blend($min_max(lhs, rhs), lhs, $cmp::<_CMP_UNORD_Q>(lhs, lhs)))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+    };
+    (@avx512 $vec: ident, $min_max: ident, $add: ident, $cmp: ident, $blend: ident) => {
+        #[cfg(target_arch = "x86")]
+        use ::core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use ::core::arch::x86_64::*;
+        use ::core::mem::transmute;
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value; N]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute($add(transmute(lhs), transmute(rhs)))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            let (lhs, rhs): ($vec, $vec) = (transmute(lhs), transmute(rhs));
+            transmute($blend($cmp::<_CMP_UNORD_Q>(lhs, lhs), $min_max(lhs, rhs), lhs))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+    };
+    // the NEON minimum and maximum propagate NaN from either operand
+    (@neon $vec: ident, $min_max: ident, $add: ident) => {
+        use ::core::arch::aarch64::*;
+        use ::core::mem::transmute;
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value; N]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute($add(transmute(lhs), transmute(rhs)))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            let (lhs, rhs): ($vec, $vec) = (transmute(lhs), transmute(rhs));
+            transmute($min_max(lhs, rhs))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __inject_mod_tropical {
+    ($module: ident, $tropical: ident, $float: ident, $simd: ident, $N: expr, $unroll: tt $(, $target: tt)?) => {
+        mod $module {
+            use super::*;
+            use $crate::simd::MixedSimd;
+
+            const N: usize = $N;
+            type Pack = [T; N];
+
+            $crate::__tropical_pack_ops!($module, $tropical, $float);
+
+            #[inline(always)]
+            unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+                lhs * rhs
+            }
+
+            #[inline(always)]
+            unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+                lhs + rhs
+            }
+
+            #[inline(always)]
+            unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+                a * b + c
+            }
+
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x1x1, 1, 1);
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x1x2, 1, 2);
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x1x3, 1, 3);
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x1x4, 1, 4);
+
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x2x1, 2, 1);
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x2x2, 2, 2);
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x2x3, 2, 3);
+            $crate::microkernel!(zero: T::ZERO; $([$target])?, $unroll, x2x4, 2, 4);
+
+            $crate::microkernel_fn_array! {
+                [x1x1, x1x2, x1x3, x1x4,],
+                [x2x1, x2x2, x2x3, x2x4,],
+            }
+
+            #[inline(never)]
+            pub unsafe fn gemm_basic(
+                m: usize,
+                n: usize,
+                k: usize,
+                dst: *mut T,
+                dst_cs: isize,
+                dst_rs: isize,
+                read_dst: bool,
+                lhs: *const T,
+                lhs_cs: isize,
+                lhs_rs: isize,
+                rhs: *const T,
+                rhs_cs: isize,
+                rhs_rs: isize,
+                alpha: T,
+                beta: T,
+                _conj_dst: bool,
+                _conj_lhs: bool,
+                _conj_rhs: bool,
+                parallelism: $crate::Parallelism,
+            ) -> Result<(), $crate::gemm::AllocError> {
+                $crate::gemm::gemm_basic_generic::<_, T, N, { MR_DIV_N * N }, NR, MR_DIV_N, 0, 0>(
+                    <$crate::simd::$simd as MixedSimd<T, T, T, T>>::try_new().unwrap(),
+                    m,
+                    n,
+                    k,
+                    dst,
+                    dst_cs,
+                    dst_rs,
+                    read_dst,
+                    lhs,
+                    lhs_cs,
+                    lhs_rs,
+                    rhs,
+                    rhs_cs,
+                    rhs_rs,
+                    alpha,
+                    beta,
+                    false,
+                    false,
+                    false,
+                    |a, b, c| a * b + c,
+                    &UKR,
+                    &[],
+                    false,
+                    parallelism,
+                )
+            }
+        }
+    };
+}
+
+/// Defines `get_gemm_fn` for the tropical semiring `$tropical<$float>`, along with its
+/// microkernels, which replace the fused multiply-add with a vector addition followed by a vector
+/// minimum (or maximum).
+#[macro_export]
+macro_rules! gemm_tropical_def {
+    (
+        $tropical: ident, $float: ident;
+        scalar: $scalar_n: expr, fma: $fma_n: expr $(, avx512f: $avx512f_n: expr)? $(, neon: $neon_n: expr)? $(,)?
+    ) => {
+        type T = $crate::tropical::$tropical<$float>;
+
+        type GemmTy = unsafe fn(
+            usize,
+            usize,
+            usize,
+            *mut T,
+            isize,
+            isize,
+            bool,
+            *const T,
+            isize,
+            isize,
+            *const T,
+            isize,
+            isize,
+            T,
+            T,
+            bool,
+            bool,
+            bool,
+            $crate::Parallelism,
+        ) -> Result<(), $crate::gemm::AllocError>;
+
+        fn init_gemm_fn() -> GemmTy {
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            {
+                $(
+                    const _: usize = $avx512f_n;
+                    #[cfg(feature = "nightly")]
+                    if $crate::feature_detected!("avx512f") {
+                        return avx512f::gemm_basic;
+                    }
+                )?
+                if $crate::feature_detected!("fma") && $crate::feature_detected!("avx2") {
+                    return fma::gemm_basic;
+                }
+            }
+
+            $(
+                const _: usize = $neon_n;
+                #[cfg(target_arch = "aarch64")]
+                if $crate::feature_detected!("neon") {
+                    return neon::gemm_basic;
+                }
+            )?
+
+            scalar::gemm_basic
+        }
+
+        static GEMM_PTR: ::core::sync::atomic::AtomicPtr<()> =
+            ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut());
+
+        #[inline(never)]
+        fn init_gemm_ptr() -> GemmTy {
+            let gemm_fn = init_gemm_fn();
+            GEMM_PTR.store(gemm_fn as *mut (), ::core::sync::atomic::Ordering::Relaxed);
+            gemm_fn
+        }
+
+        #[inline(always)]
+        pub fn get_gemm_fn() -> GemmTy {
+            let mut gemm_fn = GEMM_PTR.load(::core::sync::atomic::Ordering::Relaxed);
+            if gemm_fn.is_null() {
+                gemm_fn = init_gemm_ptr() as *mut ();
+            }
+            unsafe { ::core::mem::transmute(gemm_fn) }
+        }
+
+        $crate::__inject_mod_tropical!(scalar, $tropical, $float, Scalar, $scalar_n, 2);
+
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        $crate::__inject_mod_tropical!(fma, $tropical, $float, V3, $fma_n, 2, "fma");
+
+        $(
+            #[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+            $crate::__inject_mod_tropical!(avx512f, $tropical, $float, V4, $avx512f_n, 4, "avx512f");
+        )?
+
+        $(
+            #[cfg(target_arch = "aarch64")]
+            $crate::__inject_mod_tropical!(neon, $tropical, $float, Scalar, $neon_n, 4, "neon");
+        )?
+    };
+}
@@ -1,18 +1,20 @@
 use dyn_stack::{DynStack, MemBuffer, StackReq};
 #[cfg(feature = "std")]
-use gemm_common::gemm::L2_SLAB;
+use gemm_common::gemm::with_l2_slab;
 #[cfg(feature = "rayon")]
 use gemm_common::gemm::{get_threading_threshold, par_for_each};
 
+use core::any::TypeId;
 use gemm_common::{
     cache::{kernel_params, DivCeil, KernelParams},
-    gemm::CACHELINE_ALIGN,
+    gemm::{AllocError, CACHELINE_ALIGN},
     gemv, gevv,
     microkernel::MicroKernelFn,
     pack_operands::quick_zero,
     simd::{MixedSimd, NullaryFnOnce},
     Parallelism, Ptr,
 };
+use num_traits::{One, Zero};
 type T = half::f16;
 
 #[allow(unused_imports)]
@@ -22,7 +24,8 @@ use gemm_common::simd::*;
 unsafe fn pack_generic_inner_loop<
     const N: usize,
     const DST_WIDTH: usize,
-    S: MixedSimd<T, T, T, f32>,
+    D,
+    S: MixedSimd<T, T, D, f32>,
 >(
     simd: S,
     mut dst: *mut f32,
@@ -102,7 +105,7 @@ unsafe fn pack_generic_inner_loop<
                 for j in 0..src_width / N {
                     let j = j * N;
                     let dst = dst.add(j) as *mut S::AccN;
-                    *dst = simd.simd_from_dst(*(src.offset(j as isize * src_rs) as *const S::DstN));
+                    *dst = simd.simd_from_lhs(*(src.offset(j as isize * src_rs) as *const S::LhsN));
                 }
                 src = src.wrapping_offset(src_cs);
                 dst = dst.add(DST_WIDTH);
@@ -125,7 +128,7 @@ unsafe fn pack_generic_inner_loop<
 }
 
 #[inline(always)]
-unsafe fn pack_generic<const N: usize, const DST_WIDTH: usize, S: MixedSimd<T, T, T, f32>>(
+unsafe fn pack_generic<const N: usize, const DST_WIDTH: usize, D, S: MixedSimd<T, T, D, f32>>(
     simd: S,
     m: usize,
     k: usize,
@@ -139,19 +142,19 @@ unsafe fn pack_generic<const N: usize, const DST_WIDTH: usize, S: MixedSimd<T, T
 
     let mut i = 0;
     while i < m_width {
-        pack_generic_inner_loop::<N, DST_WIDTH, _>(simd, dst, src, src_rs, src_cs, DST_WIDTH, k);
+        pack_generic_inner_loop::<N, DST_WIDTH, D, _>(simd, dst, src, src_rs, src_cs, DST_WIDTH, k);
         src = src.wrapping_offset(src_rs * DST_WIDTH as isize);
         dst = dst.add(dst_stride);
 
         i += DST_WIDTH;
     }
     if i < m {
-        pack_generic_inner_loop::<N, DST_WIDTH, _>(simd, dst, src, src_rs, src_cs, m - i, k);
+        pack_generic_inner_loop::<N, DST_WIDTH, D, _>(simd, dst, src, src_rs, src_cs, m - i, k);
     }
 }
 
 #[inline(never)]
-pub unsafe fn pack_lhs<const N: usize, const MR: usize, S: MixedSimd<T, T, T, f32>>(
+pub unsafe fn pack_lhs<const N: usize, const MR: usize, D, S: MixedSimd<T, T, D, f32>>(
     simd: S,
     m: usize,
     k: usize,
@@ -163,7 +166,7 @@ pub unsafe fn pack_lhs<const N: usize, const MR: usize, S: MixedSimd<T, T, T, f3
 ) {
     let dst = dst.0;
     let src = src.0;
-    struct Impl<const N: usize, const MR: usize, S> {
+    struct Impl<const N: usize, const MR: usize, D, S> {
         simd: S,
         m: usize,
         k: usize,
@@ -172,8 +175,11 @@ pub unsafe fn pack_lhs<const N: usize, const MR: usize, S: MixedSimd<T, T, T, f3
         src_cs: isize,
         src_rs: isize,
         dst_stride: usize,
+        __marker: core::marker::PhantomData<fn() -> D>,
     }
-    impl<const N: usize, const MR: usize, S: MixedSimd<T, T, T, f32>> NullaryFnOnce for Impl<N, MR, S> {
+    impl<const N: usize, const MR: usize, D, S: MixedSimd<T, T, D, f32>> NullaryFnOnce
+        for Impl<N, MR, D, S>
+    {
         type Output = ();
 
         #[inline(always)]
@@ -187,12 +193,15 @@ pub unsafe fn pack_lhs<const N: usize, const MR: usize, S: MixedSimd<T, T, T, f3
                 src_cs,
                 src_rs,
                 dst_stride,
+                __marker: _,
             } = self;
-            unsafe { pack_generic::<N, MR, _>(simd, m, k, dst, src, src_cs, src_rs, dst_stride) };
+            unsafe {
+                pack_generic::<N, MR, D, _>(simd, m, k, dst, src, src_cs, src_rs, dst_stride)
+            };
         }
     }
 
-    simd.vectorize(Impl::<N, MR, _> {
+    simd.vectorize(Impl::<N, MR, D, _> {
         simd,
         m,
         k,
@@ -201,11 +210,12 @@ pub unsafe fn pack_lhs<const N: usize, const MR: usize, S: MixedSimd<T, T, T, f3
         src_cs,
         src_rs,
         dst_stride,
+        __marker: core::marker::PhantomData,
     });
 }
 
 #[inline(never)]
-pub unsafe fn pack_rhs<const N: usize, const NR: usize, S: MixedSimd<T, T, T, f32>>(
+pub unsafe fn pack_rhs<const N: usize, const NR: usize, D, S: MixedSimd<T, T, D, f32>>(
     simd: S,
     n: usize,
     k: usize,
@@ -218,7 +228,7 @@ pub unsafe fn pack_rhs<const N: usize, const NR: usize, S: MixedSimd<T, T, T, f3
     let dst = dst.0;
     let src = src.0;
 
-    struct Impl<const N: usize, const NR: usize, S> {
+    struct Impl<const N: usize, const NR: usize, D, S> {
         simd: S,
         n: usize,
         k: usize,
@@ -227,8 +237,11 @@ pub unsafe fn pack_rhs<const N: usize, const NR: usize, S: MixedSimd<T, T, T, f3
         src_cs: isize,
         src_rs: isize,
         dst_stride: usize,
+        __marker: core::marker::PhantomData<fn() -> D>,
     }
-    impl<const N: usize, const NR: usize, S: MixedSimd<T, T, T, f32>> NullaryFnOnce for Impl<N, NR, S> {
+    impl<const N: usize, const NR: usize, D, S: MixedSimd<T, T, D, f32>> NullaryFnOnce
+        for Impl<N, NR, D, S>
+    {
         type Output = ();
 
         #[inline(always)]
@@ -242,12 +255,15 @@ pub unsafe fn pack_rhs<const N: usize, const NR: usize, S: MixedSimd<T, T, T, f3
                 src_cs,
                 src_rs,
                 dst_stride,
+                __marker: _,
             } = self;
-            unsafe { pack_generic::<N, NR, _>(simd, n, k, dst, src, src_rs, src_cs, dst_stride) };
+            unsafe {
+                pack_generic::<N, NR, D, _>(simd, n, k, dst, src, src_rs, src_cs, dst_stride)
+            };
         }
     }
 
-    simd.vectorize(Impl::<N, NR, _> {
+    simd.vectorize(Impl::<N, NR, D, _> {
         simd,
         n,
         k,
@@ -256,6 +272,7 @@ pub unsafe fn pack_rhs<const N: usize, const NR: usize, S: MixedSimd<T, T, T, f3
         src_cs,
         src_rs,
         dst_stride,
+        __marker: core::marker::PhantomData,
     });
 }
 
@@ -265,13 +282,14 @@ pub unsafe fn gemm_basic_generic<
     const MR: usize,
     const NR: usize,
     const MR_DIV_N: usize,
-    S: MixedSimd<T, T, T, f32>,
+    D: Boilerplate + One + Zero + core::ops::Mul<Output = D>,
+    S: MixedSimd<T, T, D, f32>,
 >(
     simd: S,
     m: usize,
     n: usize,
     k: usize,
-    dst: *mut T,
+    dst: *mut D,
     dst_cs: isize,
     dst_rs: isize,
     read_dst: bool,
@@ -281,29 +299,29 @@ pub unsafe fn gemm_basic_generic<
     rhs: *const T,
     rhs_cs: isize,
     rhs_rs: isize,
-    mut alpha: T,
-    beta: T,
+    mut alpha: D,
+    beta: D,
     dispatcher: &[[MicroKernelFn<f32>; NR]; MR_DIV_N],
     parallelism: Parallelism,
-) {
+) -> Result<(), AllocError> {
     if m == 0 || n == 0 {
-        return;
+        return Ok(());
     }
     if !read_dst {
-        alpha = T::ZERO;
+        alpha = D::zero();
     }
 
     if k == 0 {
-        if alpha == T::ZERO {
+        if alpha == D::zero() {
             for j in 0..n {
                 for i in 0..m {
-                    *dst.offset(i as isize * dst_rs + j as isize * dst_cs) = T::ZERO;
+                    *dst.offset(i as isize * dst_rs + j as isize * dst_cs) = D::zero();
                 }
             }
-            return;
+            return Ok(());
         }
-        if alpha == T::ONE {
-            return;
+        if alpha == D::one() {
+            return Ok(());
         }
 
         for j in 0..n {
@@ -312,17 +330,18 @@ pub unsafe fn gemm_basic_generic<
                 *dst = alpha * *dst;
             }
         }
-        return;
+        return Ok(());
     }
 
     {
-        if k <= 2 {
+        // the f32 destination is handled by the packed path below
+        if k <= 2 && TypeId::of::<D>() == TypeId::of::<T>() {
             gevv::gevv(
                 simd,
                 m,
                 n,
                 k,
-                dst,
+                dst as *mut T,
                 dst_cs,
                 dst_rs,
                 lhs,
@@ -331,17 +350,13 @@ pub unsafe fn gemm_basic_generic<
                 rhs,
                 rhs_cs,
                 rhs_rs,
-                alpha,
-                beta,
+                core::mem::transmute_copy::<D, T>(&alpha),
+                core::mem::transmute_copy::<D, T>(&beta),
                 |a, b, c| {
-                    simd.into_dst(simd.mult_add(
-                        simd.from_dst(a),
-                        simd.from_dst(b),
-                        simd.from_dst(c),
-                    ))
+                    T::from_f32(simd.mult_add(simd.from_lhs(a), simd.from_rhs(b), simd.from_lhs(c)))
                 },
             );
-            return;
+            return Ok(());
         }
 
         let alpha = simd.from_dst(alpha);
@@ -351,14 +366,14 @@ pub unsafe fn gemm_basic_generic<
                 simd, m, n, k, dst, dst_cs, dst_rs, lhs, lhs_cs, lhs_rs, rhs, rhs_cs, rhs_rs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
         if n <= 1 && lhs_cs == 1 && rhs_rs == 1 {
             gemv::mixed_gemv_rowmajor(
                 simd, m, n, k, dst, dst_cs, dst_rs, lhs, lhs_cs, lhs_rs, rhs, rhs_cs, rhs_rs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
 
         if m <= 1 && rhs_cs == 1 && dst_cs == 1 {
@@ -366,14 +381,14 @@ pub unsafe fn gemm_basic_generic<
                 simd, n, m, k, dst, dst_rs, dst_cs, rhs, rhs_rs, rhs_cs, lhs, lhs_rs, lhs_cs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
         if m <= 1 && rhs_rs == 1 && lhs_cs == 1 {
             gemv::mixed_gemv_rowmajor(
                 simd, n, m, k, dst, dst_rs, dst_cs, rhs, rhs_rs, rhs_cs, lhs, lhs_rs, lhs_cs,
                 alpha, beta,
             );
-            return;
+            return Ok(());
         }
     }
 
@@ -409,12 +424,12 @@ pub unsafe fn gemm_basic_generic<
         simd_align,
     );
 
-    let mut mem = MemBuffer::new(rhs_req.and(lhs_req));
+    let mut mem = MemBuffer::try_new(rhs_req.and(lhs_req))?;
     #[cfg(not(feature = "std"))]
-    let mut l2_slab = MemBuffer::new(StackReq::new_aligned::<f32>(
+    let mut l2_slab = MemBuffer::try_new(StackReq::new_aligned::<f32>(
         packed_lhs_stride * (mc / MR),
         simd_align,
-    ));
+    ))?;
 
     let stack = DynStack::new(&mut mem);
     let (packed_rhs_storage, stack) =
@@ -441,7 +456,7 @@ pub unsafe fn gemm_basic_generic<
     while col_outer != n {
         let n_chunk = nc.min(n - col_outer);
 
-        let mut alpha = simd.from_lhs(alpha);
+        let mut alpha = simd.from_dst(alpha);
 
         let mut depth_outer = 0;
         while depth_outer != k {
@@ -474,7 +489,7 @@ pub unsafe fn gemm_basic_generic<
 
             // pack rhs
             if n_threads <= 1 {
-                pack_rhs::<N, NR, _>(
+                pack_rhs::<N, NR, D, _>(
                     simd,
                     n_chunk,
                     k_chunk,
@@ -513,7 +528,7 @@ pub unsafe fn gemm_basic_generic<
                         let j = col_inner / NR;
 
                         if ncols > 0 {
-                            pack_rhs::<N, NR, _>(
+                            pack_rhs::<N, NR, D, _>(
                                 simd,
                                 ncols,
                                 k_chunk,
@@ -535,7 +550,7 @@ pub unsafe fn gemm_basic_generic<
                 unreachable!();
             }
             if do_prepack_lhs {
-                pack_lhs::<N, MR, _>(
+                pack_lhs::<N, MR, D, _>(
                     simd,
                     m,
                     k_chunk,
@@ -600,7 +615,7 @@ pub unsafe fn gemm_basic_generic<
                     let packed_lhs_cs = MR as isize;
 
                     if !do_prepack_lhs {
-                        pack_lhs::<N, MR, _>(
+                        pack_lhs::<N, MR, D, _>(
                             simd,
                             m_chunk,
                             k_chunk,
@@ -661,7 +676,7 @@ pub unsafe fn gemm_basic_generic<
                                 packed_rhs_rs,
                                 packed_rhs_cs,
                                 0.0,
-                                beta.into(),
+                                simd.from_dst(beta),
                                 0,
                                 false,
                                 false,
@@ -728,16 +743,20 @@ pub unsafe fn gemm_basic_generic<
                     }
                 }
             } else {
+                #[cfg(feature = "std")]
+                let alloc_failed = core::sync::atomic::AtomicBool::new(false);
                 #[cfg(feature = "std")]
                 let func = |tid: usize| {
-                    L2_SLAB.with(|mem| {
-                        let mut mem = mem.borrow_mut();
-                        let stack = DynStack::new(&mut mem);
+                    let packed = with_l2_slab(|mem| {
+                        let stack = DynStack::new(mem);
                         let (packed_lhs_storage, _) = stack
                             .make_aligned_uninit::<f32>(packed_lhs_stride * (mc / MR), simd_align);
                         let packed_lhs = Ptr(packed_lhs_storage.as_mut_ptr() as *mut f32);
                         func(tid, packed_lhs);
                     });
+                    if packed.is_err() {
+                        alloc_failed.store(true, core::sync::atomic::Ordering::Relaxed);
+                    }
                 };
 
                 #[cfg(not(feature = "std"))]
@@ -760,6 +779,11 @@ pub unsafe fn gemm_basic_generic<
                         }
                     }
                 }
+
+                #[cfg(feature = "std")]
+                if alloc_failed.into_inner() {
+                    return Err(AllocError);
+                }
             }
 
             alpha = 1.0;
@@ -767,11 +791,13 @@ pub unsafe fn gemm_basic_generic<
         }
         col_outer += n_chunk;
     }
+
+    Ok(())
 }
 
 pub mod f16 {
     use super::gemm_basic_generic;
-    use gemm_common::Parallelism;
+    use gemm_common::{gemm::AllocError, Parallelism};
 
     type T = half::f16;
     type GemmTy = unsafe fn(
@@ -794,7 +820,7 @@ pub mod f16 {
         bool,
         bool,
         Parallelism,
-    );
+    ) -> Result<(), AllocError>;
 
     fn init_gemm_fn() -> GemmTy {
         #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
@@ -879,8 +905,8 @@ pub mod f16 {
             _conj_lhs: bool,
             _conj_rhs: bool,
             parallelism: gemm_common::Parallelism,
-        ) {
-            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, _>(
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, T, _>(
                 Scalar,
                 m,
                 n,
@@ -899,7 +925,7 @@ pub mod f16 {
                 beta,
                 &UKR,
                 parallelism,
-            );
+            )
         }
     }
 
@@ -931,8 +957,8 @@ pub mod f16 {
             _conj_lhs: bool,
             _conj_rhs: bool,
             parallelism: gemm_common::Parallelism,
-        ) {
-            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, _>(
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, T, _>(
                 gemm_common::simd::Neon::try_new().unwrap(),
                 m,
                 n,
@@ -951,13 +977,14 @@ pub mod f16 {
                 beta,
                 &UKR,
                 parallelism,
-            );
+            )
         }
     }
 
     #[cfg(target_arch = "aarch64")]
     mod neonfp16 {
         use crate::microkernel::neonfp16::f16::*;
+        use gemm_common::gemm::AllocError;
         use gemm_common::simd::{MixedSimd, NeonFp16};
         type T = half::f16;
 
@@ -982,7 +1009,7 @@ pub mod f16 {
             _conj_lhs: bool,
             _conj_rhs: bool,
             parallelism: gemm_common::Parallelism,
-        ) {
+        ) -> Result<(), AllocError> {
             let simd = <NeonFp16 as MixedSimd<T, T, T, T>>::try_new().unwrap();
 
             gemm_common::gemm::gemm_basic_generic::<_, _, N, { MR_DIV_N * N }, NR, MR_DIV_N, 0, 0>(
@@ -1010,7 +1037,7 @@ pub mod f16 {
                 &[],
                 false,
                 parallelism,
-            );
+            )
         }
     }
 
@@ -1018,6 +1045,7 @@ pub mod f16 {
     #[cfg(feature = "experimental-apple-amx")]
     mod amx {
         use crate::microkernel::amx::f16::*;
+        use gemm_common::gemm::AllocError;
         use gemm_common::simd::{MixedSimd, NeonFp16};
         type T = half::f16;
 
@@ -1042,7 +1070,7 @@ pub mod f16 {
             _conj_lhs: bool,
             _conj_rhs: bool,
             parallelism: gemm_common::Parallelism,
-        ) {
+        ) -> Result<(), AllocError> {
             let simd = <NeonFp16 as MixedSimd<T, T, T, T>>::try_new().unwrap();
 
             gemm_common::gemm::gemm_basic_generic::<_, _, N, { MR_DIV_N * N }, NR, MR_DIV_N, 0, 0>(
@@ -1070,7 +1098,7 @@ pub mod f16 {
                 &[],
                 true,
                 parallelism,
-            );
+            )
         }
     }
 
@@ -1102,8 +1130,8 @@ pub mod f16 {
             _conj_lhs: bool,
             _conj_rhs: bool,
             parallelism: gemm_common::Parallelism,
-        ) {
-            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, _>(
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, T, _>(
                 V3::try_new().unwrap(),
                 m,
                 n,
@@ -1122,7 +1150,7 @@ pub mod f16 {
                 beta,
                 &UKR,
                 parallelism,
-            );
+            )
         }
     }
 
@@ -1154,8 +1182,8 @@ pub mod f16 {
             _conj_lhs: bool,
             _conj_rhs: bool,
             parallelism: gemm_common::Parallelism,
-        ) {
-            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, _>(
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, T, _>(
                 V4::try_new().unwrap(),
                 m,
                 n,
@@ -1174,7 +1202,291 @@ pub mod f16 {
                 beta,
                 &UKR,
                 parallelism,
-            );
+            )
+        }
+    }
+}
+
+/// `f16` inputs, with an `f32` destination and scalars.
+pub mod f16_f32 {
+    use super::gemm_basic_generic;
+    use gemm_common::{gemm::AllocError, Parallelism};
+
+    type T = half::f16;
+    type GemmTy = unsafe fn(
+        usize,
+        usize,
+        usize,
+        *mut f32,
+        isize,
+        isize,
+        bool,
+        *const T,
+        isize,
+        isize,
+        *const T,
+        isize,
+        isize,
+        f32,
+        f32,
+        bool,
+        bool,
+        bool,
+        Parallelism,
+    ) -> Result<(), AllocError>;
+
+    fn init_gemm_fn() -> GemmTy {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            #[cfg(feature = "nightly")]
+            if gemm_common::feature_detected!("avx512f") {
+                return avx512f::gemm_basic;
+            }
+            if gemm_common::feature_detected!("fma") {
+                fma::gemm_basic
+            } else {
+                scalar::gemm_basic
+            }
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        {
+            if gemm_common::feature_detected!("neon") {
+                neon::gemm_basic
+            } else {
+                scalar::gemm_basic
+            }
+        }
+
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+        {
+            scalar::gemm_basic
+        }
+    }
+
+    static GEMM_PTR: ::core::sync::atomic::AtomicPtr<()> =
+        ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut());
+
+    #[inline(never)]
+    fn init_gemm_ptr() -> GemmTy {
+        let gemm_fn = init_gemm_fn();
+        GEMM_PTR.store(gemm_fn as *mut (), ::core::sync::atomic::Ordering::Relaxed);
+        gemm_fn
+    }
+
+    #[inline(always)]
+    pub fn get_gemm_fn() -> GemmTy {
+        let mut gemm_fn = GEMM_PTR.load(::core::sync::atomic::Ordering::Relaxed);
+        if gemm_fn.is_null() {
+            gemm_fn = init_gemm_ptr() as *mut ();
+        }
+        unsafe { ::core::mem::transmute(gemm_fn) }
+    }
+
+    mod scalar {
+        use super::*;
+        use gemm_common::simd::Scalar;
+        use gemm_f32::microkernel::scalar::f32::*;
+        const N: usize = 1;
+
+        #[inline(never)]
+        pub unsafe fn gemm_basic(
+            m: usize,
+            n: usize,
+            k: usize,
+            dst: *mut f32,
+            dst_cs: isize,
+            dst_rs: isize,
+            read_dst: bool,
+            lhs: *const T,
+            lhs_cs: isize,
+            lhs_rs: isize,
+            rhs: *const T,
+            rhs_cs: isize,
+            rhs_rs: isize,
+            alpha: f32,
+            beta: f32,
+            _conj_dst: bool,
+            _conj_lhs: bool,
+            _conj_rhs: bool,
+            parallelism: gemm_common::Parallelism,
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, f32, _>(
+                Scalar,
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                read_dst,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                rhs_rs,
+                alpha,
+                beta,
+                &UKR,
+                parallelism,
+            )
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    mod neon {
+        use super::*;
+        use gemm_common::simd::MixedSimd;
+        use gemm_f32::microkernel::neon::f32::*;
+        const N: usize = 4;
+
+        #[inline(never)]
+        pub unsafe fn gemm_basic(
+            m: usize,
+            n: usize,
+            k: usize,
+            dst: *mut f32,
+            dst_cs: isize,
+            dst_rs: isize,
+            read_dst: bool,
+            lhs: *const T,
+            lhs_cs: isize,
+            lhs_rs: isize,
+            rhs: *const T,
+            rhs_cs: isize,
+            rhs_rs: isize,
+            alpha: f32,
+            beta: f32,
+            _conj_dst: bool,
+            _conj_lhs: bool,
+            _conj_rhs: bool,
+            parallelism: gemm_common::Parallelism,
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, f32, _>(
+                <gemm_common::simd::Neon as MixedSimd<T, T, f32, f32>>::try_new().unwrap(),
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                read_dst,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                rhs_rs,
+                alpha,
+                beta,
+                &UKR,
+                parallelism,
+            )
+        }
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    mod fma {
+        use super::*;
+        use gemm_common::simd::V3;
+        use gemm_f32::microkernel::fma::f32::*;
+        const N: usize = 8;
+
+        #[inline(never)]
+        pub unsafe fn gemm_basic(
+            m: usize,
+            n: usize,
+            k: usize,
+            dst: *mut f32,
+            dst_cs: isize,
+            dst_rs: isize,
+            read_dst: bool,
+            lhs: *const T,
+            lhs_cs: isize,
+            lhs_rs: isize,
+            rhs: *const T,
+            rhs_cs: isize,
+            rhs_rs: isize,
+            alpha: f32,
+            beta: f32,
+            _conj_dst: bool,
+            _conj_lhs: bool,
+            _conj_rhs: bool,
+            parallelism: gemm_common::Parallelism,
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, f32, _>(
+                V3::try_new().unwrap(),
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                read_dst,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                rhs_rs,
+                alpha,
+                beta,
+                &UKR,
+                parallelism,
+            )
+        }
+    }
+
+    #[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+    mod avx512f {
+        use super::*;
+        use gemm_common::simd::V4;
+        use gemm_f32::microkernel::avx512f::f32::*;
+        const N: usize = 16;
+
+        #[inline(never)]
+        pub unsafe fn gemm_basic(
+            m: usize,
+            n: usize,
+            k: usize,
+            dst: *mut f32,
+            dst_cs: isize,
+            dst_rs: isize,
+            read_dst: bool,
+            lhs: *const T,
+            lhs_cs: isize,
+            lhs_rs: isize,
+            rhs: *const T,
+            rhs_cs: isize,
+            rhs_rs: isize,
+            alpha: f32,
+            beta: f32,
+            _conj_dst: bool,
+            _conj_lhs: bool,
+            _conj_rhs: bool,
+            parallelism: gemm_common::Parallelism,
+        ) -> Result<(), AllocError> {
+            gemm_basic_generic::<N, { MR_DIV_N * N }, NR, MR_DIV_N, f32, _>(
+                V4::try_new().unwrap(),
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                read_dst,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                rhs_rs,
+                alpha,
+                beta,
+                &UKR,
+                parallelism,
+            )
         }
     }
 }
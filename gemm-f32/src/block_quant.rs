@@ -0,0 +1,123 @@
+use core::sync::atomic::{AtomicU8, Ordering};
+use gemm_common::{
+    block_quant::{gemm_basic_generic, QuantBlock},
+    gemm::AllocError,
+    Parallelism,
+};
+
+/// Microkernels used by [`gemm_basic`], selected once since the function is generic over the
+/// block format.
+#[derive(Copy, Clone)]
+#[repr(u8)]
+enum Arch {
+    Scalar,
+    #[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+    Avx512f,
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    Fma,
+    #[cfg(target_arch = "aarch64")]
+    Neon,
+    #[cfg(target_arch = "wasm32")]
+    Simd128,
+}
+
+impl Arch {
+    fn detect() -> Self {
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        {
+            #[cfg(feature = "nightly")]
+            if gemm_common::feature_detected!("avx512f") {
+                return Arch::Avx512f;
+            }
+            if gemm_common::feature_detected!("fma") {
+                return Arch::Fma;
+            }
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        if gemm_common::feature_detected!("neon") {
+            return Arch::Neon;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        if gemm_common::feature_detected!("simd128") {
+            return Arch::Simd128;
+        }
+
+        Arch::Scalar
+    }
+
+    #[inline]
+    fn get() -> Self {
+        static ARCH: AtomicU8 = AtomicU8::new(u8::MAX);
+        let mut arch = ARCH.load(Ordering::Relaxed);
+        if arch == u8::MAX {
+            arch = Self::detect() as u8;
+            ARCH.store(arch, Ordering::Relaxed);
+        }
+        // SAFETY: `arch` was stored by a previous call from a valid discriminant
+        unsafe { core::mem::transmute::<u8, Arch>(arch) }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, where `rhs` is block-quantized and dequantized while it is
+/// packed. See [`gemm_common::block_quant`] for the layout of `rhs`.
+///
+/// # Safety
+///
+/// `dst` and `lhs` must be valid for the given strides, and `rhs` must be valid for reads of `n`
+/// columns of `k / QK` blocks, `rhs_cs` blocks apart.
+pub unsafe fn gemm_basic<B: QuantBlock>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut f32,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const f32,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const B,
+    rhs_cs: isize,
+    alpha: f32,
+    beta: f32,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    macro_rules! dispatch {
+        ($module: ident, $N: expr, $simd: expr) => {{
+            use crate::microkernel::$module::f32::*;
+            return gemm_basic_generic::<{ $N }, { MR_DIV_N * $N }, NR, MR_DIV_N, B, _>(
+                $simd,
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                read_dst,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                alpha,
+                beta,
+                &UKR,
+                parallelism,
+            );
+        }};
+    }
+
+    match Arch::get() {
+        #[cfg(all(feature = "nightly", any(target_arch = "x86", target_arch = "x86_64")))]
+        Arch::Avx512f => dispatch!(avx512f, 16, gemm_common::simd::V4::try_new().unwrap()),
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        Arch::Fma => dispatch!(fma, 8, gemm_common::simd::V3::try_new().unwrap()),
+        #[cfg(target_arch = "aarch64")]
+        Arch::Neon => dispatch!(neon, 4, gemm_common::simd::Scalar),
+        #[cfg(target_arch = "wasm32")]
+        Arch::Simd128 => dispatch!(simd128, 4, gemm_common::simd::Scalar),
+        Arch::Scalar => dispatch!(scalar, 1, gemm_common::simd::Scalar),
+    }
+}
@@ -2,3 +2,240 @@ pub mod f32 {
     type T = f32;
     gemm_common::gemm_def!(f32, 2);
 }
+
+/// `f32` operands and destination, accumulated in `f32`.
+pub mod f32_f32 {
+    gemm_common::gemm_mixed_def!(
+        f32, f32, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f16` operands and destination, accumulated in `f32`.
+#[cfg(feature = "f16")]
+pub mod f16_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f16, f16, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `bf16` operands and destination, accumulated in `f32`.
+#[cfg(feature = "bf16")]
+pub mod bf16_bf16 {
+    use half::bf16;
+
+    gemm_common::gemm_mixed_def!(
+        bf16, bf16, bf16, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f8e4m3` operands with an `f32` destination, accumulated in `f32`.
+pub mod f8e4m3_f32 {
+    use gemm_common::f8::f8e4m3;
+
+    gemm_common::gemm_mixed_def!(
+        f8e4m3, f8e4m3, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f8e4m3` operands with an `f8e4m3` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e4m3_f8e4m3 {
+    use gemm_common::f8::f8e4m3;
+
+    gemm_common::gemm_mixed_def!(
+        f8e4m3, f8e4m3, f8e4m3, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e4m3` operands with an `f8e5m2` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e4m3_f8e5m2 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e4m3, f8e4m3, f8e5m2, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e4m3` lhs and `f8e5m2` rhs with an `f32` destination, accumulated in `f32`.
+pub mod f8e4m3_f8e5m2_f32 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e4m3, f8e5m2, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f8e4m3` lhs and `f8e5m2` rhs with an `f8e4m3` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e4m3_f8e5m2_f8e4m3 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e4m3, f8e5m2, f8e4m3, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e4m3` lhs and `f8e5m2` rhs with an `f8e5m2` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e4m3_f8e5m2_f8e5m2 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e4m3, f8e5m2, f8e5m2, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e5m2` lhs and `f8e4m3` rhs with an `f32` destination, accumulated in `f32`.
+pub mod f8e5m2_f8e4m3_f32 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e5m2, f8e4m3, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f8e5m2` lhs and `f8e4m3` rhs with an `f8e4m3` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e5m2_f8e4m3_f8e4m3 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e5m2, f8e4m3, f8e4m3, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e5m2` lhs and `f8e4m3` rhs with an `f8e5m2` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e5m2_f8e4m3_f8e5m2 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e5m2, f8e4m3, f8e5m2, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e5m2` operands with an `f32` destination, accumulated in `f32`.
+pub mod f8e5m2_f32 {
+    use gemm_common::f8::f8e5m2;
+
+    gemm_common::gemm_mixed_def!(
+        f8e5m2, f8e5m2, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f8e5m2` operands with an `f8e4m3` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e5m2_f8e4m3 {
+    use gemm_common::f8::{f8e4m3, f8e5m2};
+
+    gemm_common::gemm_mixed_def!(
+        f8e5m2, f8e5m2, f8e4m3, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f8e5m2` operands with an `f8e5m2` destination, accumulated in `f32`.
+/// `alpha` and `beta` are given in `f32`.
+pub mod f8e5m2_f8e5m2 {
+    use gemm_common::f8::f8e5m2;
+
+    gemm_common::gemm_mixed_def!(
+        f8e5m2, f8e5m2, f8e5m2, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4;
+        scale: f32,
+    );
+}
+
+/// `f32` lhs and `f16` rhs with an `f32` destination, accumulated in `f32`.
+#[cfg(feature = "f16")]
+pub mod f32_f16_f32 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f32, f16, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f16` lhs and `f32` rhs with an `f32` destination, accumulated in `f32`.
+#[cfg(feature = "f16")]
+pub mod f16_f32_f32 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f32, f32, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f32` lhs and `f32` rhs with an `f16` destination, accumulated in `f32`.
+#[cfg(feature = "f16")]
+pub mod f32_f32_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f32, f32, f16, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f32` lhs and `f16` rhs with an `f16` destination, accumulated in `f32`.
+#[cfg(feature = "f16")]
+pub mod f32_f16_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f32, f16, f16, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// `f16` lhs and `f32` rhs with an `f16` destination, accumulated in `f32`.
+#[cfg(feature = "f16")]
+pub mod f16_f32_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f32, f16, f32, f32, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// Min-plus semiring over `f32`.
+pub mod min_plus_f32 {
+    gemm_common::gemm_tropical_def!(
+        MinPlus, f32;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
+
+/// Max-plus semiring over `f32`.
+pub mod max_plus_f32 {
+    gemm_common::gemm_tropical_def!(
+        MaxPlus, f32;
+        scalar: 1, fma: 8, avx512f: 16, neon: 4,
+    );
+}
@@ -5,6 +5,8 @@
 )]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+#[cfg(feature = "f16")]
+pub mod block_quant;
 pub mod gemm;
 pub mod microkernel;
 
@@ -0,0 +1,70 @@
+use gemm_common::{abs::gemm_basic_generic, gemm::AllocError, Parallelism};
+
+/// dst := |lhs|×|rhs|, with the absolute values taken while the operands are packed.
+///
+/// # Safety
+///
+/// The matrices must be valid for the given dimensions and strides, and `dst` must not alias the
+/// operands.
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_basic(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut f64,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const f64,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const f64,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    macro_rules! dispatch {
+        ($module: ident, $N: expr, $simd: expr) => {{
+            use crate::microkernel::$module::f64::*;
+            return gemm_basic_generic::<{ $N }, { MR_DIV_N * $N }, NR, MR_DIV_N, _>(
+                $simd,
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                rhs_rs,
+                &UKR,
+                parallelism,
+            );
+        }};
+    }
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    {
+        #[cfg(feature = "nightly")]
+        if gemm_common::feature_detected!("avx512f") {
+            dispatch!(avx512f, 8, gemm_common::simd::V4::try_new().unwrap());
+        }
+        if gemm_common::feature_detected!("fma") {
+            dispatch!(fma, 4, gemm_common::simd::V3::try_new().unwrap());
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    if gemm_common::feature_detected!("neon") {
+        dispatch!(neon, 2, gemm_common::simd::Scalar);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    if gemm_common::feature_detected!("simd128") {
+        dispatch!(simd128, 2, gemm_common::simd::Scalar);
+    }
+
+    dispatch!(scalar, 1, gemm_common::simd::Scalar)
+}
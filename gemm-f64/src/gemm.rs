@@ -2,3 +2,215 @@ pub mod f64 {
     type T = f64;
     gemm_common::gemm_def!(f64, 1);
 }
+
+/// `f64` operands and destination, accumulated in `f64`.
+pub mod f64_f64 {
+    gemm_common::gemm_mixed_def!(
+        f64, f64, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f32` operands and destination, accumulated in `f64`.
+pub mod f32_f32 {
+    gemm_common::gemm_mixed_def!(
+        f32, f32, f32, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f32` operands with an `f64` destination.
+pub mod f32_f64 {
+    gemm_common::gemm_mixed_def!(
+        f32, f32, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f32` rhs with an `f64` destination, accumulated in `f64`.
+pub mod f64_f32_f64 {
+    gemm_common::gemm_mixed_def!(
+        f64, f32, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f32` lhs and `f64` rhs with an `f64` destination, accumulated in `f64`.
+pub mod f32_f64_f64 {
+    gemm_common::gemm_mixed_def!(
+        f32, f64, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f64` rhs with an `f32` destination, accumulated in `f64`.
+pub mod f64_f64_f32 {
+    gemm_common::gemm_mixed_def!(
+        f64, f64, f32, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f32` rhs with an `f32` destination, accumulated in `f64`.
+pub mod f64_f32_f32 {
+    gemm_common::gemm_mixed_def!(
+        f64, f32, f32, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f32` lhs and `f64` rhs with an `f32` destination, accumulated in `f64`.
+pub mod f32_f64_f32 {
+    gemm_common::gemm_mixed_def!(
+        f32, f64, f32, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f16` lhs and `f16` rhs with an `f64` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f16_f16_f64 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f16, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f16` lhs and `f64` rhs with an `f16` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f16_f64_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f64, f16, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f16` rhs with an `f16` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f64_f16_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f64, f16, f16, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f16` lhs and `f64` rhs with an `f64` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f16_f64_f64 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f64, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f16` rhs with an `f64` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f64_f16_f64 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f64, f16, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f64` rhs with an `f16` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f64_f64_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f64, f64, f16, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f16` lhs and `f32` rhs with an `f64` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f16_f32_f64 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f32, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f16` lhs and `f64` rhs with an `f32` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f16_f64_f32 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f16, f64, f32, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f32` lhs and `f16` rhs with an `f64` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f32_f16_f64 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f32, f16, f64, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f32` lhs and `f64` rhs with an `f16` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f32_f64_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f32, f64, f16, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f16` rhs with an `f32` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f64_f16_f32 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f64, f16, f32, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// `f64` lhs and `f32` rhs with an `f16` destination, accumulated in `f64`.
+#[cfg(feature = "f16")]
+pub mod f64_f32_f16 {
+    use half::f16;
+
+    gemm_common::gemm_mixed_def!(
+        f64, f32, f16, f64, f64, UKR, MR_DIV_N, NR;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// Min-plus semiring over `f64`.
+pub mod min_plus_f64 {
+    gemm_common::gemm_tropical_def!(
+        MinPlus, f64;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
+
+/// Max-plus semiring over `f64`.
+pub mod max_plus_f64 {
+    gemm_common::gemm_tropical_def!(
+        MaxPlus, f64;
+        scalar: 1, fma: 4, avx512f: 8, neon: 2,
+    );
+}
@@ -5,6 +5,7 @@
 )]
 #![cfg_attr(not(feature = "std"), no_std)]
 
+pub mod abs;
 pub mod gemm;
 pub mod microkernel;
 
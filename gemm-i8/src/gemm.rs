@@ -0,0 +1,352 @@
+/// `i8` operands with an `i32` destination.
+///
+/// Pairs of consecutive depth elements are packed as two `i16` in each `i32` of the packed
+/// panels, and multiplied and summed in one instruction by the `i8` microkernels (`pmaddwd`, or
+/// `vpdpwssd` with AVX-VNNI). The products are exact as long as the accumulated sums fit in an
+/// `i32`, which is always the case for `k <= 2^16`. The scaling by `alpha` and `beta` wraps on
+/// overflow.
+pub mod i8_i32 {
+    use gemm_common::{
+        cache::DivCeil,
+        driver::{self, Product},
+        gemm::AllocError,
+        microkernel::MicroKernelFn,
+        pack_operands::{pack_lhs_with_sums, pack_rhs_with_sums},
+        simd::Simd,
+        Parallelism, Ptr,
+    };
+
+    type GemmTy = unsafe fn(
+        usize,
+        usize,
+        usize,
+        *mut i32,
+        isize,
+        isize,
+        bool,
+        *const i8,
+        isize,
+        isize,
+        *const i8,
+        isize,
+        isize,
+        i32,
+        i32,
+        bool,
+        bool,
+        bool,
+        Parallelism,
+    ) -> Result<(), AllocError>;
+
+    /// dst := alpha×dst + beta×lhs×rhs, computed by [`driver::gemm_product_generic`].
+    struct Pairs<'a, const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, S> {
+        simd: S,
+        dst: Ptr<i32>,
+        dst_cs: isize,
+        dst_rs: isize,
+        lhs: Ptr<i8>,
+        lhs_cs: isize,
+        lhs_rs: isize,
+        rhs: Ptr<i8>,
+        rhs_cs: isize,
+        rhs_rs: isize,
+        alpha: i32,
+        beta: i32,
+        alpha_status: u8,
+        dispatcher: &'a [[MicroKernelFn<i32>; NR]; MR_DIV_N],
+    }
+
+    impl<const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, S: Simd> Product
+        for Pairs<'_, N, MR, NR, MR_DIV_N, S>
+    {
+        type Lhs = i32;
+        type Rhs = i32;
+        type Acc = i32;
+
+        const MR: usize = MR;
+        const NR: usize = NR;
+        // a pair never straddles two depth chunks
+        const KC_ALIGN: usize = 2;
+
+        #[inline]
+        fn lhs_panel_len(&self, k: usize) -> usize {
+            k.msrv_div_ceil(2) * MR
+        }
+
+        #[inline]
+        fn rhs_panel_len(&self, k: usize) -> usize {
+            k.msrv_div_ceil(2) * NR
+        }
+
+        #[inline(always)]
+        unsafe fn pack_lhs(
+            &self,
+            packed: *mut i32,
+            row: usize,
+            nrows: usize,
+            depth: usize,
+            k: usize,
+            _: bool,
+        ) {
+            pack_lhs_with_sums::<i8, MR, _>(
+                self.simd,
+                nrows,
+                k,
+                Ptr(packed),
+                self.lhs
+                    .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+                self.lhs_cs,
+                self.lhs_rs,
+                self.lhs_panel_len(k),
+                Ptr(core::ptr::null_mut()),
+            );
+        }
+
+        #[inline(always)]
+        unsafe fn pack_rhs(
+            &self,
+            packed: *mut i32,
+            col: usize,
+            ncols: usize,
+            depth: usize,
+            k: usize,
+            _: bool,
+        ) {
+            pack_rhs_with_sums::<i8, NR, _>(
+                self.simd,
+                ncols,
+                k,
+                Ptr(packed),
+                self.rhs
+                    .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+                self.rhs_cs,
+                self.rhs_rs,
+                self.rhs_panel_len(k),
+                Ptr(core::ptr::null_mut()),
+            );
+        }
+
+        #[inline(always)]
+        unsafe fn accumulate(
+            &self,
+            acc: *mut i32,
+            lhs: *const i32,
+            rhs: *const i32,
+            nrows: usize,
+            ncols: usize,
+            k: usize,
+            first: bool,
+        ) {
+            self.dispatcher[nrows.msrv_div_ceil(N) - 1][ncols - 1](
+                nrows,
+                ncols,
+                k.msrv_div_ceil(2),
+                acc,
+                lhs,
+                rhs,
+                MR as isize,
+                1,
+                MR as isize,
+                NR as isize,
+                1,
+                1,
+                self.beta,
+                if first { 0 } else { 1 },
+                false,
+                false,
+                false,
+                lhs.wrapping_add(self.lhs_panel_len(k)),
+            );
+        }
+
+        #[inline(always)]
+        unsafe fn store(
+            &self,
+            acc: *const i32,
+            row: usize,
+            col: usize,
+            nrows: usize,
+            ncols: usize,
+        ) {
+            for j in 0..ncols {
+                for i in 0..nrows {
+                    let acc = *acc.add(i + j * MR);
+                    let dst = self
+                        .dst
+                        .wrapping_offset(
+                            (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                        )
+                        .0;
+                    *dst = match self.alpha_status {
+                        0 => acc,
+                        1 => (*dst).wrapping_add(acc),
+                        _ => self.alpha.wrapping_mul(*dst).wrapping_add(acc),
+                    };
+                }
+            }
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[inline(always)]
+    unsafe fn gemm_basic_generic<
+        const N: usize,
+        const MR: usize,
+        const NR: usize,
+        const MR_DIV_N: usize,
+        S: Simd,
+    >(
+        simd: S,
+        dispatcher: &[[MicroKernelFn<i32>; NR]; MR_DIV_N],
+        m: usize,
+        n: usize,
+        k: usize,
+        dst: *mut i32,
+        dst_cs: isize,
+        dst_rs: isize,
+        read_dst: bool,
+        lhs: *const i8,
+        lhs_cs: isize,
+        lhs_rs: isize,
+        rhs: *const i8,
+        rhs_cs: isize,
+        rhs_rs: isize,
+        alpha: i32,
+        beta: i32,
+        parallelism: Parallelism,
+    ) -> Result<(), AllocError> {
+        let alpha = if read_dst { alpha } else { 0 };
+        let alpha_status = match alpha {
+            0 => 0,
+            1 => {
+                if k == 0 {
+                    return Ok(());
+                }
+                1
+            }
+            _ => 2,
+        };
+
+        driver::gemm_product_generic(
+            &Pairs::<N, MR, NR, MR_DIV_N, S> {
+                simd,
+                dst: Ptr(dst),
+                dst_cs,
+                dst_rs,
+                lhs: Ptr(lhs as *mut i8),
+                lhs_cs,
+                lhs_rs,
+                rhs: Ptr(rhs as *mut i8),
+                rhs_cs,
+                rhs_rs,
+                alpha,
+                beta,
+                alpha_status,
+                dispatcher,
+            },
+            m,
+            n,
+            k,
+            parallelism,
+        )
+    }
+
+    macro_rules! gemm_basic {
+        ($arch: ident, $n: expr, $simd: expr) => {
+            mod $arch {
+                use super::*;
+                use crate::microkernel::$arch::i8::*;
+
+                #[inline(never)]
+                #[allow(clippy::too_many_arguments)]
+                pub unsafe fn gemm_basic(
+                    m: usize,
+                    n: usize,
+                    k: usize,
+                    dst: *mut i32,
+                    dst_cs: isize,
+                    dst_rs: isize,
+                    read_dst: bool,
+                    lhs: *const i8,
+                    lhs_cs: isize,
+                    lhs_rs: isize,
+                    rhs: *const i8,
+                    rhs_cs: isize,
+                    rhs_rs: isize,
+                    alpha: i32,
+                    beta: i32,
+                    _conj_dst: bool,
+                    _conj_lhs: bool,
+                    _conj_rhs: bool,
+                    parallelism: Parallelism,
+                ) -> Result<(), AllocError> {
+                    gemm_basic_generic::<$n, { MR_DIV_N * $n }, NR, MR_DIV_N, _>(
+                        $simd,
+                        &UKR,
+                        m,
+                        n,
+                        k,
+                        dst,
+                        dst_cs,
+                        dst_rs,
+                        read_dst,
+                        lhs,
+                        lhs_cs,
+                        lhs_rs,
+                        rhs,
+                        rhs_cs,
+                        rhs_rs,
+                        alpha,
+                        beta,
+                        parallelism,
+                    )
+                }
+            }
+        };
+    }
+
+    gemm_basic!(scalar, 1, gemm_common::simd::Scalar);
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    gemm_basic!(fma, 8, gemm_common::simd::V3::try_new().unwrap());
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    gemm_basic!(avxvnni, 8, gemm_common::simd::V3::try_new().unwrap());
+    #[cfg(target_arch = "aarch64")]
+    gemm_basic!(
+        neon,
+        4,
+        <gemm_common::simd::Neon as gemm_common::simd::MixedSimd<i8, i8, i32, i32>>::try_new()
+            .unwrap()
+    );
+
+    fn init_gemm_fn() -> GemmTy {
+        // both x86 paths run on `V3`
+        #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+        if gemm_common::simd::V3::try_new().is_some() {
+            if gemm_common::feature_detected!("avxvnni") {
+                return avxvnni::gemm_basic;
+            }
+            return fma::gemm_basic;
+        }
+
+        #[cfg(target_arch = "aarch64")]
+        if gemm_common::feature_detected!("neon") {
+            return neon::gemm_basic;
+        }
+
+        scalar::gemm_basic
+    }
+
+    static GEMM_PTR: ::core::sync::atomic::AtomicPtr<()> =
+        ::core::sync::atomic::AtomicPtr::new(::core::ptr::null_mut());
+
+    #[inline(always)]
+    pub fn get_gemm_fn() -> GemmTy {
+        let mut gemm_fn = GEMM_PTR.load(::core::sync::atomic::Ordering::Relaxed);
+        if gemm_fn.is_null() {
+            let init = init_gemm_fn();
+            GEMM_PTR.store(init as *mut (), ::core::sync::atomic::Ordering::Relaxed);
+            gemm_fn = init as *mut ();
+        }
+        unsafe { ::core::mem::transmute::<*mut (), GemmTy>(gemm_fn) }
+    }
+}
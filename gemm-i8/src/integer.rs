@@ -0,0 +1,684 @@
+//! `i32` and `i64` products, computed either with wrapping arithmetic, or with checks for
+//! overflow.
+
+use core::sync::atomic::{AtomicBool, Ordering};
+use gemm_common::{
+    cache::DivCeil,
+    driver::{self, Product},
+    gemm::AllocError,
+    microkernel::MicroKernelFn,
+    pack_operands::{pack_lhs, pack_rhs},
+    simd::{Scalar, Simd},
+    Parallelism, Ptr,
+};
+
+/// Integer types that [`gemm_wrapping`] and [`gemm_checked`] can multiply, implemented for `i32`
+/// and `i64`.
+pub trait IntScalar: Copy + Eq + num_traits::Zero + Send + Sync + 'static {
+    #[doc(hidden)]
+    const ZERO: Self;
+    #[doc(hidden)]
+    const ONE: Self;
+
+    #[doc(hidden)]
+    fn wrapping_mul_add(a: Self, b: Self, c: Self) -> Self;
+    /// Returns `(a × b + c, overflowed)`.
+    #[doc(hidden)]
+    fn overflowing_mul_add(a: Self, b: Self, c: Self) -> (Self, bool);
+
+    #[allow(clippy::too_many_arguments)]
+    #[doc(hidden)]
+    unsafe fn gemm_wrapping(
+        m: usize,
+        n: usize,
+        k: usize,
+        dst: *mut Self,
+        dst_cs: isize,
+        dst_rs: isize,
+        read_dst: bool,
+        lhs: *const Self,
+        lhs_cs: isize,
+        lhs_rs: isize,
+        rhs: *const Self,
+        rhs_cs: isize,
+        rhs_rs: isize,
+        alpha: Self,
+        beta: Self,
+        parallelism: Parallelism,
+    ) -> Result<(), AllocError>;
+}
+
+macro_rules! impl_int_scalar {
+    ($ty: ident, fma: $fma_n: expr, neon: $neon_n: expr) => {
+        impl IntScalar for $ty {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            #[inline(always)]
+            fn wrapping_mul_add(a: Self, b: Self, c: Self) -> Self {
+                a.wrapping_mul(b).wrapping_add(c)
+            }
+
+            #[inline(always)]
+            fn overflowing_mul_add(a: Self, b: Self, c: Self) -> (Self, bool) {
+                let (ab, mul_overflow) = a.overflowing_mul(b);
+                let (abc, add_overflow) = ab.overflowing_add(c);
+                (abc, mul_overflow | add_overflow)
+            }
+
+            unsafe fn gemm_wrapping(
+                m: usize,
+                n: usize,
+                k: usize,
+                dst: *mut Self,
+                dst_cs: isize,
+                dst_rs: isize,
+                read_dst: bool,
+                lhs: *const Self,
+                lhs_cs: isize,
+                lhs_rs: isize,
+                rhs: *const Self,
+                rhs_cs: isize,
+                rhs_rs: isize,
+                alpha: Self,
+                beta: Self,
+                parallelism: Parallelism,
+            ) -> Result<(), AllocError> {
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+                if gemm_common::feature_detected!("fma") && gemm_common::feature_detected!("avx2") {
+                    use crate::microkernel::fma::$ty::*;
+                    return gemm_wrapping_generic::<
+                        $ty,
+                        $fma_n,
+                        { MR_DIV_N * $fma_n },
+                        NR,
+                        MR_DIV_N,
+                        _,
+                    >(
+                        gemm_common::simd::V3::try_new().unwrap(),
+                        &UKR,
+                        m,
+                        n,
+                        k,
+                        dst,
+                        dst_cs,
+                        dst_rs,
+                        read_dst,
+                        lhs,
+                        lhs_cs,
+                        lhs_rs,
+                        rhs,
+                        rhs_cs,
+                        rhs_rs,
+                        alpha,
+                        beta,
+                        parallelism,
+                    );
+                }
+
+                #[cfg(target_arch = "aarch64")]
+                if gemm_common::feature_detected!("neon") {
+                    use crate::microkernel::neon::$ty::*;
+                    return gemm_wrapping_generic::<
+                        $ty,
+                        $neon_n,
+                        { MR_DIV_N * $neon_n },
+                        NR,
+                        MR_DIV_N,
+                        _,
+                    >(
+                        <gemm_common::simd::Neon as gemm_common::simd::MixedSimd<
+                            i8,
+                            i8,
+                            i32,
+                            i32,
+                        >>::try_new()
+                        .unwrap(),
+                        &UKR,
+                        m,
+                        n,
+                        k,
+                        dst,
+                        dst_cs,
+                        dst_rs,
+                        read_dst,
+                        lhs,
+                        lhs_cs,
+                        lhs_rs,
+                        rhs,
+                        rhs_cs,
+                        rhs_rs,
+                        alpha,
+                        beta,
+                        parallelism,
+                    );
+                }
+
+                use crate::microkernel::scalar::$ty::*;
+                gemm_wrapping_generic::<$ty, 1, { MR_DIV_N }, NR, MR_DIV_N, _>(
+                    Scalar,
+                    &UKR,
+                    m,
+                    n,
+                    k,
+                    dst,
+                    dst_cs,
+                    dst_rs,
+                    read_dst,
+                    lhs,
+                    lhs_cs,
+                    lhs_rs,
+                    rhs,
+                    rhs_cs,
+                    rhs_rs,
+                    alpha,
+                    beta,
+                    parallelism,
+                )
+            }
+        }
+    };
+}
+
+impl_int_scalar!(i32, fma: 8, neon: 4);
+impl_int_scalar!(i64, fma: 4, neon: 2);
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// All the operations wrap on overflow, so the result is the exact one modulo `2^T::BITS`, and is
+/// exact as long as it fits in a `T`.
+///
+/// # Safety
+///
+/// The matrices must be valid for the given dimensions and strides, and `dst` must not overlap
+/// `lhs` or `rhs`.
+#[allow(clippy::too_many_arguments)]
+#[inline]
+pub unsafe fn gemm_wrapping<T: IntScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    T::gemm_wrapping(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        parallelism,
+    )
+}
+
+/// dst := alpha×dst + beta×lhs×rhs with wrapping arithmetic, computed by
+/// [`driver::gemm_product_generic`].
+struct Wrapping<'a, T, const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, S> {
+    simd: S,
+    dst: Ptr<T>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<T>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<T>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    alpha_status: u8,
+    dispatcher: &'a [[MicroKernelFn<T>; NR]; MR_DIV_N],
+}
+
+impl<
+        T: IntScalar,
+        const N: usize,
+        const MR: usize,
+        const NR: usize,
+        const MR_DIV_N: usize,
+        S: Simd,
+    > Product for Wrapping<'_, T, N, MR, NR, MR_DIV_N, S>
+{
+    type Lhs = T;
+    type Rhs = T;
+    type Acc = T;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut T,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs::<T, N, MR, _>(
+            self.simd,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut T,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs::<T, 1, NR, _>(
+            self.simd,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs,
+            self.rhs_rs,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut T,
+        lhs: *const T,
+        rhs: *const T,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        self.dispatcher[nrows.msrv_div_ceil(N) - 1][ncols - 1](
+            nrows,
+            ncols,
+            k,
+            acc,
+            lhs,
+            rhs,
+            MR as isize,
+            1,
+            MR as isize,
+            NR as isize,
+            1,
+            T::ONE,
+            self.beta,
+            if first { 0 } else { 1 },
+            false,
+            false,
+            false,
+            lhs.wrapping_add(self.lhs_panel_len(k)),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const T, row: usize, col: usize, nrows: usize, ncols: usize) {
+        for j in 0..ncols {
+            for i in 0..nrows {
+                let acc = *acc.add(i + j * MR);
+                let dst = self
+                    .dst
+                    .wrapping_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0;
+                *dst = match self.alpha_status {
+                    0 => acc,
+                    1 => T::wrapping_mul_add(T::ONE, *dst, acc),
+                    _ => T::wrapping_mul_add(self.alpha, *dst, acc),
+                };
+            }
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+#[inline(always)]
+unsafe fn gemm_wrapping_generic<
+    T: IntScalar,
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    S: Simd,
+>(
+    simd: S,
+    dispatcher: &[[MicroKernelFn<T>; NR]; MR_DIV_N],
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    let alpha = if read_dst { alpha } else { T::ZERO };
+    let alpha_status = if alpha == T::ZERO {
+        0
+    } else if alpha == T::ONE {
+        if k == 0 {
+            return Ok(());
+        }
+        1
+    } else {
+        2
+    };
+
+    driver::gemm_product_generic(
+        &Wrapping::<T, N, MR, NR, MR_DIV_N, S> {
+            simd,
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut T),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut T),
+            rhs_cs,
+            rhs_rs,
+            alpha,
+            beta,
+            alpha_status,
+            dispatcher,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
+
+/// Register block of the checked microkernel.
+const CHECKED_MR: usize = 4;
+const CHECKED_NR: usize = 4;
+
+type CheckedKernelFn<T> = unsafe fn(usize, *mut T, *const T, *const T, usize, usize, bool) -> bool;
+
+/// Adds the products of a depth block to a full `MR × NR` block of accumulators, stored in
+/// column-major order with a column stride of `MR`, starting from zero if `first` is set. Returns
+/// whether a product or a partial sum of the first `nrows` rows and `ncols` columns overflowed.
+#[inline(always)]
+unsafe fn checked_kernel<T: IntScalar>(
+    k: usize,
+    acc: *mut T,
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    nrows: usize,
+    ncols: usize,
+    first: bool,
+) -> bool {
+    const MR: usize = CHECKED_MR;
+    const NR: usize = CHECKED_NR;
+
+    let mut local = [[T::ZERO; MR]; NR];
+    // one flag per lane, so that the loop can be vectorized
+    let mut overflow = [[false; MR]; NR];
+    if !first {
+        for (j, local) in local.iter_mut().enumerate() {
+            for (i, local) in local.iter_mut().enumerate() {
+                *local = *acc.add(i + j * MR);
+            }
+        }
+    }
+
+    for depth in 0..k {
+        let lhs: [T; MR] = core::array::from_fn(|i| *packed_lhs.add(depth * MR + i));
+        for ((local, overflow), j) in local.iter_mut().zip(&mut overflow).zip(0..NR) {
+            let rhs = *packed_rhs.add(depth * NR + j);
+            for ((local, overflow), &lhs) in local.iter_mut().zip(overflow).zip(&lhs) {
+                let (sum, o) = T::overflowing_mul_add(lhs, rhs, *local);
+                *local = sum;
+                *overflow |= o;
+            }
+        }
+    }
+
+    for (j, local) in local.iter().enumerate() {
+        for (i, &local) in local.iter().enumerate() {
+            *acc.add(i + j * MR) = local;
+        }
+    }
+
+    // the padding lanes do not belong to the product
+    overflow[..ncols]
+        .iter()
+        .any(|overflow| overflow[..nrows].iter().any(|&o| o))
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[target_feature(enable = "avx2")]
+unsafe fn checked_kernel_avx2<T: IntScalar>(
+    k: usize,
+    acc: *mut T,
+    packed_lhs: *const T,
+    packed_rhs: *const T,
+    nrows: usize,
+    ncols: usize,
+    first: bool,
+) -> bool {
+    checked_kernel::<T>(k, acc, packed_lhs, packed_rhs, nrows, ncols, first)
+}
+
+/// dst := alpha×dst + beta×lhs×rhs with checks for overflow, computed by
+/// [`driver::gemm_product_generic`]. Once an overflow is found, the remaining tiles are skipped.
+struct Checked<T> {
+    dst: Ptr<T>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<T>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<T>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    read_dst: bool,
+    kernel: CheckedKernelFn<T>,
+    overflowed: AtomicBool,
+}
+
+impl<T: IntScalar> Product for Checked<T> {
+    type Lhs = T;
+    type Rhs = T;
+    type Acc = T;
+
+    const MR: usize = CHECKED_MR;
+    const NR: usize = CHECKED_NR;
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut T,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_lhs::<T, 1, CHECKED_MR, _>(
+            Scalar,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut T,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        _: bool,
+    ) {
+        pack_rhs::<T, 1, CHECKED_NR, _>(
+            Scalar,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs,
+            self.rhs_rs,
+            self.rhs_panel_len(k),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut T,
+        lhs: *const T,
+        rhs: *const T,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        if self.overflowed.load(Ordering::Relaxed) {
+            return;
+        }
+        if (self.kernel)(k, acc, lhs, rhs, nrows, ncols, first) {
+            self.overflowed.store(true, Ordering::Relaxed);
+        }
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const T, row: usize, col: usize, nrows: usize, ncols: usize) {
+        if self.overflowed.load(Ordering::Relaxed) {
+            return;
+        }
+        for j in 0..ncols {
+            for i in 0..nrows {
+                let dst = self
+                    .dst
+                    .wrapping_offset(
+                        (row + i) as isize * self.dst_rs + (col + j) as isize * self.dst_cs,
+                    )
+                    .0;
+                let (value, o0) =
+                    T::overflowing_mul_add(self.beta, *acc.add(i + j * CHECKED_MR), T::ZERO);
+                let (value, o1) = if self.read_dst {
+                    T::overflowing_mul_add(self.alpha, *dst, value)
+                } else {
+                    (value, false)
+                };
+                if o0 | o1 {
+                    self.overflowed.store(true, Ordering::Relaxed);
+                    return;
+                }
+                *dst = value;
+            }
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm_wrapping`], and returns `Ok(true)` if an intermediate value overflowed, in which
+/// case the contents of `dst` are unspecified.
+///
+/// Each element is computed as `alpha × dst + beta × sum`, where `sum` is accumulated over the
+/// depth in increasing order, and the intermediate values are the products, the partial sums, and
+/// the terms of the final combination. The product `alpha × dst` is skipped if `read_dst` is not
+/// set.
+///
+/// # Safety
+///
+/// Same as [`gemm_wrapping`].
+#[allow(clippy::too_many_arguments)]
+pub unsafe fn gemm_checked<T: IntScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) -> Result<bool, AllocError> {
+    let mut kernel: CheckedKernelFn<T> = checked_kernel::<T>;
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if gemm_common::feature_detected!("avx2") {
+        kernel = checked_kernel_avx2::<T>;
+    }
+
+    let product = Checked {
+        dst: Ptr(dst),
+        dst_cs,
+        dst_rs,
+        lhs: Ptr(lhs as *mut T),
+        lhs_cs,
+        lhs_rs,
+        rhs: Ptr(rhs as *mut T),
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        read_dst,
+        kernel,
+        overflowed: AtomicBool::new(false),
+    };
+    driver::gemm_product_generic(&product, m, n, k, parallelism)?;
+    Ok(product.overflowed.into_inner())
+}
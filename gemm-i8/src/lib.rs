@@ -0,0 +1,9 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+pub mod gemm;
+pub mod integer;
+pub mod microkernel;
+pub mod quantized;
+
+#[macro_use]
+extern crate gemm_common;
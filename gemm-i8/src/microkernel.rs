@@ -0,0 +1,750 @@
+// the integer kernels wrap on overflow, like the SIMD instructions they are built on
+
+pub mod scalar {
+    pub mod i32 {
+        type T = i32;
+        const N: usize = 1;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_mul(rhs[0])]
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_add(rhs[0])]
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(, 2, x1x1, 1, 1);
+        microkernel!(, 2, x1x2, 1, 2);
+        microkernel!(, 2, x1x3, 1, 3);
+        microkernel!(, 2, x1x4, 1, 4);
+
+        microkernel!(, 2, x2x1, 2, 1);
+        microkernel!(, 2, x2x2, 2, 2);
+        microkernel!(, 2, x2x3, 2, 3);
+        microkernel!(, 2, x2x4, 2, 4);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4,],
+            [x2x1, x2x2, x2x3, x2x4,],
+        }
+    }
+
+    pub mod i64 {
+        type T = i64;
+        const N: usize = 1;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_mul(rhs[0])]
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_add(rhs[0])]
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(, 2, x1x1, 1, 1);
+        microkernel!(, 2, x1x2, 1, 2);
+        microkernel!(, 2, x1x3, 1, 3);
+        microkernel!(, 2, x1x4, 1, 4);
+
+        microkernel!(, 2, x2x1, 2, 1);
+        microkernel!(, 2, x2x2, 2, 2);
+        microkernel!(, 2, x2x3, 2, 3);
+        microkernel!(, 2, x2x4, 2, 4);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4,],
+            [x2x1, x2x2, x2x3, x2x4,],
+        }
+    }
+
+    // each packed `i32` holds two consecutive depth elements as a pair of `i16`
+    pub mod i8 {
+        use core::mem::transmute;
+
+        type T = i32;
+        const N: usize = 1;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            [value]
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_mul(rhs[0])]
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_add(rhs[0])]
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        unsafe fn dot(lhs: Pack, rhs: Pack, acc: Pack) -> Pack {
+            let [a0, a1]: [i16; 2] = transmute(lhs[0]);
+            let [b0, b1]: [i16; 2] = transmute(rhs[0]);
+            [acc[0].wrapping_add(a0 as i32 * b0 as i32 + a1 as i32 * b1 as i32)]
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(dot: dot; , 2, x1x1, 1, 1);
+        microkernel!(dot: dot; , 2, x1x2, 1, 2);
+        microkernel!(dot: dot; , 2, x1x3, 1, 3);
+        microkernel!(dot: dot; , 2, x1x4, 1, 4);
+
+        microkernel!(dot: dot; , 2, x2x1, 2, 1);
+        microkernel!(dot: dot; , 2, x2x2, 2, 2);
+        microkernel!(dot: dot; , 2, x2x3, 2, 3);
+        microkernel!(dot: dot; , 2, x2x4, 2, 4);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4,],
+            [x2x1, x2x2, x2x3, x2x4,],
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod fma {
+    pub mod i32 {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+        use core::mem::transmute;
+
+        type T = i32;
+        const N: usize = 8;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_set1_epi32(value))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_mullo_epi32(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_add_epi32(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(["avx2"], 2, x1x1, 1, 1);
+        microkernel!(["avx2"], 2, x1x2, 1, 2);
+        microkernel!(["avx2"], 2, x1x3, 1, 3);
+        microkernel!(["avx2"], 2, x1x4, 1, 4);
+        microkernel!(["avx2"], 2, x1x5, 1, 5);
+        microkernel!(["avx2"], 2, x1x6, 1, 6);
+
+        microkernel!(["avx2"], 2, x2x1, 2, 1);
+        microkernel!(["avx2"], 2, x2x2, 2, 2);
+        microkernel!(["avx2"], 2, x2x3, 2, 3);
+        microkernel!(["avx2"], 2, x2x4, 2, 4);
+        microkernel!(["avx2"], 2, x2x5, 2, 5);
+        microkernel!(["avx2"], 2, x2x6, 2, 6);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, x1x5, x1x6,],
+            [x2x1, x2x2, x2x3, x2x4, x2x5, x2x6,],
+        }
+    }
+
+    pub mod i64 {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+        use core::mem::transmute;
+
+        type T = i64;
+        const N: usize = 4;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_set1_epi64x(value))
+        }
+
+        // avx2 has no 64-bit multiplication, so the low half of the product is assembled from
+        // 32-bit products: lo(a) lo(b) + ((hi(a) lo(b) + lo(a) hi(b)) << 32)
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            let (lhs, rhs): (__m256i, __m256i) = (
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            );
+            let lo = _mm256_mul_epu32(lhs, rhs);
+            let cross = _mm256_add_epi64(
+                _mm256_mul_epu32(_mm256_srli_epi64::<32>(lhs), rhs),
+                _mm256_mul_epu32(lhs, _mm256_srli_epi64::<32>(rhs)),
+            );
+            transmute::<__m256i, Pack>(_mm256_add_epi64(lo, _mm256_slli_epi64::<32>(cross)))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_add_epi64(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(["avx2"], 2, x1x1, 1, 1);
+        microkernel!(["avx2"], 2, x1x2, 1, 2);
+        microkernel!(["avx2"], 2, x1x3, 1, 3);
+        microkernel!(["avx2"], 2, x1x4, 1, 4);
+        microkernel!(["avx2"], 2, x1x5, 1, 5);
+        microkernel!(["avx2"], 2, x1x6, 1, 6);
+
+        microkernel!(["avx2"], 2, x2x1, 2, 1);
+        microkernel!(["avx2"], 2, x2x2, 2, 2);
+        microkernel!(["avx2"], 2, x2x3, 2, 3);
+        microkernel!(["avx2"], 2, x2x4, 2, 4);
+        microkernel!(["avx2"], 2, x2x5, 2, 5);
+        microkernel!(["avx2"], 2, x2x6, 2, 6);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, x1x5, x1x6,],
+            [x2x1, x2x2, x2x3, x2x4, x2x5, x2x6,],
+        }
+    }
+
+    // each packed `i32` holds two consecutive depth elements as a pair of `i16`, which are
+    // multiplied and summed with `pmaddwd`. `pmaddubsw` is not used since it saturates
+    pub mod i8 {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+        use core::mem::transmute;
+
+        type T = i32;
+        const N: usize = 8;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_set1_epi32(value))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_mullo_epi32(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_add_epi32(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        unsafe fn dot(lhs: Pack, rhs: Pack, acc: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_add_epi32(
+                transmute::<Pack, __m256i>(acc),
+                _mm256_madd_epi16(
+                    transmute::<Pack, __m256i>(lhs),
+                    transmute::<Pack, __m256i>(rhs),
+                ),
+            ))
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(dot: dot; ["avx2"], 2, x1x1, 1, 1);
+        microkernel!(dot: dot; ["avx2"], 2, x1x2, 1, 2);
+        microkernel!(dot: dot; ["avx2"], 2, x1x3, 1, 3);
+        microkernel!(dot: dot; ["avx2"], 2, x1x4, 1, 4);
+        microkernel!(dot: dot; ["avx2"], 2, x1x5, 1, 5);
+        microkernel!(dot: dot; ["avx2"], 2, x1x6, 1, 6);
+
+        microkernel!(dot: dot; ["avx2"], 2, x2x1, 2, 1);
+        microkernel!(dot: dot; ["avx2"], 2, x2x2, 2, 2);
+        microkernel!(dot: dot; ["avx2"], 2, x2x3, 2, 3);
+        microkernel!(dot: dot; ["avx2"], 2, x2x4, 2, 4);
+        microkernel!(dot: dot; ["avx2"], 2, x2x5, 2, 5);
+        microkernel!(dot: dot; ["avx2"], 2, x2x6, 2, 6);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, x1x5, x1x6,],
+            [x2x1, x2x2, x2x3, x2x4, x2x5, x2x6,],
+        }
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+pub mod avxvnni {
+    // same layout as `fma::i8`, with the multiplication and the accumulation fused by
+    // `vpdpwssd`
+    pub mod i8 {
+        #[cfg(target_arch = "x86")]
+        use core::arch::x86::*;
+        #[cfg(target_arch = "x86_64")]
+        use core::arch::x86_64::*;
+        use core::mem::transmute;
+
+        type T = i32;
+        const N: usize = 8;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_set1_epi32(value))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_mullo_epi32(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_add_epi32(
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        unsafe fn dot(lhs: Pack, rhs: Pack, acc: Pack) -> Pack {
+            transmute::<__m256i, Pack>(_mm256_dpwssd_avx_epi32(
+                transmute::<Pack, __m256i>(acc),
+                transmute::<Pack, __m256i>(lhs),
+                transmute::<Pack, __m256i>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x1x1, 1, 1);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x1x2, 1, 2);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x1x3, 1, 3);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x1x4, 1, 4);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x1x5, 1, 5);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x1x6, 1, 6);
+
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x2x1, 2, 1);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x2x2, 2, 2);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x2x3, 2, 3);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x2x4, 2, 4);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x2x5, 2, 5);
+        microkernel!(dot: dot; ["avx2,avxvnni"], 2, x2x6, 2, 6);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, x1x5, x1x6,],
+            [x2x1, x2x2, x2x3, x2x4, x2x5, x2x6,],
+        }
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+pub mod neon {
+    pub mod i32 {
+        use core::arch::aarch64::*;
+        use core::mem::transmute;
+
+        type T = i32;
+        const N: usize = 4;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<int32x4_t, Pack>(vdupq_n_s32(value))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<int32x4_t, Pack>(vmulq_s32(
+                transmute::<Pack, int32x4_t>(lhs),
+                transmute::<Pack, int32x4_t>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<int32x4_t, Pack>(vaddq_s32(
+                transmute::<Pack, int32x4_t>(lhs),
+                transmute::<Pack, int32x4_t>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            transmute::<int32x4_t, Pack>(vmlaq_s32(
+                transmute::<Pack, int32x4_t>(c),
+                transmute::<Pack, int32x4_t>(a),
+                transmute::<Pack, int32x4_t>(b),
+            ))
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(["neon"], 4, x1x1, 1, 1);
+        microkernel!(["neon"], 4, x1x2, 1, 2);
+        microkernel!(["neon"], 4, x1x3, 1, 3);
+        microkernel!(["neon"], 4, x1x4, 1, 4);
+
+        microkernel!(["neon"], 4, x2x1, 2, 1);
+        microkernel!(["neon"], 4, x2x2, 2, 2);
+        microkernel!(["neon"], 4, x2x3, 2, 3);
+        microkernel!(["neon"], 4, x2x4, 2, 4);
+
+        microkernel!(["neon"], 4, x3x1, 3, 1);
+        microkernel!(["neon"], 4, x3x2, 3, 2);
+        microkernel!(["neon"], 4, x3x3, 3, 3);
+        microkernel!(["neon"], 4, x3x4, 3, 4);
+
+        microkernel!(["neon"], 4, x4x1, 4, 1);
+        microkernel!(["neon"], 4, x4x2, 4, 2);
+        microkernel!(["neon"], 4, x4x3, 4, 3);
+        microkernel!(["neon"], 4, x4x4, 4, 4);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, ],
+            [x2x1, x2x2, x2x3, x2x4, ],
+            [x3x1, x3x2, x3x3, x3x4, ],
+            [x4x1, x4x2, x4x3, x4x4, ],
+        }
+    }
+
+    pub mod i64 {
+        use core::arch::aarch64::*;
+        use core::mem::transmute;
+
+        type T = i64;
+        const N: usize = 2;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<int64x2_t, Pack>(vdupq_n_s64(value))
+        }
+
+        // neon has no 64-bit multiplication
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            [lhs[0].wrapping_mul(rhs[0]), lhs[1].wrapping_mul(rhs[1])]
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<int64x2_t, Pack>(vaddq_s64(
+                transmute::<Pack, int64x2_t>(lhs),
+                transmute::<Pack, int64x2_t>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            add(mul(a, b), c)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(["neon"], 4, x1x1, 1, 1);
+        microkernel!(["neon"], 4, x1x2, 1, 2);
+        microkernel!(["neon"], 4, x1x3, 1, 3);
+        microkernel!(["neon"], 4, x1x4, 1, 4);
+
+        microkernel!(["neon"], 4, x2x1, 2, 1);
+        microkernel!(["neon"], 4, x2x2, 2, 2);
+        microkernel!(["neon"], 4, x2x3, 2, 3);
+        microkernel!(["neon"], 4, x2x4, 2, 4);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, ],
+            [x2x1, x2x2, x2x3, x2x4, ],
+        }
+    }
+
+    // each packed `i32` holds two consecutive depth elements as a pair of `i16`
+    pub mod i8 {
+        use core::arch::aarch64::*;
+        use core::mem::transmute;
+
+        type T = i32;
+        const N: usize = 4;
+        type Pack = [T; N];
+
+        #[inline(always)]
+        unsafe fn splat(value: T) -> Pack {
+            transmute::<int32x4_t, Pack>(vdupq_n_s32(value))
+        }
+
+        #[inline(always)]
+        unsafe fn mul(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<int32x4_t, Pack>(vmulq_s32(
+                transmute::<Pack, int32x4_t>(lhs),
+                transmute::<Pack, int32x4_t>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn add(lhs: Pack, rhs: Pack) -> Pack {
+            transmute::<int32x4_t, Pack>(vaddq_s32(
+                transmute::<Pack, int32x4_t>(lhs),
+                transmute::<Pack, int32x4_t>(rhs),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn mul_add(a: Pack, b: Pack, c: Pack) -> Pack {
+            transmute::<int32x4_t, Pack>(vmlaq_s32(
+                transmute::<Pack, int32x4_t>(c),
+                transmute::<Pack, int32x4_t>(a),
+                transmute::<Pack, int32x4_t>(b),
+            ))
+        }
+
+        #[inline(always)]
+        unsafe fn dot(lhs: Pack, rhs: Pack, acc: Pack) -> Pack {
+            let (lhs, rhs): (int16x8_t, int16x8_t) = (
+                transmute::<Pack, int16x8_t>(lhs),
+                transmute::<Pack, int16x8_t>(rhs),
+            );
+            let lo = vmull_s16(vget_low_s16(lhs), vget_low_s16(rhs));
+            let hi = vmull_high_s16(lhs, rhs);
+            transmute::<int32x4_t, Pack>(vaddq_s32(
+                transmute::<Pack, int32x4_t>(acc),
+                vpaddq_s32(lo, hi),
+            ))
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul(lhs: T, rhs: T) -> T {
+            lhs.wrapping_mul(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_add(lhs: T, rhs: T) -> T {
+            lhs.wrapping_add(rhs)
+        }
+
+        #[inline(always)]
+        pub unsafe fn scalar_mul_add(a: T, b: T, c: T) -> T {
+            a.wrapping_mul(b).wrapping_add(c)
+        }
+
+        microkernel!(dot: dot; ["neon"], 4, x1x1, 1, 1);
+        microkernel!(dot: dot; ["neon"], 4, x1x2, 1, 2);
+        microkernel!(dot: dot; ["neon"], 4, x1x3, 1, 3);
+        microkernel!(dot: dot; ["neon"], 4, x1x4, 1, 4);
+
+        microkernel!(dot: dot; ["neon"], 4, x2x1, 2, 1);
+        microkernel!(dot: dot; ["neon"], 4, x2x2, 2, 2);
+        microkernel!(dot: dot; ["neon"], 4, x2x3, 2, 3);
+        microkernel!(dot: dot; ["neon"], 4, x2x4, 2, 4);
+
+        microkernel!(dot: dot; ["neon"], 4, x3x1, 3, 1);
+        microkernel!(dot: dot; ["neon"], 4, x3x2, 3, 2);
+        microkernel!(dot: dot; ["neon"], 4, x3x3, 3, 3);
+        microkernel!(dot: dot; ["neon"], 4, x3x4, 3, 4);
+
+        microkernel!(dot: dot; ["neon"], 4, x4x1, 4, 1);
+        microkernel!(dot: dot; ["neon"], 4, x4x2, 4, 2);
+        microkernel!(dot: dot; ["neon"], 4, x4x3, 4, 3);
+        microkernel!(dot: dot; ["neon"], 4, x4x4, 4, 4);
+
+        microkernel_fn_array! {
+            [x1x1, x1x2, x1x3, x1x4, ],
+            [x2x1, x2x2, x2x3, x2x4, ],
+            [x3x1, x3x2, x3x3, x3x4, ],
+            [x4x1, x4x2, x4x3, x4x4, ],
+        }
+    }
+}
@@ -0,0 +1,475 @@
+//! `u8 × i8` products with zero-points, as used by quantized models.
+//!
+//! The operands are packed as is, and the zero-point corrections are applied to the accumulators
+//! afterwards, using the row sums of `lhs` and the column sums of `rhs` that are gathered while
+//! packing:
+//!
+//! `Σ (a - za)(b - zb) = Σ ab - zb Σ a - za Σ b + k za zb`
+
+use dyn_stack::{DynStack, MemBuffer, StackReq};
+use gemm_common::{
+    cache::DivCeil,
+    driver::{self, Product},
+    gemm::AllocError,
+    microkernel::MicroKernelFn,
+    pack_operands::{pack_lhs_with_sums, pack_rhs_with_sums},
+    simd::Simd,
+    Parallelism, Ptr,
+};
+
+/// Quantization parameters of [`gemm_u8i8`].
+///
+/// A quantized value `q` stands for the real value `scale × (q - zero_point)`.
+#[derive(Copy, Clone, Debug)]
+pub struct QuantParams<'a> {
+    pub lhs_scale: f32,
+    pub lhs_zero_point: u8,
+    /// Either one scale per column of `rhs`, or a single scale shared by all the columns.
+    pub rhs_scales: &'a [f32],
+    /// Either one zero-point per column of `rhs`, or a single zero-point shared by all the
+    /// columns.
+    pub rhs_zero_points: &'a [i8],
+    /// Ignored for an `i32` destination.
+    pub dst_scale: f32,
+    /// Ignored for an `i32` destination.
+    pub dst_zero_point: i32,
+}
+
+/// Destination types of [`gemm_u8i8`]: `i32` receives the zero-point corrected accumulator,
+/// while `u8` and `i8` receive the requantized result.
+pub trait QuantizedScalar: Copy + Send + Sync + 'static {
+    #[doc(hidden)]
+    fn requantize(acc: i32, multiplier: f32, zero_point: i32) -> Self;
+}
+
+// rounds to the nearest integer, with ties going to even, without depending on `std`
+#[inline(always)]
+fn round_ties_even(x: f32) -> i32 {
+    // 1.5 × 2^23: the sum has no fractional bits left, so the addition does the rounding
+    const MAGIC: f32 = 12582912.0;
+    let x = x.clamp(-4194304.0, 4194304.0);
+    ((x + MAGIC) - MAGIC) as i32
+}
+
+// the parameter of the column `j`, from a slice with either one element per column or a single
+// shared one
+#[inline(always)]
+fn per_column<T: Copy>(values: &[T], j: usize) -> T {
+    values[if values.len() == 1 { 0 } else { j }]
+}
+
+impl QuantizedScalar for i32 {
+    #[inline(always)]
+    fn requantize(acc: i32, _: f32, _: i32) -> Self {
+        acc
+    }
+}
+
+macro_rules! impl_quantized_scalar {
+    ($ty: ty) => {
+        impl QuantizedScalar for $ty {
+            #[inline(always)]
+            fn requantize(acc: i32, multiplier: f32, zero_point: i32) -> Self {
+                round_ties_even(acc as f32 * multiplier)
+                    .saturating_add(zero_point)
+                    .clamp(<$ty>::MIN as i32, <$ty>::MAX as i32) as $ty
+            }
+        }
+    };
+}
+
+impl_quantized_scalar!(u8);
+impl_quantized_scalar!(i8);
+
+/// Computes `Σ_depth (lhs[i, depth] - lhs_zero_point) × (rhs[depth, j] - rhs_zero_points[j])`
+/// for each `(i, j)` and stores it to `dst`, requantized to `D`.
+///
+/// For a `u8` or `i8` destination, the sum is scaled by `lhs_scale × rhs_scales[j] / dst_scale`,
+/// rounded to the nearest integer with ties to even, shifted by `dst_zero_point` and saturated.
+///
+/// The sums are computed with wrapping arithmetic, so they are exact as long as the result fits
+/// in an `i32`.
+///
+/// # Safety
+///
+/// `dst`, `lhs` and `rhs` must be valid for `m × n`, `m × k` and `k × n` elements with the given
+/// strides, and `dst` must not alias the operands.
+///
+/// # Panics
+///
+/// Panics if `rhs_scales` or `rhs_zero_points` have neither `n` elements nor a single one.
+pub unsafe fn gemm_u8i8<D: QuantizedScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut D,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const u8,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const i8,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    params: &QuantParams<'_>,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    assert!(params.rhs_scales.len() == n || params.rhs_scales.len() == 1);
+    assert!(params.rhs_zero_points.len() == n || params.rhs_zero_points.len() == 1);
+
+    #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+    if let Some(simd) = gemm_common::simd::V3::try_new() {
+        if gemm_common::feature_detected!("avxvnni") {
+            use crate::microkernel::avxvnni::i8::*;
+            return gemm_u8i8_generic::<D, 8, { MR_DIV_N * 8 }, NR, MR_DIV_N, _>(
+                simd,
+                &UKR,
+                m,
+                n,
+                k,
+                dst,
+                dst_cs,
+                dst_rs,
+                lhs,
+                lhs_cs,
+                lhs_rs,
+                rhs,
+                rhs_cs,
+                rhs_rs,
+                params,
+                parallelism,
+            );
+        }
+        use crate::microkernel::fma::i8::*;
+        return gemm_u8i8_generic::<D, 8, { MR_DIV_N * 8 }, NR, MR_DIV_N, _>(
+            simd,
+            &UKR,
+            m,
+            n,
+            k,
+            dst,
+            dst_cs,
+            dst_rs,
+            lhs,
+            lhs_cs,
+            lhs_rs,
+            rhs,
+            rhs_cs,
+            rhs_rs,
+            params,
+            parallelism,
+        );
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    if let Some(simd) =
+        <gemm_common::simd::Neon as gemm_common::simd::MixedSimd<i8, i8, i32, i32>>::try_new()
+    {
+        use crate::microkernel::neon::i8::*;
+        return gemm_u8i8_generic::<D, 4, { MR_DIV_N * 4 }, NR, MR_DIV_N, _>(
+            simd,
+            &UKR,
+            m,
+            n,
+            k,
+            dst,
+            dst_cs,
+            dst_rs,
+            lhs,
+            lhs_cs,
+            lhs_rs,
+            rhs,
+            rhs_cs,
+            rhs_rs,
+            params,
+            parallelism,
+        );
+    }
+
+    use crate::microkernel::scalar::i8::*;
+    gemm_u8i8_generic::<D, 1, { MR_DIV_N }, NR, MR_DIV_N, _>(
+        gemm_common::simd::Scalar,
+        &UKR,
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        params,
+        parallelism,
+    )
+}
+
+/// Naive implementation of the integer part of [`gemm_u8i8`], used as a reference in tests:
+/// stores the zero-point corrected sums to the `i32` destination `dst`.
+///
+/// # Safety
+///
+/// Same as [`gemm_u8i8`].
+pub unsafe fn gemm_u8i8_reference(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut i32,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const u8,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    lhs_zero_point: u8,
+    rhs: *const i8,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    rhs_zero_points: &[i8],
+) {
+    assert!(rhs_zero_points.len() == n || rhs_zero_points.len() == 1);
+
+    for j in 0..n {
+        let rhs_zero_point = per_column(rhs_zero_points, j);
+        for i in 0..m {
+            let mut acc = 0i32;
+            for depth in 0..k {
+                let a = *lhs.offset(i as isize * lhs_rs + depth as isize * lhs_cs) as i32
+                    - lhs_zero_point as i32;
+                let b = *rhs.offset(depth as isize * rhs_rs + j as isize * rhs_cs) as i32
+                    - rhs_zero_point as i32;
+                acc = acc.wrapping_add(a * b);
+            }
+            *dst.offset(i as isize * dst_rs + j as isize * dst_cs) = acc;
+        }
+    }
+}
+
+/// dst := requantize(lhs×rhs - zero-point corrections), computed by
+/// [`driver::gemm_product_generic`] with the `i8` microkernels, which take pairs of depth elements
+/// packed as `i16`. The row and column sums are gathered on the first pass over each operand,
+/// which the driver finishes before storing any tile that needs them.
+struct Quantized<'a, D, const N: usize, const MR: usize, const NR: usize, const MR_DIV_N: usize, S>
+{
+    simd: S,
+    dst: Ptr<D>,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: Ptr<u8>,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: Ptr<i8>,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    k: usize,
+    params: &'a QuantParams<'a>,
+    row_sums: Ptr<i32>,
+    col_sums: Ptr<i32>,
+    dispatcher: &'a [[MicroKernelFn<i32>; NR]; MR_DIV_N],
+}
+
+impl<
+        D: QuantizedScalar,
+        const N: usize,
+        const MR: usize,
+        const NR: usize,
+        const MR_DIV_N: usize,
+        S: Simd,
+    > Product for Quantized<'_, D, N, MR, NR, MR_DIV_N, S>
+{
+    type Lhs = i32;
+    type Rhs = i32;
+    type Acc = i32;
+
+    const MR: usize = MR;
+    const NR: usize = NR;
+    // a pair never straddles two depth chunks
+    const KC_ALIGN: usize = 2;
+
+    #[inline]
+    fn lhs_panel_len(&self, k: usize) -> usize {
+        k.msrv_div_ceil(2) * MR
+    }
+
+    #[inline]
+    fn rhs_panel_len(&self, k: usize) -> usize {
+        k.msrv_div_ceil(2) * NR
+    }
+
+    #[inline(always)]
+    unsafe fn pack_lhs(
+        &self,
+        packed: *mut i32,
+        row: usize,
+        nrows: usize,
+        depth: usize,
+        k: usize,
+        first_pass: bool,
+    ) {
+        pack_lhs_with_sums::<u8, MR, _>(
+            self.simd,
+            nrows,
+            k,
+            Ptr(packed),
+            self.lhs
+                .wrapping_offset(row as isize * self.lhs_rs + depth as isize * self.lhs_cs),
+            self.lhs_cs,
+            self.lhs_rs,
+            self.lhs_panel_len(k),
+            if first_pass {
+                self.row_sums.wrapping_add(row)
+            } else {
+                Ptr(core::ptr::null_mut())
+            },
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn pack_rhs(
+        &self,
+        packed: *mut i32,
+        col: usize,
+        ncols: usize,
+        depth: usize,
+        k: usize,
+        first_pass: bool,
+    ) {
+        pack_rhs_with_sums::<i8, NR, _>(
+            self.simd,
+            ncols,
+            k,
+            Ptr(packed),
+            self.rhs
+                .wrapping_offset(depth as isize * self.rhs_rs + col as isize * self.rhs_cs),
+            self.rhs_cs,
+            self.rhs_rs,
+            self.rhs_panel_len(k),
+            if first_pass {
+                self.col_sums.wrapping_add(col)
+            } else {
+                Ptr(core::ptr::null_mut())
+            },
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn accumulate(
+        &self,
+        acc: *mut i32,
+        lhs: *const i32,
+        rhs: *const i32,
+        nrows: usize,
+        ncols: usize,
+        k: usize,
+        first: bool,
+    ) {
+        self.dispatcher[nrows.msrv_div_ceil(N) - 1][ncols - 1](
+            nrows,
+            ncols,
+            k.msrv_div_ceil(2),
+            acc,
+            lhs,
+            rhs,
+            MR as isize,
+            1,
+            MR as isize,
+            NR as isize,
+            1,
+            1,
+            1,
+            if first { 0 } else { 1 },
+            false,
+            false,
+            false,
+            lhs.wrapping_add(self.lhs_panel_len(k)),
+        );
+    }
+
+    #[inline(always)]
+    unsafe fn store(&self, acc: *const i32, row: usize, col: usize, nrows: usize, ncols: usize) {
+        let params = self.params;
+        let lhs_zero_point = params.lhs_zero_point as i32;
+
+        for j in 0..ncols {
+            let col = col + j;
+            let rhs_zero_point = per_column(params.rhs_zero_points, col) as i32;
+            let multiplier =
+                params.lhs_scale * per_column(params.rhs_scales, col) / params.dst_scale;
+            let correction = (self.k as i32)
+                .wrapping_mul(lhs_zero_point)
+                .wrapping_mul(rhs_zero_point)
+                .wrapping_sub(lhs_zero_point.wrapping_mul(*self.col_sums.0.add(col)));
+
+            for i in 0..nrows {
+                let row = row + i;
+                let value = (*acc.add(i + j * MR))
+                    .wrapping_sub(rhs_zero_point.wrapping_mul(*self.row_sums.0.add(row)))
+                    .wrapping_add(correction);
+                *self
+                    .dst
+                    .wrapping_offset(row as isize * self.dst_rs + col as isize * self.dst_cs)
+                    .0 = D::requantize(value, multiplier, params.dst_zero_point);
+            }
+        }
+    }
+}
+
+#[inline(always)]
+#[allow(clippy::too_many_arguments)]
+unsafe fn gemm_u8i8_generic<
+    D: QuantizedScalar,
+    const N: usize,
+    const MR: usize,
+    const NR: usize,
+    const MR_DIV_N: usize,
+    S: Simd,
+>(
+    simd: S,
+    dispatcher: &[[MicroKernelFn<i32>; NR]; MR_DIV_N],
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut D,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const u8,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const i8,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    params: &QuantParams<'_>,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    let req = StackReq::new::<i32>(m).and(StackReq::new::<i32>(n));
+    let mut mem = MemBuffer::try_new(req)?;
+    let stack = DynStack::new(&mut mem);
+    let (mut row_sums, stack) = stack.make_with(m, |_| 0i32);
+    let (mut col_sums, _) = stack.make_with(n, |_| 0i32);
+
+    driver::gemm_product_generic(
+        &Quantized::<D, N, MR, NR, MR_DIV_N, S> {
+            simd,
+            dst: Ptr(dst),
+            dst_cs,
+            dst_rs,
+            lhs: Ptr(lhs as *mut u8),
+            lhs_cs,
+            lhs_rs,
+            rhs: Ptr(rhs as *mut i8),
+            rhs_cs,
+            rhs_rs,
+            k,
+            params,
+            row_sums: Ptr(row_sums.as_mut_ptr()),
+            col_sums: Ptr(col_sums.as_mut_ptr()),
+            dispatcher,
+        },
+        m,
+        n,
+        k,
+        parallelism,
+    )
+}
@@ -30,7 +30,7 @@ fn make_data<T: Copy + One>(
     }
 }
 
-fn bench_gemm<T: One + Copy + 'static>(
+fn bench_gemm<T: GemmScalar + One>(
     bencher: Bencher,
     list![par, dst, lhs, rhs, m, n, k]: List![
         Parallelism,
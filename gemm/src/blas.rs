@@ -0,0 +1,186 @@
+//! Entry points following the BLAS `?gemm` conventions.
+//!
+//! These compute `C := alpha×op(A)×op(B) + beta×C`, where `op(X)` is `X`, its transpose, or its
+//! conjugate transpose. Note that `alpha` and `beta` have the opposite meaning of the ones taken
+//! by [`gemm`](crate::gemm()).
+
+use crate::{c32, c64, matmul, GemmScalar, MatMut, MatRef, Parallelism};
+use num_traits::Zero;
+
+/// Storage order of the matrices.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Layout {
+    RowMajor,
+    ColMajor,
+}
+
+/// Operation applied to an input matrix.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Transpose {
+    /// `op(X) = X`
+    No,
+    /// `op(X) = Xᵀ`
+    Trans,
+    /// `op(X) = Xᴴ`. Same as [`Transpose::Trans`] for real types.
+    ConjTrans,
+}
+
+/// Row and column strides of the matrices of a `?gemm` call.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct GemmStrides {
+    /// `(row stride, column stride)` of `op(A)`.
+    pub a: (isize, isize),
+    /// `(row stride, column stride)` of `op(B)`.
+    pub b: (isize, isize),
+    /// `(row stride, column stride)` of `C`.
+    pub c: (isize, isize),
+}
+
+/// Checks the leading dimensions of a `?gemm` call and returns the strides of `op(A)`, `op(B)`
+/// and `C`.
+///
+/// A leading dimension must be at least the number of columns (row-major) or rows (column-major)
+/// of the stored matrix, and at least one. On failure, the one-based position of the first
+/// invalid argument in the Fortran `?gemm` interface is returned: 8 for `lda`, 10 for `ldb` and
+/// 13 for `ldc`.
+pub fn gemm_strides(
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
+    m: usize,
+    n: usize,
+    k: usize,
+    lda: usize,
+    ldb: usize,
+    ldc: usize,
+) -> Result<GemmStrides, usize> {
+    // strides of `op(X)`, where `op(X)` is `nrows×ncols`
+    let strides = |trans: Transpose, nrows: usize, ncols: usize, ld: usize, pos: usize| {
+        let (stored_nrows, stored_ncols) = match trans {
+            Transpose::No => (nrows, ncols),
+            Transpose::Trans | Transpose::ConjTrans => (ncols, nrows),
+        };
+        let min = match layout {
+            Layout::RowMajor => stored_ncols,
+            Layout::ColMajor => stored_nrows,
+        }
+        .max(1);
+        let ld = match isize::try_from(ld) {
+            Ok(ld) if ld as usize >= min => ld,
+            _ => return Err(pos),
+        };
+        let (rs, cs) = match layout {
+            Layout::RowMajor => (ld, 1),
+            Layout::ColMajor => (1, ld),
+        };
+        Ok(match trans {
+            Transpose::No => (rs, cs),
+            Transpose::Trans | Transpose::ConjTrans => (cs, rs),
+        })
+    };
+
+    Ok(GemmStrides {
+        a: strides(transa, m, k, lda, 8)?,
+        b: strides(transb, k, n, ldb, 10)?,
+        c: strides(Transpose::No, m, n, ldc, 13)?,
+    })
+}
+
+#[track_caller]
+fn gemm_blas<T: GemmScalar + Zero>(
+    layout: Layout,
+    transa: Transpose,
+    transb: Transpose,
+    m: usize,
+    n: usize,
+    k: usize,
+    alpha: T,
+    a: &[T],
+    lda: usize,
+    b: &[T],
+    ldb: usize,
+    beta: T,
+    c: &mut [T],
+    ldc: usize,
+    parallelism: Parallelism,
+) {
+    let strides = match gemm_strides(layout, transa, transb, m, n, k, lda, ldb, ldc) {
+        Ok(strides) => strides,
+        Err(pos) => panic!("invalid leading dimension (argument {pos} of ?gemm)"),
+    };
+    // as in the reference blas, `a` and `b` are not read when `alpha` is zero
+    let k = if alpha.is_zero() { 0 } else { k };
+    let lhs = MatRef::from_slice(a, m, k, strides.a.0, strides.a.1);
+    let rhs = MatRef::from_slice(b, k, n, strides.b.0, strides.b.1);
+    let dst = MatMut::from_slice(c, m, n, strides.c.0, strides.c.1);
+
+    // as in the reference blas, `c` is not read when `beta` is zero
+    let dst_scale = if beta.is_zero() { None } else { Some(beta) };
+
+    matmul(
+        dst,
+        lhs,
+        rhs,
+        dst_scale,
+        alpha,
+        false,
+        transa == Transpose::ConjTrans,
+        transb == Transpose::ConjTrans,
+        parallelism,
+    );
+}
+
+macro_rules! blas_def {
+    ($name: ident, $ty: ty) => {
+        /// C := alpha×op(A)×op(B) + beta×C
+        ///
+        /// `op(A)` is `m×k`, `op(B)` is `k×n` and `C` is `m×n`. If `alpha` is zero, `A` and `B`
+        /// are not read, and if `beta` is zero, `C` is not read.
+        ///
+        /// # Panics
+        ///
+        /// Panics if a leading dimension is smaller than the number of columns (row-major) or
+        /// rows (column-major) of the stored matrix, or if a slice is too short.
+        #[track_caller]
+        pub fn $name(
+            layout: Layout,
+            transa: Transpose,
+            transb: Transpose,
+            m: usize,
+            n: usize,
+            k: usize,
+            alpha: $ty,
+            a: &[$ty],
+            lda: usize,
+            b: &[$ty],
+            ldb: usize,
+            beta: $ty,
+            c: &mut [$ty],
+            ldc: usize,
+            parallelism: Parallelism,
+        ) {
+            gemm_blas(
+                layout,
+                transa,
+                transb,
+                m,
+                n,
+                k,
+                alpha,
+                a,
+                lda,
+                b,
+                ldb,
+                beta,
+                c,
+                ldc,
+                parallelism,
+            )
+        }
+    };
+}
+
+blas_def!(sgemm, f32);
+blas_def!(dgemm, f64);
+blas_def!(cgemm, c32);
+blas_def!(zgemm, c64);
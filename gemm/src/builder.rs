@@ -0,0 +1,185 @@
+use crate::{gemm::try_gemm, GemmError, GemmScalar, Parallelism};
+
+#[derive(Copy, Clone, Debug)]
+struct Operand<P> {
+    ptr: P,
+    row_stride: isize,
+    col_stride: isize,
+}
+
+/// Descriptor of a call to [`gemm`](crate::gemm()), computing `dst := alpha×dst + beta×lhs×rhs`.
+///
+/// Strides are in elements, and are passed row stride first. The destination is only read if
+/// [`Gemm::alpha`] is set, and `beta` defaults to one.
+///
+/// The descriptor is `Copy`, so it can be stored and run again, either after replacing an operand
+/// or with [`Gemm::run_with`].
+#[derive(Copy, Clone, Debug)]
+pub struct Gemm<T> {
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: Option<Operand<*mut T>>,
+    lhs: Option<Operand<*const T>>,
+    rhs: Option<Operand<*const T>>,
+    alpha: Option<T>,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+}
+
+impl<T: GemmScalar> Gemm<T> {
+    /// Creates a descriptor for a product where `dst` is `m×n`, `lhs` is `m×k` and `rhs` is `k×n`.
+    #[inline]
+    pub fn new(m: usize, n: usize, k: usize) -> Self {
+        Self {
+            m,
+            n,
+            k,
+            dst: None,
+            lhs: None,
+            rhs: None,
+            alpha: None,
+            beta: T::one(),
+            conj_dst: false,
+            conj_lhs: false,
+            conj_rhs: false,
+            parallelism: Parallelism::None,
+        }
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn dst(mut self, ptr: *mut T, row_stride: isize, col_stride: isize) -> Self {
+        self.dst = Some(Operand {
+            ptr,
+            row_stride,
+            col_stride,
+        });
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn lhs(mut self, ptr: *const T, row_stride: isize, col_stride: isize) -> Self {
+        self.lhs = Some(Operand {
+            ptr,
+            row_stride,
+            col_stride,
+        });
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn rhs(mut self, ptr: *const T, row_stride: isize, col_stride: isize) -> Self {
+        self.rhs = Some(Operand {
+            ptr,
+            row_stride,
+            col_stride,
+        });
+        self
+    }
+
+    /// Reads the destination and scales it by `alpha`.
+    #[inline]
+    #[must_use]
+    pub fn alpha(mut self, alpha: T) -> Self {
+        self.alpha = Some(alpha);
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn beta(mut self, beta: T) -> Self {
+        self.beta = beta;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn conj_dst(mut self) -> Self {
+        self.conj_dst = true;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn conj_lhs(mut self) -> Self {
+        self.conj_lhs = true;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn conj_rhs(mut self) -> Self {
+        self.conj_rhs = true;
+        self
+    }
+
+    #[inline]
+    #[must_use]
+    pub fn parallelism(mut self, parallelism: Parallelism) -> Self {
+        self.parallelism = parallelism;
+        self
+    }
+
+    /// Runs the product. The arguments are validated as in [`try_gemm`](crate::try_gemm).
+    ///
+    /// # Safety
+    ///
+    /// The operand pointers must be valid for the given dimensions and strides.
+    pub unsafe fn run(&self) -> Result<(), GemmError> {
+        let dst = self.dst.ok_or(GemmError::MissingOperand("dst"))?;
+        let lhs = self.lhs.ok_or(GemmError::MissingOperand("lhs"))?;
+        let rhs = self.rhs.ok_or(GemmError::MissingOperand("rhs"))?;
+
+        try_gemm(
+            self.m,
+            self.n,
+            self.k,
+            dst.ptr,
+            dst.col_stride,
+            dst.row_stride,
+            self.alpha.is_some(),
+            lhs.ptr,
+            lhs.col_stride,
+            lhs.row_stride,
+            rhs.ptr,
+            rhs.col_stride,
+            rhs.row_stride,
+            self.alpha.unwrap_or(T::zero()),
+            self.beta,
+            self.conj_dst,
+            self.conj_lhs,
+            self.conj_rhs,
+            self.parallelism,
+        )
+    }
+
+    /// Same as [`Gemm::run`], with the operand pointers replaced and the strides kept.
+    ///
+    /// # Safety
+    ///
+    /// The operand pointers must be valid for the given dimensions and strides.
+    pub unsafe fn run_with(
+        &self,
+        dst: *mut T,
+        lhs: *const T,
+        rhs: *const T,
+    ) -> Result<(), GemmError> {
+        let mut this = *self;
+        if let Some(op) = &mut this.dst {
+            op.ptr = dst;
+        }
+        if let Some(op) = &mut this.lhs {
+            op.ptr = lhs;
+        }
+        if let Some(op) = &mut this.rhs {
+            op.ptr = rhs;
+        }
+        this.run()
+    }
+}
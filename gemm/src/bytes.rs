@@ -0,0 +1,65 @@
+use crate::{GemmScalar, Parallelism};
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`](crate::gemm()), but the strides are in bytes instead of elements, so the
+/// operands can be fields of an array of structs. The elements do not need to be aligned.
+///
+/// The operands are read through their byte strides while they are packed, and each element of
+/// `dst` is written once, through its byte strides, after the whole depth has been accumulated.
+/// `f16` and `bf16` products are accumulated in `f32`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+///
+/// # Safety
+///
+/// Same as [`gemm`](crate::gemm()), with the element at `(i, j)` of each matrix located
+/// `i * rs + j * cs` bytes from its pointer.
+#[track_caller]
+pub unsafe fn gemm_bytes<T: GemmScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = T::get_gemm_bytes_fn()(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        conj_dst,
+        conj_lhs,
+        conj_rhs,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
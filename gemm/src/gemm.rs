@@ -1,5 +1,8 @@
-use crate::Parallelism;
-use core::any::TypeId;
+use crate::{mat::footprint, Parallelism};
+use core::fmt;
+#[cfg(feature = "f16")]
+use gemm_common::cache::DivCeil;
+use gemm_common::gemm::AllocError;
 
 #[allow(non_camel_case_types)]
 pub type c32 = num_complex::Complex32;
@@ -8,8 +11,224 @@ pub type c64 = num_complex::Complex64;
 #[cfg(feature = "f16")]
 #[allow(non_camel_case_types)]
 pub type f16 = gemm_f16::f16;
+#[cfg(feature = "bf16")]
+#[allow(non_camel_case_types)]
+pub type bf16 = gemm_bf16::bf16;
+pub use gemm_binary::gemm::BitLayout;
+#[cfg(feature = "f16")]
+pub use gemm_common::block_quant::{BlockQ4, BlockQ8, QuantBlock, QK};
+pub use gemm_common::compensated::CompensatedScalar;
+pub use gemm_common::f8::{f8e4m3, f8e5m2};
+pub use gemm_common::modular::ModScalar;
+pub use gemm_common::ring::RingScalar;
+pub use gemm_common::tropical::{MaxPlus, MinPlus};
+pub use gemm_i8::integer::IntScalar;
+pub use gemm_i8::quantized::{QuantParams, QuantizedScalar};
+
+type GemmFn<T, Lhs = T, Rhs = Lhs, Scale = T> = unsafe fn(
+    usize,
+    usize,
+    usize,
+    *mut T,
+    isize,
+    isize,
+    bool,
+    *const Lhs,
+    isize,
+    isize,
+    *const Rhs,
+    isize,
+    isize,
+    Scale,
+    Scale,
+    bool,
+    bool,
+    bool,
+    Parallelism,
+) -> Result<(), AllocError>;
+
+/// Products of `Lhs×Rhs` and of the transposed `Rhs×Lhs` into `Dst`, with `f32` scale factors.
+type Fp8GemmFns<Dst, Lhs, Rhs> = (GemmFn<Dst, Lhs, Rhs, f32>, GemmFn<Dst, Rhs, Lhs, f32>);
+
+mod seal {
+    pub trait Seal {}
+}
+
+/// Scalar types supported by [`gemm`]: `f32`, `f64`, `gemm::f16`, `gemm::bf16`, `gemm::c32` and
+/// `gemm::c64`.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait GemmScalar:
+    seal::Seal
+    + Copy
+    + Send
+    + Sync
+    + core::fmt::Debug
+    + PartialEq
+    + num_traits::Zero
+    + num_traits::One
+    + 'static
+{
+    /// Whether the conjugation flags of [`gemm`] have an effect on this type.
+    const IS_COMPLEX: bool;
+
+    /// Complex conjugate, or the identity for real types.
+    fn conj(self) -> Self;
+
+    #[doc(hidden)]
+    fn get_gemm_fn() -> GemmFn<Self>;
+
+    #[doc(hidden)]
+    fn get_gemm_bytes_fn() -> GemmFn<Self>;
+}
+
+macro_rules! impl_gemm_scalar {
+    ($ty: ty, $get_gemm_fn: path, $get_gemm_bytes_fn: path, $is_complex: expr) => {
+        impl seal::Seal for $ty {}
+        impl GemmScalar for $ty {
+            const IS_COMPLEX: bool = $is_complex;
+
+            #[inline(always)]
+            fn conj(self) -> Self {
+                gemm_common::gemm::Conj::conj(self)
+            }
+
+            #[inline(always)]
+            fn get_gemm_fn() -> GemmFn<Self> {
+                $get_gemm_fn()
+            }
+
+            #[inline(always)]
+            fn get_gemm_bytes_fn() -> GemmFn<Self> {
+                $get_gemm_bytes_fn()
+            }
+        }
+    };
+}
+
+#[cfg(feature = "f16")]
+impl_gemm_scalar!(
+    f16,
+    gemm_f16::gemm::f16::get_gemm_fn,
+    gemm_f32::gemm::f16_f16::get_gemm_bytes_fn,
+    false
+);
+#[cfg(feature = "bf16")]
+impl_gemm_scalar!(
+    bf16,
+    gemm_bf16::gemm::bf16::get_gemm_fn,
+    gemm_f32::gemm::bf16_bf16::get_gemm_bytes_fn,
+    false
+);
+impl_gemm_scalar!(
+    f32,
+    gemm_f32::gemm::f32::get_gemm_fn,
+    gemm_f32::gemm::f32_f32::get_gemm_bytes_fn,
+    false
+);
+impl_gemm_scalar!(
+    f64,
+    gemm_f64::gemm::f64::get_gemm_fn,
+    gemm_f64::gemm::f64_f64::get_gemm_bytes_fn,
+    false
+);
+impl_gemm_scalar!(
+    c32,
+    gemm_c32::gemm::f32::get_gemm_fn,
+    gemm_c32::gemm::c32_c32::get_gemm_bytes_fn,
+    true
+);
+impl_gemm_scalar!(
+    c64,
+    gemm_c64::gemm::f64::get_gemm_fn,
+    gemm_c64::gemm::c64_c64::get_gemm_bytes_fn,
+    true
+);
+
+/// Error returned by [`try_gemm`].
+///
+/// There is no variant for unsupported scalar types: [`try_gemm`] is bounded by the sealed
+/// [`GemmScalar`] trait, so such types are rejected at compile time.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum GemmError {
+    /// A dimension, or the byte offset of a matrix element, does not fit in an `isize`.
+    Overflow,
+    /// The memory spanned by `dst` overlaps the memory spanned by `lhs` or `rhs`.
+    Aliasing,
+    /// The scratch memory used for packing the operands could not be allocated.
+    AllocationFailed,
+    /// An operand of a [`Gemm`](crate::Gemm) descriptor was not set. Contains `"dst"`, `"lhs"` or
+    /// `"rhs"`.
+    MissingOperand(&'static str),
+    /// An intermediate value of an integer product overflowed.
+    IntegerOverflow,
+    /// The depth `k` is not a multiple of the block size of a block-quantized operand.
+    InvalidDepth,
+}
+
+impl fmt::Display for GemmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GemmError::Overflow => f.write_str("matrix dimensions or strides overflow isize"),
+            GemmError::Aliasing => f.write_str("destination overlaps with an input matrix"),
+            GemmError::AllocationFailed => f.write_str("memory allocation failed"),
+            GemmError::MissingOperand(name) => write!(f, "missing {name} operand"),
+            GemmError::IntegerOverflow => f.write_str("an intermediate integer value overflowed"),
+            GemmError::InvalidDepth => f.write_str("depth is not a multiple of the block size"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for GemmError {}
 
-unsafe fn gemm_dispatch<T: 'static>(
+impl From<AllocError> for GemmError {
+    #[inline]
+    fn from(_: AllocError) -> Self {
+        GemmError::AllocationFailed
+    }
+}
+
+/// Returns the range of addresses spanned by a matrix, or `None` if it is empty.
+fn span<T>(
+    ptr: *const T,
+    nrows: usize,
+    ncols: usize,
+    row_stride: isize,
+    col_stride: isize,
+) -> Result<Option<(usize, usize)>, GemmError> {
+    if nrows == 0 || ncols == 0 {
+        return Ok(None);
+    }
+    let size = core::mem::size_of::<T>() as isize;
+    let (lo, hi) = (|| {
+        let (lo, hi) = footprint(nrows, ncols, row_stride, col_stride)?;
+        let lo = lo.checked_mul(size)?;
+        let hi = hi.checked_add(1)?.checked_mul(size)?;
+        hi.checked_sub(lo)?;
+        let addr = ptr as usize;
+        Some((addr.checked_add_signed(lo)?, addr.checked_add_signed(hi)?))
+    })()
+    .ok_or(GemmError::Overflow)?;
+    Ok(Some((lo, hi)))
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], but the arguments are validated and failures are reported as a
+/// [`GemmError`] instead of panicking.
+///
+/// Aliasing is detected by comparing the address ranges spanned by the matrices, so interleaved
+/// matrices that do not share any element are also rejected.
+///
+/// Unlike the other failures, an unsupported scalar type is a compile-time error, since `T` must
+/// implement [`GemmScalar`].
+///
+/// # Safety
+///
+/// `dst`, `lhs` and `rhs` must be valid for the given dimensions and strides.
+pub unsafe fn try_gemm<T: GemmScalar>(
     m: usize,
     n: usize,
     k: usize,
@@ -29,135 +248,55 @@ unsafe fn gemm_dispatch<T: 'static>(
     conj_lhs: bool,
     conj_rhs: bool,
     parallelism: Parallelism,
-) {
-    #[cfg(feature = "f16")]
-    if TypeId::of::<T>() == TypeId::of::<f16>() {
-        return gemm_f16::gemm::f16::get_gemm_fn()(
-            m,
-            n,
-            k,
-            dst as *mut f16,
-            dst_cs,
-            dst_rs,
-            read_dst,
-            lhs as *mut f16,
-            lhs_cs,
-            lhs_rs,
-            rhs as *mut f16,
-            rhs_cs,
-            rhs_rs,
-            *(&alpha as *const T as *const f16),
-            *(&beta as *const T as *const f16),
-            false,
-            false,
-            false,
-            parallelism,
-        );
+) -> Result<(), GemmError> {
+    if isize::try_from(m.max(n).max(k)).is_err() {
+        return Err(GemmError::Overflow);
     }
 
-    if TypeId::of::<T>() == TypeId::of::<f64>() {
-        gemm_f64::gemm::f64::get_gemm_fn()(
-            m,
-            n,
-            k,
-            dst as *mut f64,
-            dst_cs,
-            dst_rs,
-            read_dst,
-            lhs as *mut f64,
-            lhs_cs,
-            lhs_rs,
-            rhs as *mut f64,
-            rhs_cs,
-            rhs_rs,
-            *(&alpha as *const T as *const f64),
-            *(&beta as *const T as *const f64),
-            false,
-            false,
-            false,
-            parallelism,
-        )
-    } else if TypeId::of::<T>() == TypeId::of::<f32>() {
-        gemm_f32::gemm::f32::get_gemm_fn()(
-            m,
-            n,
-            k,
-            dst as *mut f32,
-            dst_cs,
-            dst_rs,
-            read_dst,
-            lhs as *mut f32,
-            lhs_cs,
-            lhs_rs,
-            rhs as *mut f32,
-            rhs_cs,
-            rhs_rs,
-            *(&alpha as *const T as *const f32),
-            *(&beta as *const T as *const f32),
-            false,
-            false,
-            false,
-            parallelism,
-        )
-    } else if TypeId::of::<T>() == TypeId::of::<c64>() {
-        gemm_c64::gemm::f64::get_gemm_fn()(
-            m,
-            n,
-            k,
-            dst as *mut c64,
-            dst_cs,
-            dst_rs,
-            read_dst,
-            lhs as *mut c64,
-            lhs_cs,
-            lhs_rs,
-            rhs as *mut c64,
-            rhs_cs,
-            rhs_rs,
-            *(&alpha as *const T as *const c64),
-            *(&beta as *const T as *const c64),
-            conj_dst,
-            conj_lhs,
-            conj_rhs,
-            parallelism,
-        )
-    } else if TypeId::of::<T>() == TypeId::of::<c32>() {
-        gemm_c32::gemm::f32::get_gemm_fn()(
-            m,
-            n,
-            k,
-            dst as *mut c32,
-            dst_cs,
-            dst_rs,
-            read_dst,
-            lhs as *mut c32,
-            lhs_cs,
-            lhs_rs,
-            rhs as *mut c32,
-            rhs_cs,
-            rhs_rs,
-            *(&alpha as *const T as *const c32),
-            *(&beta as *const T as *const c32),
-            conj_dst,
-            conj_lhs,
-            conj_rhs,
-            parallelism,
-        )
-    } else {
-        panic!();
+    let dst_span = span(dst, m, n, dst_rs, dst_cs)?;
+    let lhs_span = span(lhs, m, k, lhs_rs, lhs_cs)?;
+    let rhs_span = span(rhs, k, n, rhs_rs, rhs_cs)?;
+    if let Some((dst_lo, dst_hi)) = dst_span {
+        for (lo, hi) in [lhs_span, rhs_span].into_iter().flatten() {
+            if dst_lo < hi && lo < dst_hi {
+                return Err(GemmError::Aliasing);
+            }
+        }
     }
+
+    Ok(gemm_impl(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        conj_dst,
+        conj_lhs,
+        conj_rhs,
+        parallelism,
+    )?)
 }
 
 /// dst := alpha×dst + beta×lhs×rhs
 ///
 /// # Panics
 ///
-/// Panics if `T` is not `f32`, `f64`, `gemm::f16`, `gemm::c32`, or `gemm::c64`.
-pub unsafe fn gemm<T: 'static>(
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm<T: GemmScalar>(
     m: usize,
     n: usize,
     k: usize,
-    mut dst: *mut T,
+    dst: *mut T,
     dst_cs: isize,
     dst_rs: isize,
     read_dst: bool,
@@ -174,33 +313,1626 @@ pub unsafe fn gemm<T: 'static>(
     conj_rhs: bool,
     parallelism: Parallelism,
 ) {
-    // we want to transpose if the destination is column-oriented, since the microkernel prefers
-    // column major matrices.
-    let do_transpose = dst_cs.abs() < dst_rs.abs();
-
-    let (
+    if let Err(err) = gemm_impl(
         m,
         n,
-        mut dst_cs,
-        mut dst_rs,
-        mut lhs,
-        mut lhs_cs,
-        mut lhs_rs,
-        mut rhs,
-        mut rhs_cs,
-        mut rhs_rs,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        conj_dst,
         conj_lhs,
         conj_rhs,
-    ) = if do_transpose {
-        (
-            n, m, dst_rs, dst_cs, rhs, rhs_rs, rhs_cs, lhs, lhs_rs, lhs_cs, conj_rhs, conj_lhs,
-        )
-    } else {
-        (
-            m, n, dst_cs, dst_rs, lhs, lhs_cs, lhs_rs, rhs, rhs_cs, rhs_rs, conj_lhs, conj_rhs,
-        )
-    };
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with `f16` inputs and an `f32` destination. The products are accumulated in
+/// `f32` and stored without being rounded to `f16`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+#[cfg(feature = "f16")]
+pub unsafe fn gemm_f16_f32(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut f32,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const f16,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const f16,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: f32,
+    beta: f32,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_with(
+        gemm_f16::gemm::f16_f32::get_gemm_fn(),
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        false,
+        false,
+        false,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`] with `f32` operands, except that `rhs` is given in a block-quantized format
+/// such as [`BlockQ4`] or [`BlockQ8`]: column `j` of `rhs` is made of `k / QK` consecutive blocks
+/// starting at `rhs.offset(j * rhs_cs)`. `rhs` is dequantized one panel at a time while it is
+/// packed, so the full `f32` matrix is never materialized.
+///
+/// Returns [`GemmError::InvalidDepth`] if `k` is not a multiple of [`QK`], and
+/// [`GemmError::AllocationFailed`] if the scratch memory used for packing the operands cannot be
+/// allocated.
+#[cfg(feature = "f16")]
+pub unsafe fn gemm_block_quant<B: QuantBlock>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut f32,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const f32,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const B,
+    rhs_cs: isize,
+    alpha: f32,
+    beta: f32,
+    parallelism: Parallelism,
+) -> Result<(), GemmError> {
+    if !k.msrv_is_multiple_of(QK) {
+        return Err(GemmError::InvalidDepth);
+    }
+    gemm_f32::block_quant::gemm_basic(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        alpha,
+        beta,
+        parallelism,
+    )?;
+    Ok(())
+}
 
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with `i8` inputs and an `i32` destination. Pairs of consecutive depth elements
+/// are packed as `i16` and multiplied with `pmaddwd`, or `vpdpwssd` with AVX-VNNI, and the product
+/// `lhs×rhs` is accumulated exactly for `k <= 2^16`. `alpha×dst` and `beta×lhs×rhs` wrap on
+/// overflow, like the rest of the `i32` arithmetic.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_i8(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut i32,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const i8,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const i8,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: i32,
+    beta: i32,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_with(
+        gemm_i8::gemm::i8_i32::get_gemm_fn(),
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        false,
+        false,
+        false,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := requantize(dequantize(lhs)×dequantize(rhs))
+///
+/// Asymmetric quantized product of `u8` activations with `i8` weights, where the weights can have
+/// per-column scales and zero-points, as in the ONNX `QLinearMatMul` and `MatMulInteger`
+/// operators. The integer product is computed exactly, with the zero-points subtracted using the
+/// row and column sums gathered while packing the operands. For a `u8` or `i8` destination, the
+/// result is then scaled by `lhs_scale × rhs_scales[j] / dst_scale`, rounded to the nearest
+/// integer with ties to even, shifted by `dst_zero_point` and saturated.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated, or if
+/// `rhs_scales` or `rhs_zero_points` have neither `n` elements nor a single one.
+pub unsafe fn gemm_quantized<D: QuantizedScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut D,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const u8,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const i8,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    params: &QuantParams<'_>,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_i8::quantized::gemm_u8i8(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        params,
+        parallelism,
+    ) {
+        panic!("{}", GemmError::from(err));
+    }
+}
+
+/// Operand types that [`gemm_wide`] can accumulate in double precision into a `Dst` destination:
+/// `f32` into `f32` or `f64`, and `c32` into `c32` or `c64`.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait WideScalar<Dst: GemmScalar>: GemmScalar {
+    #[doc(hidden)]
+    fn get_wide_gemm_fn() -> GemmFn<Dst, Self>;
+}
+
+macro_rules! impl_wide_scalar {
+    ($ty: ty, $dst: ty, $get_gemm_fn: path) => {
+        impl WideScalar<$dst> for $ty {
+            #[inline(always)]
+            fn get_wide_gemm_fn() -> GemmFn<$dst, Self> {
+                $get_gemm_fn()
+            }
+        }
+    };
+}
+
+impl_wide_scalar!(f32, f32, gemm_f64::gemm::f32_f32::get_gemm_fn);
+impl_wide_scalar!(f32, f64, gemm_f64::gemm::f32_f64::get_gemm_fn);
+impl_wide_scalar!(c32, c32, gemm_c64::gemm::c32_c32::get_gemm_fn);
+impl_wide_scalar!(c32, c64, gemm_c64::gemm::c32_c64::get_gemm_fn);
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with the operands converted to `f64` (or `c64`) while they are packed, and
+/// the products accumulated by the double precision microkernels. The destination is either of
+/// the operand type, in which case the result is rounded once when it is stored, or of the wider
+/// type.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_wide<T: WideScalar<D>, D: GemmScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut D,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: D,
+    beta: D,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_with(
+        T::get_wide_gemm_fn(),
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        conj_dst && D::IS_COMPLEX,
+        conj_lhs && D::IS_COMPLEX,
+        conj_rhs && D::IS_COMPLEX,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// Operand and destination types of [`gemm_mixed`]. `Self` is the type of `lhs`, and each of
+/// `Self`, `Rhs` and `Dst` can be any of `f32`, `f64` and `gemm::f16`.
+///
+/// This trait is sealed and cannot be implemented outside of this crate.
+pub trait MixedScalar<Rhs: GemmScalar, Dst: GemmScalar>: GemmScalar {
+    #[doc(hidden)]
+    fn get_mixed_gemm_fn() -> (GemmFn<Dst, Self, Rhs>, GemmFn<Dst, Rhs, Self>);
+}
+
+macro_rules! impl_mixed_scalar {
+    ($lhs: ty, $rhs: ty, $dst: ty, $get_gemm_fn: path, $get_transposed_gemm_fn: path) => {
+        impl MixedScalar<$rhs, $dst> for $lhs {
+            #[inline(always)]
+            fn get_mixed_gemm_fn() -> (GemmFn<$dst, Self, $rhs>, GemmFn<$dst, $rhs, Self>) {
+                ($get_gemm_fn(), $get_transposed_gemm_fn())
+            }
+        }
+    };
+}
+
+impl_mixed_scalar!(
+    f32,
+    f32,
+    f32,
+    gemm_f32::gemm::f32::get_gemm_fn,
+    gemm_f32::gemm::f32::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f32,
+    f32,
+    f64,
+    gemm_f64::gemm::f32_f64::get_gemm_fn,
+    gemm_f64::gemm::f32_f64::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f32,
+    f64,
+    f32,
+    gemm_f64::gemm::f32_f64_f32::get_gemm_fn,
+    gemm_f64::gemm::f64_f32_f32::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f32,
+    f64,
+    f64,
+    gemm_f64::gemm::f32_f64_f64::get_gemm_fn,
+    gemm_f64::gemm::f64_f32_f64::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f64,
+    f32,
+    f32,
+    gemm_f64::gemm::f64_f32_f32::get_gemm_fn,
+    gemm_f64::gemm::f32_f64_f32::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f64,
+    f32,
+    f64,
+    gemm_f64::gemm::f64_f32_f64::get_gemm_fn,
+    gemm_f64::gemm::f32_f64_f64::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f64,
+    f64,
+    f32,
+    gemm_f64::gemm::f64_f64_f32::get_gemm_fn,
+    gemm_f64::gemm::f64_f64_f32::get_gemm_fn
+);
+impl_mixed_scalar!(
+    f64,
+    f64,
+    f64,
+    gemm_f64::gemm::f64::get_gemm_fn,
+    gemm_f64::gemm::f64::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f16,
+    f16,
+    gemm_f16::gemm::f16::get_gemm_fn,
+    gemm_f16::gemm::f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f16,
+    f32,
+    gemm_f16::gemm::f16_f32::get_gemm_fn,
+    gemm_f16::gemm::f16_f32::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f16,
+    f64,
+    gemm_f64::gemm::f16_f16_f64::get_gemm_fn,
+    gemm_f64::gemm::f16_f16_f64::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f32,
+    f16,
+    gemm_f32::gemm::f16_f32_f16::get_gemm_fn,
+    gemm_f32::gemm::f32_f16_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f32,
+    f32,
+    gemm_f32::gemm::f16_f32_f32::get_gemm_fn,
+    gemm_f32::gemm::f32_f16_f32::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f32,
+    f64,
+    gemm_f64::gemm::f16_f32_f64::get_gemm_fn,
+    gemm_f64::gemm::f32_f16_f64::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f64,
+    f16,
+    gemm_f64::gemm::f16_f64_f16::get_gemm_fn,
+    gemm_f64::gemm::f64_f16_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f64,
+    f32,
+    gemm_f64::gemm::f16_f64_f32::get_gemm_fn,
+    gemm_f64::gemm::f64_f16_f32::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f16,
+    f64,
+    f64,
+    gemm_f64::gemm::f16_f64_f64::get_gemm_fn,
+    gemm_f64::gemm::f64_f16_f64::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f32,
+    f16,
+    f16,
+    gemm_f32::gemm::f32_f16_f16::get_gemm_fn,
+    gemm_f32::gemm::f16_f32_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f32,
+    f16,
+    f32,
+    gemm_f32::gemm::f32_f16_f32::get_gemm_fn,
+    gemm_f32::gemm::f16_f32_f32::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f32,
+    f16,
+    f64,
+    gemm_f64::gemm::f32_f16_f64::get_gemm_fn,
+    gemm_f64::gemm::f16_f32_f64::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f32,
+    f32,
+    f16,
+    gemm_f32::gemm::f32_f32_f16::get_gemm_fn,
+    gemm_f32::gemm::f32_f32_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f32,
+    f64,
+    f16,
+    gemm_f64::gemm::f32_f64_f16::get_gemm_fn,
+    gemm_f64::gemm::f64_f32_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f64,
+    f16,
+    f16,
+    gemm_f64::gemm::f64_f16_f16::get_gemm_fn,
+    gemm_f64::gemm::f16_f64_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f64,
+    f16,
+    f32,
+    gemm_f64::gemm::f64_f16_f32::get_gemm_fn,
+    gemm_f64::gemm::f16_f64_f32::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f64,
+    f16,
+    f64,
+    gemm_f64::gemm::f64_f16_f64::get_gemm_fn,
+    gemm_f64::gemm::f16_f64_f64::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f64,
+    f32,
+    f16,
+    gemm_f64::gemm::f64_f32_f16::get_gemm_fn,
+    gemm_f64::gemm::f32_f64_f16::get_gemm_fn
+);
+#[cfg(feature = "f16")]
+impl_mixed_scalar!(
+    f64,
+    f64,
+    f16,
+    gemm_f64::gemm::f64_f64_f16::get_gemm_fn,
+    gemm_f64::gemm::f64_f64_f16::get_gemm_fn
+);
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with the types of `lhs`, `rhs` and `dst` chosen independently among `f32`,
+/// `f64` and `gemm::f16`, e.g. `f32` activations multiplied by `f16` weights into an `f32`
+/// destination. The operands are converted to the accumulator type while they are packed, so no
+/// converted copy of either of them is allocated. The products are accumulated in `f64` if any of
+/// the three types is `f64`, and in `f32` otherwise, except when all three are `f16`, which uses
+/// the same kernels as [`gemm`]. The accumulator is kept across the whole depth and rounded once
+/// when it is stored to `dst`. The `f16` kernels of [`gemm`] are the exception, they round the
+/// partial sums to `f16` after each block of the depth.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_mixed<Lhs: MixedScalar<Rhs, Dst>, Rhs: GemmScalar, Dst: GemmScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut Dst,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const Lhs,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const Rhs,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: Dst,
+    beta: Dst,
+    parallelism: Parallelism,
+) {
+    let (gemm_fn, transposed_fn) = Lhs::get_mixed_gemm_fn();
+    if let Err(err) = gemm_with_transposed(
+        gemm_fn,
+        transposed_fn,
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        false,
+        false,
+        false,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], for any scalar type that forms a commutative ring, such as modular integers
+/// or dual numbers. The products are computed by a portable microkernel, with the same cache
+/// blocking and multithreading as the other types. See [`RingScalar`] for the requirements on
+/// `T`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_ring<T: RingScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_with(
+        gemm_common::ring::gemm_basic::<T>,
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        false,
+        false,
+        false,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// Semiring types of [`gemm_tropical`]: [`MinPlus`] and [`MaxPlus`], over `f32` or `f64`.
+pub trait TropicalScalar: Copy + 'static {
+    #[doc(hidden)]
+    fn get_tropical_gemm_fn() -> GemmFn<Self>;
+}
+
+macro_rules! impl_tropical_scalar {
+    ($ty: ty, $get_gemm_fn: path) => {
+        impl TropicalScalar for $ty {
+            #[inline(always)]
+            fn get_tropical_gemm_fn() -> GemmFn<Self> {
+                $get_gemm_fn()
+            }
+        }
+    };
+}
+
+impl_tropical_scalar!(MinPlus<f32>, gemm_f32::gemm::min_plus_f32::get_gemm_fn);
+impl_tropical_scalar!(MaxPlus<f32>, gemm_f32::gemm::max_plus_f32::get_gemm_fn);
+impl_tropical_scalar!(MinPlus<f64>, gemm_f64::gemm::min_plus_f64::get_gemm_fn);
+impl_tropical_scalar!(MaxPlus<f64>, gemm_f64::gemm::max_plus_f64::get_gemm_fn);
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with the sum and product of the tropical semiring `T`. For [`MinPlus`], this
+/// computes `dst[i, j] = min(alpha + dst[i, j], beta + min_k(lhs[i, k] + rhs[k, j]))`, so that
+/// `alpha` and `beta` should usually be `T::ONE`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_tropical<T: TropicalScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_with(
+        T::get_tropical_gemm_fn(),
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        false,
+        false,
+        false,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], for `f32` or `f64` matrices, computed in an accuracy mode that is slower, but
+/// whose result does not lose precision when `k` is large or the terms cancel. The dot products
+/// are accumulated with error-free transformations, and their compensation terms are only added
+/// back once the whole depth has been processed, so each element is as accurate as if it had been
+/// computed with twice the working precision, then rounded.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_compensated<T: CompensatedScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_common::compensated::gemm_compensated(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// \[dst_lo, dst_hi\] := enclosure of lhs×rhs
+///
+/// Computes `f64` bounds such that `dst_lo ≤ lhs×rhs ≤ dst_hi` holds for each element of the
+/// exact product, including when it underflows. `dst_hi` first receives the product computed by
+/// the `f64` kernels, and `dst_lo` the product of the absolute values of the operands, from which
+/// an a-priori bound of the rounding error is derived. The bounds are infinite if the product or
+/// its error bound overflows, and may be NaN if an input is NaN or infinite.
+///
+/// `dst_lo` and `dst_hi` share the same strides, and must not overlap each other, `lhs` or `rhs`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_interval(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst_lo: *mut f64,
+    dst_hi: *mut f64,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const f64,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const f64,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_interval_impl(
+        m,
+        n,
+        k,
+        dst_lo,
+        dst_hi,
+        dst_cs,
+        dst_rs,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        parallelism,
+    ) {
+        panic!("{}", GemmError::from(err));
+    }
+}
+
+unsafe fn gemm_interval_impl(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst_lo: *mut f64,
+    dst_hi: *mut f64,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const f64,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const f64,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    if m == 0 || n == 0 {
+        return Ok(());
+    }
+    let dst =
+        |ptr: *mut f64, i: usize, j: usize| ptr.offset(i as isize * dst_rs + j as isize * dst_cs);
+
+    if k == 0 {
+        for j in 0..n {
+            for i in 0..m {
+                *dst(dst_lo, i, j) = 0.0;
+                *dst(dst_hi, i, j) = 0.0;
+            }
+        }
+        return Ok(());
+    }
+
+    let gemm_fn = <f64 as GemmScalar>::get_gemm_fn();
+    gemm_with(
+        gemm_fn,
+        m,
+        n,
+        k,
+        dst_hi,
+        dst_cs,
+        dst_rs,
+        false,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        0.0,
+        1.0,
+        false,
+        false,
+        false,
+        parallelism,
+    )?;
+    gemm_f64::abs::gemm_basic(
+        m,
+        n,
+        k,
+        dst_lo,
+        dst_cs,
+        dst_rs,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        parallelism,
+    )?;
+
+    // with u = 2^-53 and eta = 2^-1074, the computed product `c` and the computed product of the
+    // absolute values `t` satisfy |c - lhs×rhs| <= γ_k |lhs|×|rhs| + k eta, and
+    // |lhs|×|rhs| <= t / (1 - γ_k) + k eta, whichever order and fused multiply-adds the kernels
+    // use. for (k + 2) u <= 1/4, this gives |c - lhs×rhs| <= 2 (k + 2) u t + 2 k eta
+    let unit_roundoff = f64::EPSILON / 2.0;
+    let (scale, underflow) = if (k + 2) as f64 * unit_roundoff <= 0.25 {
+        (
+            2.0 * (k + 2) as f64 * unit_roundoff,
+            2.0 * k as f64 * f64::from_bits(1),
+        )
+    } else {
+        (f64::INFINITY, f64::INFINITY)
+    };
+
+    for j in 0..n {
+        for i in 0..m {
+            let (lo, hi) = (dst(dst_lo, i, j), dst(dst_hi, i, j));
+            let (c, t) = (*hi, *lo);
+            // each rounding to nearest is corrected by one step away from the exact value
+            let radius = next_up(next_up(scale * t) + underflow);
+            (*lo, *hi) = if radius == f64::INFINITY {
+                (f64::NEG_INFINITY, f64::INFINITY)
+            } else {
+                (next_down(c - radius), next_up(c + radius))
+            };
+        }
+    }
+
+    Ok(())
+}
+
+/// Smallest `f64` greater than `x`, or `x` if it is `+∞` or NaN.
+#[inline]
+fn next_up(x: f64) -> f64 {
+    if x.is_nan() || x == f64::INFINITY {
+        x
+    } else if x == 0.0 {
+        f64::from_bits(1)
+    } else if x > 0.0 {
+        f64::from_bits(x.to_bits() + 1)
+    } else {
+        f64::from_bits(x.to_bits() - 1)
+    }
+}
+
+/// Largest `f64` less than `x`, or `x` if it is `-∞` or NaN.
+#[inline]
+fn next_down(x: f64) -> f64 {
+    -next_up(-x)
+}
+
+/// dst := alpha×dst + beta×lhs×rhs, modulo `p`
+///
+/// Same as [`gemm`], with `u32` or `u64` residues modulo `p >= 2`. The result is exact: the
+/// products are accumulated in integers twice as wide as `T`, and reduced modulo `p` before they
+/// can overflow. The inputs must be residues in `0..p`.
+///
+/// # Panics
+///
+/// Panics if `p < 2`, or if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_mod<T: ModScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    p: T,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_common::modular::gemm_mod(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        p,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with `i32` or `i64` matrices. All the operations wrap on overflow, so the
+/// result is exact modulo `2^T::BITS`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_int_wrapping<T: IntScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_i8::integer::gemm_wrapping(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm_int_wrapping`], and returns [`GemmError::IntegerOverflow`] if a product, a
+/// partial sum over the depth (accumulated in increasing order), or a term of the final
+/// combination overflowed. In that case, the contents of `dst` are unspecified.
+pub unsafe fn gemm_int_checked<T: IntScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    parallelism: Parallelism,
+) -> Result<(), GemmError> {
+    let overflowed = gemm_i8::integer::gemm_checked(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        parallelism,
+    )?;
+    if overflowed {
+        Err(GemmError::IntegerOverflow)
+    } else {
+        Ok(())
+    }
+}
+
+/// dst\[i, j\] := number of depth indices where lhs\[i, depth\] == rhs\[depth, j\]
+///
+/// `lhs` is an `m × k` bit matrix and `rhs` a `k × n` bit matrix, packed 64 elements per `u64`
+/// in the given [`BitLayout`], with rows (or columns) that are `stride` words apart. For
+/// binarized values where `0` stands for `-1` and `1` for `+1`, the dot product is
+/// `2 × dst[i, j] - k`.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_xnor_popcount(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut i32,
+    dst_cs: isize,
+    dst_rs: isize,
+    lhs: *const u64,
+    lhs_layout: BitLayout,
+    lhs_stride: usize,
+    rhs: *const u64,
+    rhs_layout: BitLayout,
+    rhs_stride: usize,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_binary::gemm::gemm_xnor_popcount(
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        lhs,
+        lhs_layout,
+        lhs_stride,
+        rhs,
+        rhs_layout,
+        rhs_stride,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// dst := lhs×rhs over the boolean semiring, where the sum is OR and the product is AND.
+///
+/// The operands are stored as in [`gemm_xnor_popcount`], and so is `dst`, an `m × n` bit matrix.
+/// The bits of `dst` outside of the matrix are left unchanged.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_or_and(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut u64,
+    dst_layout: BitLayout,
+    dst_stride: usize,
+    lhs: *const u64,
+    lhs_layout: BitLayout,
+    lhs_stride: usize,
+    rhs: *const u64,
+    rhs_layout: BitLayout,
+    rhs_stride: usize,
+    parallelism: Parallelism,
+) {
+    if let Err(err) = gemm_binary::gemm::gemm_or_and(
+        m,
+        n,
+        k,
+        dst,
+        dst_layout,
+        dst_stride,
+        lhs,
+        lhs_layout,
+        lhs_stride,
+        rhs,
+        rhs_layout,
+        rhs_stride,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+/// Per-tensor scale factors of [`gemm_fp8`]: an FP8 value `q` of a matrix stands for the real
+/// value `scale × q`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Fp8Scales {
+    pub lhs: f32,
+    pub rhs: f32,
+    /// Ignored for an `f32` destination.
+    pub dst: f32,
+}
+
+impl Default for Fp8Scales {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            lhs: 1.0,
+            rhs: 1.0,
+            dst: 1.0,
+        }
+    }
+}
+
+/// Destination types of [`gemm_fp8`]: `f32`, [`f8e4m3`] and [`f8e5m2`].
+pub trait Fp8Dst: Copy + 'static {
+    #[doc(hidden)]
+    fn to_f32(self) -> f32;
+    #[doc(hidden)]
+    fn from_f32(value: f32) -> Self;
+    /// The scale factor of a destination of this type, `scales.dst` for the FP8 types.
+    #[doc(hidden)]
+    fn dst_scale(scales: Fp8Scales) -> f32;
+}
+
+/// Operand and destination types of [`gemm_fp8`]. `Self` is the type of `lhs`, `Self` and `Rhs`
+/// can each be [`f8e4m3`] or [`f8e5m2`], and `Dst` can be `f32` or either of them.
+pub trait Fp8Scalar<Rhs: Fp8Dst, Dst: Fp8Dst>: Fp8Dst {
+    #[doc(hidden)]
+    fn get_fp8_gemm_fn() -> Fp8GemmFns<Dst, Self, Rhs>;
+}
+
+impl Fp8Dst for f32 {
+    #[inline(always)]
+    fn to_f32(self) -> f32 {
+        self
+    }
+
+    #[inline(always)]
+    fn from_f32(value: f32) -> Self {
+        value
+    }
+
+    #[inline(always)]
+    fn dst_scale(_: Fp8Scales) -> f32 {
+        1.0
+    }
+}
+
+macro_rules! impl_fp8_dst {
+    ($ty: ty) => {
+        impl Fp8Dst for $ty {
+            #[inline(always)]
+            fn to_f32(self) -> f32 {
+                <$ty>::to_f32(self)
+            }
+
+            #[inline(always)]
+            fn from_f32(value: f32) -> Self {
+                <$ty>::from_f32(value)
+            }
+
+            #[inline(always)]
+            fn dst_scale(scales: Fp8Scales) -> f32 {
+                scales.dst
+            }
+        }
+    };
+}
+
+impl_fp8_dst!(f8e4m3);
+impl_fp8_dst!(f8e5m2);
+
+macro_rules! impl_fp8_scalar {
+    ($lhs: ty, $rhs: ty, $dst: ty, $get_gemm_fn: path, $get_transposed_gemm_fn: path) => {
+        impl Fp8Scalar<$rhs, $dst> for $lhs {
+            #[inline(always)]
+            fn get_fp8_gemm_fn() -> Fp8GemmFns<$dst, Self, $rhs> {
+                ($get_gemm_fn(), $get_transposed_gemm_fn())
+            }
+        }
+    };
+}
+
+impl_fp8_scalar!(
+    f8e4m3,
+    f8e4m3,
+    f32,
+    gemm_f32::gemm::f8e4m3_f32::get_gemm_fn,
+    gemm_f32::gemm::f8e4m3_f32::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e4m3,
+    f8e4m3,
+    f8e4m3,
+    gemm_f32::gemm::f8e4m3_f8e4m3::get_gemm_fn,
+    gemm_f32::gemm::f8e4m3_f8e4m3::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e4m3,
+    f8e4m3,
+    f8e5m2,
+    gemm_f32::gemm::f8e4m3_f8e5m2::get_gemm_fn,
+    gemm_f32::gemm::f8e4m3_f8e5m2::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e4m3,
+    f8e5m2,
+    f32,
+    gemm_f32::gemm::f8e4m3_f8e5m2_f32::get_gemm_fn,
+    gemm_f32::gemm::f8e5m2_f8e4m3_f32::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e4m3,
+    f8e5m2,
+    f8e4m3,
+    gemm_f32::gemm::f8e4m3_f8e5m2_f8e4m3::get_gemm_fn,
+    gemm_f32::gemm::f8e5m2_f8e4m3_f8e4m3::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e4m3,
+    f8e5m2,
+    f8e5m2,
+    gemm_f32::gemm::f8e4m3_f8e5m2_f8e5m2::get_gemm_fn,
+    gemm_f32::gemm::f8e5m2_f8e4m3_f8e5m2::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e5m2,
+    f8e4m3,
+    f32,
+    gemm_f32::gemm::f8e5m2_f8e4m3_f32::get_gemm_fn,
+    gemm_f32::gemm::f8e4m3_f8e5m2_f32::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e5m2,
+    f8e4m3,
+    f8e4m3,
+    gemm_f32::gemm::f8e5m2_f8e4m3_f8e4m3::get_gemm_fn,
+    gemm_f32::gemm::f8e4m3_f8e5m2_f8e4m3::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e5m2,
+    f8e4m3,
+    f8e5m2,
+    gemm_f32::gemm::f8e5m2_f8e4m3_f8e5m2::get_gemm_fn,
+    gemm_f32::gemm::f8e4m3_f8e5m2_f8e5m2::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e5m2,
+    f8e5m2,
+    f32,
+    gemm_f32::gemm::f8e5m2_f32::get_gemm_fn,
+    gemm_f32::gemm::f8e5m2_f32::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e5m2,
+    f8e5m2,
+    f8e4m3,
+    gemm_f32::gemm::f8e5m2_f8e4m3::get_gemm_fn,
+    gemm_f32::gemm::f8e5m2_f8e4m3::get_gemm_fn
+);
+impl_fp8_scalar!(
+    f8e5m2,
+    f8e5m2,
+    f8e5m2,
+    gemm_f32::gemm::f8e5m2_f8e5m2::get_gemm_fn,
+    gemm_f32::gemm::f8e5m2_f8e5m2::get_gemm_fn
+);
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Same as [`gemm`], with FP8 operands that are widened to `f32` while they are packed and
+/// multiplied by the `f32` microkernels. `lhs` and `rhs` stand for `scales.lhs × lhs` and
+/// `scales.rhs × rhs`, and each of them can be [`f8e4m3`] or [`f8e5m2`] independently of the
+/// other.
+///
+/// The destination is either `f32`, or an FP8 type standing for `scales.dst × dst`. In the
+/// latter case, the product is accumulated in `f32` over the whole depth, combined with
+/// `alpha×dst`, then rounded to the nearest FP8 value and saturated to the finite range of the
+/// format when it is stored.
+///
+/// # Panics
+///
+/// Panics if the scratch memory used for packing the operands cannot be allocated.
+pub unsafe fn gemm_fp8<Lhs: Fp8Scalar<Rhs, Dst>, Rhs: Fp8Dst, Dst: Fp8Dst>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut Dst,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const Lhs,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const Rhs,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: f32,
+    beta: f32,
+    scales: Fp8Scales,
+    parallelism: Parallelism,
+) {
+    let (gemm_fn, transposed_fn) = Lhs::get_fp8_gemm_fn();
+    if let Err(err) = gemm_with_transposed(
+        gemm_fn,
+        transposed_fn,
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta * scales.lhs * scales.rhs / Dst::dst_scale(scales),
+        false,
+        false,
+        false,
+        parallelism,
+    ) {
+        panic!("{err}");
+    }
+}
+
+pub(crate) unsafe fn gemm_impl<T: GemmScalar>(
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const T,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const T,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    gemm_with(
+        T::get_gemm_fn(),
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        conj_dst && T::IS_COMPLEX,
+        conj_lhs && T::IS_COMPLEX,
+        conj_rhs && T::IS_COMPLEX,
+        parallelism,
+    )
+}
+
+/// Runs `gemm_fn` after normalizing the strides, so that the destination is column-oriented and
+/// its strides are non-negative.
+unsafe fn gemm_with<T, Src>(
+    gemm_fn: GemmFn<T, Src>,
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const Src,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const Src,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: T,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    gemm_with_transposed(
+        gemm_fn,
+        gemm_fn,
+        m,
+        n,
+        k,
+        dst,
+        dst_cs,
+        dst_rs,
+        read_dst,
+        lhs,
+        lhs_cs,
+        lhs_rs,
+        rhs,
+        rhs_cs,
+        rhs_rs,
+        alpha,
+        beta,
+        conj_dst,
+        conj_lhs,
+        conj_rhs,
+        parallelism,
+    )
+}
+
+/// Same as [`gemm_with`], for operands of different types. `transposed_fn` computes the same
+/// product with the operands exchanged, and is called instead of `gemm_fn` when the destination
+/// is transposed.
+unsafe fn gemm_with_transposed<T, Lhs, Rhs, Scale>(
+    gemm_fn: GemmFn<T, Lhs, Rhs, Scale>,
+    transposed_fn: GemmFn<T, Rhs, Lhs, Scale>,
+    m: usize,
+    n: usize,
+    k: usize,
+    dst: *mut T,
+    dst_cs: isize,
+    dst_rs: isize,
+    read_dst: bool,
+    lhs: *const Lhs,
+    lhs_cs: isize,
+    lhs_rs: isize,
+    rhs: *const Rhs,
+    rhs_cs: isize,
+    rhs_rs: isize,
+    alpha: Scale,
+    beta: Scale,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
+    // we want to transpose if the destination is column-oriented, since the microkernel prefers
+    // column major matrices.
+    if dst_cs.abs() < dst_rs.abs() {
+        gemm_with_non_negative_strides(
+            transposed_fn,
+            n,
+            m,
+            k,
+            dst,
+            dst_rs,
+            dst_cs,
+            read_dst,
+            rhs,
+            rhs_rs,
+            rhs_cs,
+            lhs,
+            lhs_rs,
+            lhs_cs,
+            alpha,
+            beta,
+            conj_dst,
+            conj_rhs,
+            conj_lhs,
+            parallelism,
+        )
+    } else {
+        gemm_with_non_negative_strides(
+            gemm_fn,
+            m,
+            n,
+            k,
+            dst,
+            dst_cs,
+            dst_rs,
+            read_dst,
+            lhs,
+            lhs_cs,
+            lhs_rs,
+            rhs,
+            rhs_cs,
+            rhs_rs,
+            alpha,
+            beta,
+            conj_dst,
+            conj_lhs,
+            conj_rhs,
+            parallelism,
+        )
+    }
+}
+
+/// Runs `gemm_fn` after flipping the strides of the destination and of the depth dimension of
+/// `lhs` so that they are non-negative.
+unsafe fn gemm_with_non_negative_strides<T, Lhs, Rhs, Scale>(
+    gemm_fn: GemmFn<T, Lhs, Rhs, Scale>,
+    m: usize,
+    n: usize,
+    k: usize,
+    mut dst: *mut T,
+    mut dst_cs: isize,
+    mut dst_rs: isize,
+    read_dst: bool,
+    mut lhs: *const Lhs,
+    mut lhs_cs: isize,
+    mut lhs_rs: isize,
+    mut rhs: *const Rhs,
+    mut rhs_cs: isize,
+    mut rhs_rs: isize,
+    alpha: Scale,
+    beta: Scale,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) -> Result<(), AllocError> {
     if dst_rs < 0 && m > 0 {
         dst = dst.wrapping_offset((m - 1) as isize * dst_rs);
         dst_rs = -dst_rs;
@@ -222,7 +1954,7 @@ pub unsafe fn gemm<T: 'static>(
         rhs_rs = -rhs_rs;
     }
 
-    gemm_dispatch(
+    gemm_fn(
         m,
         n,
         k,
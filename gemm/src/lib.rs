@@ -6,11 +6,36 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![warn(rust_2018_idioms)]
 
+pub mod blas;
+mod builder;
+mod bytes;
 mod gemm;
+mod mat;
+mod matrix;
+#[cfg(feature = "nalgebra")]
+mod nalgebra_ext;
+#[cfg(feature = "ndarray")]
+mod ndarray_ext;
 
+pub use crate::builder::Gemm;
+pub use crate::bytes::gemm_bytes;
+#[cfg(feature = "bf16")]
+pub use crate::gemm::bf16;
+pub use crate::gemm::{
+    c32, c64, f8e4m3, f8e5m2, gemm, gemm_compensated, gemm_fp8, gemm_i8, gemm_int_checked,
+    gemm_int_wrapping, gemm_interval, gemm_mixed, gemm_mod, gemm_or_and, gemm_quantized, gemm_ring,
+    gemm_tropical, gemm_wide, gemm_xnor_popcount, try_gemm, BitLayout, CompensatedScalar, Fp8Dst,
+    Fp8Scalar, Fp8Scales, GemmError, GemmScalar, IntScalar, MaxPlus, MinPlus, MixedScalar,
+    ModScalar, QuantParams, QuantizedScalar, RingScalar, TropicalScalar, WideScalar,
+};
 #[cfg(feature = "f16")]
-pub use crate::gemm::f16;
-pub use crate::gemm::{c32, c64, gemm};
+pub use crate::gemm::{f16, gemm_block_quant, gemm_f16_f32, BlockQ4, BlockQ8, QuantBlock, QK};
+pub use crate::mat::{matmul, MatMut, MatRef};
+pub use crate::matrix::{get_default_parallelism, set_default_parallelism, Matrix};
+#[cfg(feature = "nalgebra")]
+pub use crate::nalgebra_ext::gemm_nalgebra;
+#[cfg(feature = "ndarray")]
+pub use crate::ndarray_ext::{gemm_ndarray, gemm_ndarray_batched};
 pub use gemm_common::Parallelism;
 
 pub use gemm_common::gemm::{
@@ -29,6 +54,50 @@ mod tests {
     use alloc::{vec, vec::Vec};
     use num_traits::Float;
 
+    /// A depth that is split into several `kc` chunks, whatever the element size.
+    const DEEP_K: usize = 4099;
+
+    /// Calls `f` with the shapes and the parallelism settings shared by the tests of the extension
+    /// products: tiny and tall products, a single column, an empty depth, a depth that spans
+    /// several `kc` chunks, and products large enough to be split across threads, with the default
+    /// number of threads and with a fixed one, so that they are split even on machines with few
+    /// cores.
+    fn for_each_shape(f: impl FnMut(usize, usize, usize, Parallelism)) {
+        for_each_shape_and(&[], f);
+    }
+
+    /// Same as [`for_each_shape`], followed by the `extra` shapes that a test needs.
+    fn for_each_shape_and(
+        extra: &[(usize, usize, usize)],
+        mut f: impl FnMut(usize, usize, usize, Parallelism),
+    ) {
+        let shared = [
+            (4, 4, 4),
+            (63, 5, 2),
+            (63, 1, 10),
+            (5, 3, 0),
+            (33, 17, DEEP_K),
+            (257, 130, 65),
+        ];
+        for &(m, n, k) in shared.iter().chain(extra) {
+            for parallelism in [
+                Parallelism::None,
+                #[cfg(feature = "rayon")]
+                Parallelism::Rayon(0),
+                #[cfg(feature = "rayon")]
+                Parallelism::Rayon(3),
+            ] {
+                f(m, n, k, parallelism);
+            }
+        }
+    }
+
+    /// Half the distance between `x` rounded to `f32` and the next `f32` away from zero.
+    fn f32_half_ulp(x: f64) -> f64 {
+        let x = (x as f32).abs();
+        (f32::from_bits(x.to_bits() + 1) - x) as f64 / 2.0
+    }
+
     #[test]
     fn test_gemm_f16() {
         let mut mnks = vec![];
@@ -614,4 +683,2311 @@ mod tests {
             }
         }
     }
+
+    #[test]
+    fn test_matmul_f64() {
+        use core::mem::MaybeUninit;
+
+        for (m, n, k) in [(0, 4, 3), (4, 0, 3), (5, 7, 0), (17, 9, 13), (64, 33, 65)] {
+            #[cfg(feature = "std")]
+            dbg!(m, n, k);
+            let a_vec: Vec<f64> = (0..(m * k)).map(|_| rand::random()).collect();
+            let b_vec: Vec<f64> = (0..(k * n)).map(|_| rand::random()).collect();
+            let c_vec: Vec<f64> = (0..(m * n)).map(|_| rand::random()).collect();
+
+            // row-major lhs, column-major rhs with its columns in reverse order
+            let lhs = MatRef::from_slice(&a_vec, m, k, k as isize, 1);
+            let rhs = MatRef::from_slice(&b_vec, k, n, 1, -(k as isize));
+
+            for alpha in [None, Some(1.0), Some(2.3)] {
+                let mut expected = c_vec.clone();
+                let mut expected_view = MatMut::from_slice(&mut expected, m, n, -(n as isize), 1);
+                unsafe {
+                    gemm::gemm_fallback(
+                        m,
+                        n,
+                        k,
+                        expected_view.as_mut_ptr(),
+                        expected_view.col_stride(),
+                        expected_view.row_stride(),
+                        alpha.is_some(),
+                        lhs.as_ptr(),
+                        lhs.col_stride(),
+                        lhs.row_stride(),
+                        rhs.as_ptr(),
+                        rhs.col_stride(),
+                        rhs.row_stride(),
+                        alpha.unwrap_or(0.0),
+                        2.5,
+                    );
+                }
+
+                let mut c = c_vec.clone();
+                matmul(
+                    MatMut::from_slice(&mut c, m, n, -(n as isize), 1),
+                    lhs,
+                    rhs,
+                    alpha,
+                    2.5,
+                    false,
+                    false,
+                    false,
+                    Parallelism::None,
+                );
+                for (c, d) in c.iter().zip(expected.iter()) {
+                    assert_approx_eq::assert_approx_eq!(c, d);
+                }
+
+                if alpha.is_none() {
+                    let mut uninit = vec![MaybeUninit::<f64>::uninit(); m * n];
+                    matmul(
+                        MatMut::from_uninit_slice(&mut uninit, m, n, -(n as isize), 1),
+                        lhs,
+                        rhs,
+                        None,
+                        2.5,
+                        false,
+                        false,
+                        false,
+                        Parallelism::None,
+                    );
+                    for (c, d) in uninit.iter().zip(expected.iter()) {
+                        assert_approx_eq::assert_approx_eq!(unsafe { c.assume_init() }, d);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_mat_footprint_out_of_bounds() {
+        let data = [0.0f32; 5];
+        MatRef::from_slice(&data, 2, 3, 3, 1);
+    }
+
+    #[test]
+    fn test_mat_mut_aliasing_strides() {
+        use core::mem::MaybeUninit;
+        use std::panic::catch_unwind;
+
+        // zero stride, column stride smaller than the column extent, and the same with negative
+        // and transposed strides
+        for (nrows, ncols, rs, cs) in [
+            (2, 3, 0, 2),
+            (3, 2, 1, 0),
+            (4, 3, 1, 3),
+            (4, 3, -1, -3),
+            (3, 4, 3, 1),
+        ] {
+            assert!(catch_unwind(|| {
+                let mut data = [0.0f64; 64];
+                MatMut::from_slice(&mut data, nrows, ncols, rs, cs);
+            })
+            .is_err());
+            assert!(catch_unwind(|| {
+                let mut data = [MaybeUninit::<f64>::uninit(); 64];
+                MatMut::from_uninit_slice(&mut data, nrows, ncols, rs, cs);
+            })
+            .is_err());
+        }
+
+        // strides that only repeat along dimensions of size one, and disjoint strided layouts
+        for (nrows, ncols, rs, cs) in [
+            (1, 3, 0, 1),
+            (3, 1, 1, 0),
+            (1, 1, 0, 0),
+            (0, 3, 0, 0),
+            (4, 3, 1, 4),
+            (4, 3, -3, 1),
+            (4, 3, 2, 8),
+        ] {
+            let mut data = [0.0f64; 64];
+            MatMut::from_slice(&mut data, nrows, ncols, rs, cs);
+            let mut data = [MaybeUninit::<f64>::uninit(); 64];
+            MatMut::from_uninit_slice(&mut data, nrows, ncols, rs, cs);
+        }
+    }
+
+    #[test]
+    fn test_try_gemm() {
+        let (m, n, k) = (7, 5, 3);
+        let a_vec: Vec<f32> = (0..(m * k)).map(|_| rand::random()).collect();
+        let b_vec: Vec<f32> = (0..(k * n)).map(|_| rand::random()).collect();
+        let mut c_vec: Vec<f32> = (0..(m * n)).map(|_| rand::random()).collect();
+        let mut d_vec = c_vec.clone();
+
+        unsafe {
+            try_gemm(
+                m,
+                n,
+                k,
+                c_vec.as_mut_ptr(),
+                m as isize,
+                1,
+                true,
+                a_vec.as_ptr(),
+                m as isize,
+                1,
+                b_vec.as_ptr(),
+                k as isize,
+                1,
+                0.5,
+                2.0,
+                false,
+                false,
+                false,
+                Parallelism::None,
+            )
+            .unwrap();
+            gemm::gemm_fallback(
+                m,
+                n,
+                k,
+                d_vec.as_mut_ptr(),
+                m as isize,
+                1,
+                true,
+                a_vec.as_ptr(),
+                m as isize,
+                1,
+                b_vec.as_ptr(),
+                k as isize,
+                1,
+                0.5,
+                2.0,
+            );
+        }
+        for (c, d) in c_vec.iter().zip(d_vec.iter()) {
+            assert_approx_eq::assert_approx_eq!(c, d);
+        }
+
+        // dst overlaps lhs
+        let err = unsafe {
+            try_gemm(
+                m,
+                k,
+                k,
+                a_vec.as_ptr() as *mut f32,
+                m as isize,
+                1,
+                true,
+                a_vec.as_ptr(),
+                m as isize,
+                1,
+                b_vec.as_ptr(),
+                k as isize,
+                1,
+                1.0,
+                1.0,
+                false,
+                false,
+                false,
+                Parallelism::None,
+            )
+        };
+        assert_eq!(err, Err(GemmError::Aliasing));
+
+        let err = unsafe {
+            try_gemm(
+                m,
+                n,
+                k,
+                c_vec.as_mut_ptr(),
+                isize::MAX,
+                1,
+                true,
+                a_vec.as_ptr(),
+                m as isize,
+                1,
+                b_vec.as_ptr(),
+                k as isize,
+                1,
+                1.0,
+                1.0,
+                false,
+                false,
+                false,
+                Parallelism::None,
+            )
+        };
+        assert_eq!(err, Err(GemmError::Overflow));
+    }
+
+    #[test]
+    fn test_blas_zgemm() {
+        use crate::blas::{zgemm, Layout, Transpose};
+
+        let (m, n, k) = (6, 5, 4);
+        let alpha = c64::new(1.5, -0.5);
+        for layout in [Layout::RowMajor, Layout::ColMajor] {
+            for transa in [Transpose::No, Transpose::Trans, Transpose::ConjTrans] {
+                for transb in [Transpose::No, Transpose::Trans, Transpose::ConjTrans] {
+                    for beta in [c64::new(0.0, 0.0), c64::new(0.5, 2.0)] {
+                        // padded leading dimensions
+                        let (lda, ldb, ldc) = (m + k + 1, n + k + 2, m + n + 3);
+                        let a: Vec<c64> = (0..lda * (m + k))
+                            .map(|_| c64::new(rand::random(), rand::random()))
+                            .collect();
+                        let b: Vec<c64> = (0..ldb * (n + k))
+                            .map(|_| c64::new(rand::random(), rand::random()))
+                            .collect();
+                        let mut c: Vec<c64> = (0..ldc * (m + n))
+                            .map(|_| c64::new(rand::random(), rand::random()))
+                            .collect();
+
+                        let at = |x: &[c64], ld: usize, trans: Transpose, i: usize, j: usize| {
+                            let (i, j) = match trans {
+                                Transpose::No => (i, j),
+                                _ => (j, i),
+                            };
+                            let x = match layout {
+                                Layout::RowMajor => x[i * ld + j],
+                                Layout::ColMajor => x[i + j * ld],
+                            };
+                            if trans == Transpose::ConjTrans {
+                                x.conj()
+                            } else {
+                                x
+                            }
+                        };
+                        let mut expected = c.clone();
+                        for i in 0..m {
+                            for j in 0..n {
+                                let mut acc = c64::new(0.0, 0.0);
+                                for depth in 0..k {
+                                    acc += at(&a, lda, transa, i, depth)
+                                        * at(&b, ldb, transb, depth, j);
+                                }
+                                let idx = match layout {
+                                    Layout::RowMajor => i * ldc + j,
+                                    Layout::ColMajor => i + j * ldc,
+                                };
+                                expected[idx] = alpha * acc + beta * c[idx];
+                            }
+                        }
+
+                        zgemm(
+                            layout,
+                            transa,
+                            transb,
+                            m,
+                            n,
+                            k,
+                            alpha,
+                            &a,
+                            lda,
+                            &b,
+                            ldb,
+                            beta,
+                            &mut c,
+                            ldc,
+                            Parallelism::None,
+                        );
+                        for (c, d) in c.iter().zip(expected.iter()) {
+                            assert_approx_eq::assert_approx_eq!(c.re, d.re);
+                            assert_approx_eq::assert_approx_eq!(c.im, d.im);
+                        }
+                    }
+                }
+            }
+        }
+
+        // `a` and `b` are not read when alpha is zero, so their NaNs do not reach `c`
+        let nan = c64::new(f64::NAN, f64::NAN);
+        let (a, b) = (vec![nan; m * k], vec![nan; k * n]);
+        for beta in [c64::new(0.0, 0.0), c64::new(0.5, 2.0)] {
+            let c: Vec<c64> = (0..m * n)
+                .map(|_| c64::new(rand::random(), rand::random()))
+                .collect();
+            let mut dst = c.clone();
+            zgemm(
+                Layout::ColMajor,
+                Transpose::No,
+                Transpose::No,
+                m,
+                n,
+                k,
+                c64::new(0.0, 0.0),
+                &a,
+                m,
+                &b,
+                k,
+                beta,
+                &mut dst,
+                m,
+                Parallelism::None,
+            );
+            for (dst, c) in dst.iter().zip(&c) {
+                assert_eq!(*dst, beta * c);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gemm_builder() {
+        let (m, n, k) = (9, 4, 5);
+        let a_vec: Vec<c32> = (0..(m * k))
+            .map(|_| c32::new(rand::random(), rand::random()))
+            .collect();
+        let b_vec: Vec<c32> = (0..(k * n))
+            .map(|_| c32::new(rand::random(), rand::random()))
+            .collect();
+        let c_vec: Vec<c32> = (0..(m * n))
+            .map(|_| c32::new(rand::random(), rand::random()))
+            .collect();
+        let alpha = c32::new(0.5, 1.0);
+        let beta = c32::new(2.0, -1.5);
+
+        // row-major lhs and dst, column-major rhs
+        let desc = Gemm::new(m, n, k)
+            .lhs(a_vec.as_ptr(), k as isize, 1)
+            .rhs(b_vec.as_ptr(), 1, k as isize)
+            .alpha(alpha)
+            .beta(beta)
+            .conj_lhs();
+        assert_eq!(unsafe { desc.run() }, Err(GemmError::MissingOperand("dst")));
+
+        let mut expected = c_vec.clone();
+        unsafe {
+            gemm::gemm_cplx_fallback(
+                m,
+                n,
+                k,
+                expected.as_mut_ptr(),
+                1,
+                n as isize,
+                true,
+                a_vec.as_ptr(),
+                1,
+                k as isize,
+                b_vec.as_ptr(),
+                k as isize,
+                1,
+                alpha,
+                beta,
+                false,
+                true,
+                false,
+            );
+        }
+
+        let mut c = c_vec.clone();
+        let desc = desc.dst(c.as_mut_ptr(), n as isize, 1);
+        unsafe { desc.run().unwrap() };
+        for (c, d) in c.iter().zip(expected.iter()) {
+            assert_approx_eq::assert_approx_eq!(c.re, d.re, 1e-4);
+            assert_approx_eq::assert_approx_eq!(c.im, d.im, 1e-4);
+        }
+
+        // same strides, new pointers
+        let mut c = c_vec.clone();
+        unsafe {
+            desc.run_with(c.as_mut_ptr(), a_vec.as_ptr(), b_vec.as_ptr())
+                .unwrap()
+        };
+        for (c, d) in c.iter().zip(expected.iter()) {
+            assert_approx_eq::assert_approx_eq!(c.re, d.re, 1e-4);
+            assert_approx_eq::assert_approx_eq!(c.im, d.im, 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_matrix_f64() {
+        use crate::blas::Layout;
+
+        let (m, n, k) = (13, 6, 9);
+        for (dst_layout, lhs_layout, rhs_layout) in [
+            (Layout::ColMajor, Layout::RowMajor, Layout::ColMajor),
+            (Layout::RowMajor, Layout::ColMajor, Layout::RowMajor),
+        ] {
+            let a = Matrix::<f64>::from_fn(m, k, lhs_layout, |_, _| rand::random());
+            let b = Matrix::<f64>::from_fn(k, n, rhs_layout, |_, _| rand::random());
+            let c = Matrix::<f64>::from_fn(m, n, dst_layout, |_, _| rand::random());
+            assert_eq!(a.as_ptr() as usize % gemm_common::gemm::CACHELINE_ALIGN, 0);
+
+            let expected = |i: usize, j: usize| {
+                (0..k)
+                    .map(|depth| a[(i, depth)] * b[(depth, j)])
+                    .sum::<f64>()
+            };
+
+            let ab = &a * &b;
+            assert_eq!(ab.layout(), lhs_layout);
+
+            let mut dst = Matrix::zeros(m, n, dst_layout);
+            a.mul_into(&b, &mut dst);
+
+            let mut acc = c.clone();
+            acc.mul_add_assign(&a, &b);
+
+            for i in 0..m {
+                for j in 0..n {
+                    assert_approx_eq::assert_approx_eq!(ab[(i, j)], expected(i, j));
+                    assert_approx_eq::assert_approx_eq!(dst[(i, j)], expected(i, j));
+                    assert_approx_eq::assert_approx_eq!(acc[(i, j)], c[(i, j)] + expected(i, j));
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "ndarray")]
+    fn test_gemm_ndarray() {
+        use ndarray::{s, Array2, Array3};
+
+        let (batch, m, n, k) = (3, 11, 7, 5);
+        let a = Array3::<f32>::from_shape_fn((batch, m, k), |_| rand::random());
+        let b = Array3::<f32>::from_shape_fn((batch, n, k), |_| rand::random());
+        let c = Array3::<f32>::from_shape_fn((batch, m, n), |_| rand::random());
+
+        // reversed rows of lhs, transposed rhs, reversed columns of dst
+        let lhs = a.slice(s![.., ..;-1, ..]);
+        let rhs = b.view().permuted_axes([0, 2, 1]);
+
+        for alpha in [None, Some(0.5)] {
+            let mut dst = c.clone();
+            gemm_ndarray_batched(
+                dst.slice_mut(s![.., .., ..;-1]),
+                lhs,
+                rhs,
+                alpha,
+                2.0,
+                false,
+                false,
+                false,
+                Parallelism::None,
+            );
+
+            for i in 0..batch {
+                let mut expected: Array2<f32> = lhs
+                    .index_axis(ndarray::Axis(0), i)
+                    .dot(&rhs.index_axis(ndarray::Axis(0), i))
+                    * 2.0;
+                if let Some(alpha) = alpha {
+                    expected = expected + c.slice(s![i, .., ..;-1]).to_owned() * alpha;
+                }
+                for (x, y) in dst.slice(s![i, .., ..;-1]).iter().zip(expected.iter()) {
+                    assert_approx_eq::assert_approx_eq!(x, y, 1e-4);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "nalgebra")]
+    fn test_gemm_nalgebra() {
+        use nalgebra::{DMatrix, Matrix3x4, Matrix4x2};
+
+        // fixed dimensions
+        let lhs = Matrix3x4::<f64>::from_fn(|_, _| rand::random());
+        let rhs = Matrix4x2::<f64>::from_fn(|_, _| rand::random());
+        let mut dst = nalgebra::Matrix3x2::<f64>::zeros();
+        gemm_nalgebra(
+            &mut dst,
+            &lhs,
+            &rhs,
+            None,
+            1.0,
+            false,
+            false,
+            false,
+            Parallelism::None,
+        );
+        assert!((dst - lhs * rhs).norm() < 1e-10);
+
+        // dynamic dimensions, views and complex conjugation
+        let (m, n, k) = (13, 9, 6);
+        let a = DMatrix::<c64>::from_fn(k, m, |_, _| c64::new(rand::random(), rand::random()));
+        let b = DMatrix::<c64>::from_fn(k + 3, n, |_, _| c64::new(rand::random(), rand::random()));
+        let c = DMatrix::<c64>::from_fn(m, n + 2, |_, _| c64::new(rand::random(), rand::random()));
+        let lhs = a.transpose();
+        let rhs = b.rows(1, k);
+        let alpha = c64::new(0.5, 1.0);
+        let beta = c64::new(2.0, -1.0);
+
+        let mut dst = c.clone();
+        gemm_nalgebra(
+            &mut dst.columns_mut(2, n),
+            &lhs,
+            &rhs,
+            Some(alpha),
+            beta,
+            false,
+            true,
+            false,
+            Parallelism::None,
+        );
+        let expected = c.columns(2, n) * alpha + lhs.conjugate() * rhs * beta;
+        assert!((dst.columns(2, n) - expected).norm() < 1e-10);
+        assert_eq!(dst.columns(0, 2), c.columns(0, 2));
+    }
+
+    #[test]
+    fn test_gemm_bytes() {
+        #[derive(Copy, Clone)]
+        #[repr(C, packed)]
+        struct Sample {
+            _id: u16,
+            value: f64,
+        }
+
+        let size = core::mem::size_of::<Sample>() as isize;
+        // the operands can be empty, so the fields are not addressed through indexing
+        let field = |x: *const Sample| {
+            x.wrapping_byte_add(core::mem::offset_of!(Sample, value)) as *const f64
+        };
+        for_each_shape(|m, n, k, parallelism| {
+            let sample = |_| Sample {
+                _id: 0,
+                value: rand::random::<f64>() - 0.5,
+            };
+            // lhs is row-major, rhs and dst are column-major
+            let lhs: Vec<Sample> = (0..m * k).map(sample).collect();
+            let rhs: Vec<Sample> = (0..k * n).map(sample).collect();
+            let dst_init: Vec<Sample> = (0..m * n).map(sample).collect();
+            let value = |x: &[Sample], idx: usize| x[idx].value;
+
+            for read_dst in [false, true] {
+                let mut dst = dst_init.clone();
+                unsafe {
+                    gemm_bytes(
+                        m,
+                        n,
+                        k,
+                        field(dst.as_mut_ptr()) as *mut f64,
+                        m as isize * size,
+                        size,
+                        read_dst,
+                        field(lhs.as_ptr()),
+                        size,
+                        k as isize * size,
+                        field(rhs.as_ptr()),
+                        k as isize * size,
+                        size,
+                        0.5,
+                        2.0,
+                        false,
+                        false,
+                        false,
+                        parallelism,
+                    );
+                }
+
+                for i in 0..m {
+                    for j in 0..n {
+                        let mut expected = 0.0;
+                        for depth in 0..k {
+                            expected += value(&lhs, i * k + depth) * value(&rhs, depth + j * k);
+                        }
+                        expected *= 2.0;
+                        if read_dst {
+                            expected += 0.5 * value(&dst_init, i + j * m);
+                        }
+                        assert_approx_eq::assert_approx_eq!(
+                            value(&dst, i + j * m),
+                            expected,
+                            1e-12 * (k + 1) as f64
+                        );
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "bf16")]
+    fn test_gemm_bf16() {
+        for (m, n, k) in [
+            (4, 4, 4),
+            (63, 5, 2),
+            (63, 1, 10),
+            (1, 63, 10),
+            (257, 130, 65),
+        ] {
+            for parallelism in [
+                Parallelism::None,
+                #[cfg(feature = "rayon")]
+                Parallelism::Rayon(0),
+            ] {
+                let random = |len| -> Vec<bf16> {
+                    (0..len)
+                        .map(|_| bf16::from_f32(rand::random::<f32>() - 0.5))
+                        .collect()
+                };
+                let (a, b, c) = (random(m * k), random(k * n), random(m * n));
+                let (alpha, beta) = (bf16::from_f32(0.5), bf16::from_f32(2.0));
+
+                let mut dst = c.clone();
+                unsafe {
+                    gemm::gemm(
+                        m,
+                        n,
+                        k,
+                        dst.as_mut_ptr(),
+                        m as isize,
+                        1,
+                        true,
+                        a.as_ptr(),
+                        m as isize,
+                        1,
+                        b.as_ptr(),
+                        1,
+                        n as isize,
+                        alpha,
+                        beta,
+                        false,
+                        false,
+                        false,
+                        parallelism,
+                    );
+                }
+
+                for i in 0..m {
+                    for j in 0..n {
+                        let mut expected = 0.0f32;
+                        for depth in 0..k {
+                            expected += a[i + depth * m].to_f32() * b[depth * n + j].to_f32();
+                        }
+                        let expected =
+                            alpha.to_f32() * c[i + j * m].to_f32() + beta.to_f32() * expected;
+                        let actual = dst[i + j * m].to_f32();
+                        // bf16 has 8 bits of mantissa
+                        assert!((actual - expected).abs() <= 1e-2 * expected.abs().max(1.0));
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "f16")]
+    fn test_gemm_f16_f32() {
+        for (m, n, k) in [
+            (4, 4, 4),
+            (63, 5, 2),
+            (63, 1, 10),
+            (1, 63, 10),
+            (257, 130, 65),
+        ] {
+            for parallelism in [
+                Parallelism::None,
+                #[cfg(feature = "rayon")]
+                Parallelism::Rayon(0),
+            ] {
+                let random = |len| -> Vec<f16> {
+                    (0..len)
+                        .map(|_| f16::from_f32(rand::random::<f32>() - 0.5))
+                        .collect()
+                };
+                let (a, b) = (random(m * k), random(k * n));
+                let c: Vec<f32> = (0..m * n).map(|_| rand::random()).collect();
+
+                for read_dst in [false, true] {
+                    let mut dst = c.clone();
+                    unsafe {
+                        gemm_f16_f32(
+                            m,
+                            n,
+                            k,
+                            dst.as_mut_ptr(),
+                            m as isize,
+                            1,
+                            read_dst,
+                            a.as_ptr(),
+                            1,
+                            k as isize,
+                            b.as_ptr(),
+                            k as isize,
+                            1,
+                            0.5,
+                            2.0,
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let mut expected = 0.0f32;
+                            for depth in 0..k {
+                                expected += a[i * k + depth].to_f32() * b[depth + j * k].to_f32();
+                            }
+                            expected *= 2.0;
+                            if read_dst {
+                                expected += 0.5 * c[i + j * m];
+                            }
+                            // much tighter than the precision of f16
+                            assert_approx_eq::assert_approx_eq!(dst[i + j * m], expected, 1e-5);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "f16")]
+    fn test_gemm_block_quant() {
+        fn check<B: QuantBlock>(random_block: impl Fn() -> B) {
+            for (m, n, k) in [
+                (4, 4, 32),
+                (63, 5, 64),
+                (1, 63, 96),
+                (5, 3, 0),
+                (257, 130, 1024),
+            ] {
+                for parallelism in [
+                    Parallelism::None,
+                    #[cfg(feature = "rayon")]
+                    Parallelism::Rayon(0),
+                ] {
+                    let a: Vec<f32> = (0..m * k).map(|_| rand::random::<f32>() - 0.5).collect();
+                    let b: Vec<B> = (0..k / QK * n).map(|_| random_block()).collect();
+                    let c: Vec<f32> = (0..m * n).map(|_| rand::random()).collect();
+
+                    // column-major dequantized copy of rhs
+                    let mut b_f32 = vec![0.0f32; k * n];
+                    for (block, dst) in b.iter().zip(b_f32.chunks_exact_mut(QK)) {
+                        block.dequantize(dst.try_into().unwrap());
+                    }
+
+                    for read_dst in [false, true] {
+                        let mut dst = c.clone();
+                        unsafe {
+                            gemm_block_quant(
+                                m,
+                                n,
+                                k,
+                                dst.as_mut_ptr(),
+                                m as isize,
+                                1,
+                                read_dst,
+                                a.as_ptr(),
+                                1,
+                                k as isize,
+                                b.as_ptr(),
+                                (k / QK) as isize,
+                                0.5,
+                                2.0,
+                                parallelism,
+                            )
+                            .unwrap();
+                        }
+
+                        for i in 0..m {
+                            for j in 0..n {
+                                // the rounding errors are relative to the magnitude of the
+                                // terms, which may cancel out
+                                let mut expected = 0.0f64;
+                                let mut magnitude = 0.0f64;
+                                for depth in 0..k {
+                                    let term =
+                                        a[i * k + depth] as f64 * b_f32[depth + j * k] as f64;
+                                    expected += term;
+                                    magnitude += term.abs();
+                                }
+                                expected *= 2.0;
+                                magnitude *= 2.0;
+                                if read_dst {
+                                    expected += 0.5 * c[i + j * m] as f64;
+                                    magnitude += 0.5 * c[i + j * m].abs() as f64;
+                                }
+                                let actual = dst[i + j * m] as f64;
+                                assert!((actual - expected).abs() < 1e-5 * (1.0 + magnitude));
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        let random_scale = || f16::from_f32(rand::random::<f32>() * 0.1);
+        check(|| BlockQ4 {
+            scale: random_scale(),
+            qs: rand::random(),
+        });
+        check(|| BlockQ8 {
+            scale: random_scale(),
+            qs: rand::random(),
+        });
+
+        // nibble order and offset of the 4-bit format
+        let mut qs = [0u8; QK / 2];
+        qs[0] = 0xF0;
+        qs[1] = 0x09;
+        let mut block = [0.0f32; QK];
+        BlockQ4 {
+            scale: f16::from_f32(0.5),
+            qs,
+        }
+        .dequantize(&mut block);
+        assert_eq!(block[0], -4.0);
+        assert_eq!(block[1], 0.5);
+        assert_eq!(block[QK / 2], 3.5);
+        assert_eq!(block[QK / 2 + 1], -4.0);
+
+        // the depth must be made of whole blocks
+        let a = [0.0f32; QK + 1];
+        let b = [BlockQ8 {
+            scale: f16::ZERO,
+            qs: [0; QK],
+        }; 2];
+        let mut dst = [0.0f32];
+        let err = unsafe {
+            gemm_block_quant(
+                1,
+                1,
+                QK + 1,
+                dst.as_mut_ptr(),
+                1,
+                1,
+                false,
+                a.as_ptr(),
+                1,
+                1,
+                b.as_ptr(),
+                2,
+                0.0,
+                1.0,
+                Parallelism::None,
+            )
+        };
+        assert_eq!(err, Err(GemmError::InvalidDepth));
+    }
+
+    #[test]
+    fn test_gemm_fp8() {
+        // every finite code round trips
+        for bits in 0..=u8::MAX {
+            let x = f8e4m3(bits);
+            if bits & 0x7F != 0x7F {
+                assert_eq!(f8e4m3::from_f32(x.to_f32()), x);
+            }
+            let x = f8e5m2(bits);
+            if bits & 0x7F < 0x7C {
+                assert_eq!(f8e5m2::from_f32(x.to_f32()), x);
+            }
+        }
+        assert_eq!(f8e4m3::MAX.to_f32(), 448.0);
+        assert_eq!(f8e5m2::MAX.to_f32(), 57344.0);
+        assert_eq!(f8e4m3(0x01).to_f32(), 2.0f32.powi(-9));
+        assert_eq!(f8e5m2(0x01).to_f32(), 2.0f32.powi(-16));
+        assert_eq!(f8e5m2::INFINITY.to_f32(), f32::INFINITY);
+        assert!(f8e4m3::NAN.to_f32().is_nan());
+
+        // ties round to even, in the normal and subnormal ranges
+        assert_eq!(f8e4m3::from_f32(1.0625).to_f32(), 1.0);
+        assert_eq!(f8e4m3::from_f32(1.1875).to_f32(), 1.25);
+        assert_eq!(f8e4m3::from_f32(2.0f32.powi(-10)).to_f32(), 0.0);
+        assert_eq!(f8e4m3::from_f32(3.0 * 2.0f32.powi(-10)), f8e4m3(0x02));
+        assert_eq!(f8e5m2::from_f32(1.125).to_f32(), 1.0);
+        assert_eq!(f8e5m2::from_f32(-1.375).to_f32(), -1.5);
+
+        // out of range values saturate
+        assert_eq!(f8e4m3::from_f32(500.0), f8e4m3::MAX);
+        assert_eq!(f8e4m3::from_f32(-f32::INFINITY).to_f32(), -448.0);
+        assert_eq!(f8e5m2::from_f32(1e9), f8e5m2::MAX);
+        assert_eq!(f8e5m2::from_f32(f32::INFINITY), f8e5m2::MAX);
+
+        // `rel` is half the distance between consecutive normal values of `Dst` relative to their
+        // magnitude, and `abs` half the distance between its subnormal values, zero for `f32`
+        fn check<Lhs: Fp8Scalar<Rhs, Dst>, Rhs: Fp8Dst, Dst: Fp8Dst>(rel: f64, abs: f64) {
+            let scales = Fp8Scales {
+                lhs: 0.5,
+                rhs: 0.25,
+                dst: 2.0,
+            };
+            let (alpha, beta) = (2.0, -1.5);
+            let dst_scale = Dst::dst_scale(scales) as f64;
+            let max = Dst::from_f32(f32::MAX).to_f32() as f64;
+
+            for_each_shape(|m, n, k, parallelism| {
+                let a: Vec<Lhs> = (0..m * k)
+                    .map(|_| Lhs::from_f32(rand::random::<f32>() * 4.0 - 2.0))
+                    .collect();
+                let b: Vec<Rhs> = (0..k * n)
+                    .map(|_| Rhs::from_f32(rand::random::<f32>() * 4.0 - 2.0))
+                    .collect();
+                let c: Vec<Dst> = (0..m * n)
+                    .map(|_| Dst::from_f32(rand::random::<f32>() * 4.0 - 2.0))
+                    .collect();
+
+                for read_dst in [false, true] {
+                    // `dst` is column-major, then row-major
+                    for (dst_cs, dst_rs) in [(m as isize, 1), (1, n as isize)] {
+                        let mut dst: Vec<Dst> = (0..m * n)
+                            .map(|idx| {
+                                let (i, j) = if dst_rs == 1 {
+                                    (idx % m, idx / m)
+                                } else {
+                                    (idx / n, idx % n)
+                                };
+                                c[i + j * m]
+                            })
+                            .collect();
+                        unsafe {
+                            gemm_fp8(
+                                m,
+                                n,
+                                k,
+                                dst.as_mut_ptr(),
+                                dst_cs,
+                                dst_rs,
+                                read_dst,
+                                a.as_ptr(),
+                                1,
+                                k as isize,
+                                b.as_ptr(),
+                                k as isize,
+                                1,
+                                alpha,
+                                beta,
+                                scales,
+                                parallelism,
+                            );
+                        }
+
+                        for i in 0..m {
+                            for j in 0..n {
+                                let mut expected = 0.0f64;
+                                let mut magnitude = 0.0f64;
+                                for depth in 0..k {
+                                    let prod = a[i * k + depth].to_f32() as f64
+                                        * b[depth + j * k].to_f32() as f64;
+                                    expected += prod;
+                                    magnitude += prod.abs();
+                                }
+                                let factor = (beta * scales.lhs * scales.rhs) as f64 / dst_scale;
+                                expected *= factor;
+                                magnitude *= factor.abs();
+                                if read_dst {
+                                    let c = alpha as f64 * c[i + j * m].to_f32() as f64;
+                                    expected += c;
+                                    magnitude += c.abs();
+                                }
+
+                                // the `f32` accumulation error, followed by one rounding to the
+                                // destination type, which saturates to its finite range
+                                let err = (k + 2) as f64 * f32::EPSILON as f64 * magnitude;
+                                let tol = err + rel * (expected.abs() + err) + abs;
+                                let expected = expected.clamp(-max, max);
+                                let actual = dst
+                                    [(i as isize * dst_rs + j as isize * dst_cs) as usize]
+                                    .to_f32() as f64;
+                                assert!((actual - expected).abs() <= tol);
+                            }
+                        }
+                    }
+                }
+            });
+        }
+
+        fn check_dst<Dst: Fp8Dst>(rel: f64, abs: f64)
+        where
+            f8e4m3: Fp8Scalar<f8e4m3, Dst> + Fp8Scalar<f8e5m2, Dst>,
+            f8e5m2: Fp8Scalar<f8e4m3, Dst> + Fp8Scalar<f8e5m2, Dst>,
+        {
+            check::<f8e4m3, f8e4m3, Dst>(rel, abs);
+            check::<f8e4m3, f8e5m2, Dst>(rel, abs);
+            check::<f8e5m2, f8e4m3, Dst>(rel, abs);
+            check::<f8e5m2, f8e5m2, Dst>(rel, abs);
+        }
+
+        check_dst::<f32>(0.0, 0.0);
+        check_dst::<f8e4m3>(2.0f64.powi(-4), 2.0f64.powi(-10));
+        check_dst::<f8e5m2>(2.0f64.powi(-3), 2.0f64.powi(-17));
+    }
+
+    #[test]
+    fn test_gemm_wide() {
+        for_each_shape(|m, n, k, parallelism| {
+            let random =
+                |len| -> Vec<f32> { (0..len).map(|_| rand::random::<f32>() - 0.5).collect() };
+            let (a, b) = (random(m * k), random(k * n));
+            let c: Vec<f64> = (0..m * n).map(|_| rand::random()).collect();
+
+            for read_dst in [false, true] {
+                let mut dst = c.clone();
+                unsafe {
+                    gemm_wide(
+                        m,
+                        n,
+                        k,
+                        dst.as_mut_ptr(),
+                        1,
+                        n as isize,
+                        read_dst,
+                        a.as_ptr(),
+                        1,
+                        k as isize,
+                        b.as_ptr(),
+                        k as isize,
+                        1,
+                        0.5,
+                        2.0,
+                        false,
+                        false,
+                        false,
+                        parallelism,
+                    );
+                }
+
+                for i in 0..m {
+                    for j in 0..n {
+                        let mut expected = 0.0f64;
+                        for depth in 0..k {
+                            expected += a[i * k + depth] as f64 * b[depth + j * k] as f64;
+                        }
+                        expected *= 2.0;
+                        if read_dst {
+                            expected += 0.5 * c[i * n + j];
+                        }
+                        // much tighter than the precision of f32
+                        assert_approx_eq::assert_approx_eq!(dst[i * n + j], expected, 1e-12);
+                    }
+                }
+            }
+
+            let random = |len| -> Vec<c32> {
+                (0..len)
+                    .map(|_| c32::new(rand::random(), rand::random()))
+                    .collect()
+            };
+            let (a, b, c) = (random(m * k), random(k * n), random(m * n));
+            let widen = |z: c32| c64::new(z.re as f64, z.im as f64);
+            let (alpha, beta) = (c32::new(0.5, -1.0), c32::new(2.0, 0.25));
+
+            for conj_dst in [false, true] {
+                for conj_lhs in [false, true] {
+                    for conj_rhs in [false, true] {
+                        let mut dst = c.clone();
+                        unsafe {
+                            gemm_wide(
+                                m,
+                                n,
+                                k,
+                                dst.as_mut_ptr(),
+                                m as isize,
+                                1,
+                                true,
+                                a.as_ptr(),
+                                m as isize,
+                                1,
+                                b.as_ptr(),
+                                k as isize,
+                                1,
+                                alpha,
+                                beta,
+                                conj_dst,
+                                conj_lhs,
+                                conj_rhs,
+                                parallelism,
+                            );
+                        }
+
+                        let maybe_conj = |z: c64, conj: bool| if conj { z.conj() } else { z };
+                        for i in 0..m {
+                            for j in 0..n {
+                                let mut acc = c64::new(0.0, 0.0);
+                                let mut magnitude = 0.0;
+                                for depth in 0..k {
+                                    let prod = maybe_conj(widen(a[i + depth * m]), conj_lhs)
+                                        * maybe_conj(widen(b[depth + j * k]), conj_rhs);
+                                    acc += prod;
+                                    magnitude += prod.norm();
+                                }
+                                let expected = widen(alpha)
+                                    * maybe_conj(widen(c[i + j * m]), conj_dst)
+                                    + widen(beta) * acc;
+                                let actual = widen(dst[i + j * m]);
+                                // only rounded once, when stored to c32, including when the
+                                // depth spans several chunks
+                                let tol = 1e-13 * (1.0 + magnitude);
+                                assert!(
+                                    (actual.re - expected.re).abs()
+                                        <= f32_half_ulp(expected.re) + tol
+                                );
+                                assert!(
+                                    (actual.im - expected.im).abs()
+                                        <= f32_half_ulp(expected.im) + tol
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    #[test]
+    fn test_gemm_i8() {
+        for_each_shape(|m, n, k, parallelism| {
+            let random = |len| -> Vec<i8> { (0..len).map(|_| rand::random()).collect() };
+            let (a, b) = (random(m * k), random(k * n));
+            let c: Vec<i32> = (0..m * n).map(|_| rand::random::<i16>() as i32).collect();
+
+            for read_dst in [false, true] {
+                let mut dst = c.clone();
+                unsafe {
+                    gemm_i8(
+                        m,
+                        n,
+                        k,
+                        dst.as_mut_ptr(),
+                        m as isize,
+                        1,
+                        read_dst,
+                        a.as_ptr(),
+                        1,
+                        k as isize,
+                        b.as_ptr(),
+                        k as isize,
+                        1,
+                        3,
+                        -2,
+                        parallelism,
+                    );
+                }
+
+                for i in 0..m {
+                    for j in 0..n {
+                        let mut expected = 0i32;
+                        for depth in 0..k {
+                            expected += a[i * k + depth] as i32 * b[depth + j * k] as i32;
+                        }
+                        expected *= -2;
+                        if read_dst {
+                            expected += 3 * c[i + j * m];
+                        }
+                        assert_eq!(dst[i + j * m], expected);
+                    }
+                }
+            }
+        });
+
+        // largest magnitude products over the longest supported depth
+        let k = 1 << 16;
+        let (a, b) = (vec![i8::MIN; 2 * k], vec![i8::MIN; k]);
+        let mut dst = [0i32; 2];
+        unsafe {
+            gemm_i8(
+                2,
+                1,
+                k,
+                dst.as_mut_ptr(),
+                2,
+                1,
+                false,
+                a.as_ptr(),
+                2,
+                1,
+                b.as_ptr(),
+                1,
+                1,
+                0,
+                1,
+                Parallelism::None,
+            );
+        }
+        assert_eq!(dst, [1 << 30; 2]);
+
+        // the scaling by `alpha` and `beta` wraps
+        let mut dst = [i32::MAX];
+        unsafe {
+            gemm_i8(
+                1,
+                1,
+                1,
+                dst.as_mut_ptr(),
+                1,
+                1,
+                true,
+                [i8::MIN].as_ptr(),
+                1,
+                1,
+                [i8::MIN].as_ptr(),
+                1,
+                1,
+                2,
+                1 << 17,
+                Parallelism::None,
+            );
+        }
+        assert_eq!(dst, [i32::MAX - 1]);
+    }
+
+    #[test]
+    fn test_gemm_quantized() {
+        // `acc × 2^-shift`, rounded to the nearest integer with ties to even
+        fn shift_round(acc: i32, shift: u32) -> i32 {
+            let floor = acc >> shift;
+            let rem = acc - (floor << shift);
+            let half = (1 << shift) / 2;
+            if shift > 0 && (rem > half || (rem == half && floor & 1 == 1)) {
+                floor + 1
+            } else {
+                floor
+            }
+        }
+
+        for_each_shape(|m, n, k, parallelism| {
+            let a: Vec<u8> = (0..m * k).map(|_| rand::random()).collect();
+            let b: Vec<i8> = (0..k * n).map(|_| rand::random()).collect();
+            // powers of two, so that the requantization is exact and can be checked with integer
+            // arithmetic: the multiplier of the column `j` is `2^-(4 + shifts[j])`
+            let shifts: Vec<u32> = (0..n).map(|j| (j % 8) as u32).collect();
+            let rhs_scales: Vec<f32> = shifts.iter().map(|&s| 1.0 / (1 << s) as f32).collect();
+            let rhs_zero_points: Vec<i8> = (0..n).map(|_| rand::random()).collect();
+
+            for per_channel in [false, true] {
+                let params = QuantParams {
+                    lhs_scale: 0.5,
+                    lhs_zero_point: rand::random(),
+                    rhs_scales: if per_channel {
+                        &rhs_scales
+                    } else {
+                        &rhs_scales[..1]
+                    },
+                    rhs_zero_points: if per_channel {
+                        &rhs_zero_points
+                    } else {
+                        &rhs_zero_points[..1]
+                    },
+                    dst_scale: 8.0,
+                    dst_zero_point: 100,
+                };
+
+                let mut expected = vec![0i32; m * n];
+                let mut dst_i32 = vec![0i32; m * n];
+                let mut dst_u8 = vec![0u8; m * n];
+                unsafe {
+                    ::gemm_i8::quantized::gemm_u8i8_reference(
+                        m,
+                        n,
+                        k,
+                        expected.as_mut_ptr(),
+                        m as isize,
+                        1,
+                        a.as_ptr(),
+                        1,
+                        k as isize,
+                        params.lhs_zero_point,
+                        b.as_ptr(),
+                        k as isize,
+                        1,
+                        params.rhs_zero_points,
+                    );
+                    gemm_quantized(
+                        m,
+                        n,
+                        k,
+                        dst_i32.as_mut_ptr(),
+                        m as isize,
+                        1,
+                        a.as_ptr(),
+                        1,
+                        k as isize,
+                        b.as_ptr(),
+                        k as isize,
+                        1,
+                        &params,
+                        parallelism,
+                    );
+                    gemm_quantized(
+                        m,
+                        n,
+                        k,
+                        dst_u8.as_mut_ptr(),
+                        1,
+                        n as isize,
+                        a.as_ptr(),
+                        1,
+                        k as isize,
+                        b.as_ptr(),
+                        k as isize,
+                        1,
+                        &params,
+                        parallelism,
+                    );
+                }
+
+                assert_eq!(dst_i32, expected);
+                for i in 0..m {
+                    for j in 0..n {
+                        let shift = 4 + shifts[if per_channel { j } else { 0 }];
+                        let requantized = shift_round(expected[i + j * m], shift) + 100;
+                        assert_eq!(dst_u8[i * n + j] as i32, requantized.clamp(0, 255));
+                    }
+                }
+            }
+        });
+
+        // ties round to even, and out of range values saturate
+        assert_eq!(u8::requantize(5, 0.5, 0), 2);
+        assert_eq!(u8::requantize(7, 0.5, 0), 4);
+        assert_eq!(i8::requantize(-5, 0.5, 0), -2);
+        assert_eq!(u8::requantize(-10, 1.0, 3), 0);
+        assert_eq!(i8::requantize(1000, 1.0, 0), 127);
+    }
+
+    #[test]
+    fn test_gemm_ring() {
+        const P: u32 = 65521;
+
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Mod(u32);
+
+        impl core::ops::Add for Mod {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Mod((self.0 + rhs.0) % P)
+            }
+        }
+        impl core::ops::Mul for Mod {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Mod(((self.0 as u64 * rhs.0 as u64) % P as u64) as u32)
+            }
+        }
+        impl num_traits::Zero for Mod {
+            fn zero() -> Self {
+                Mod(0)
+            }
+            fn is_zero(&self) -> bool {
+                self.0 == 0
+            }
+        }
+        impl num_traits::One for Mod {
+            fn one() -> Self {
+                Mod(1)
+            }
+        }
+
+        // rationals in lowest terms, whose zero `0/1` is not the all-zero bit pattern: `0/0` would
+        // make the reduction divide by zero
+        #[derive(Copy, Clone, Debug, PartialEq)]
+        struct Rational {
+            num: i64,
+            den: i64,
+        }
+
+        impl Rational {
+            fn new(num: i64, den: i64) -> Self {
+                fn gcd(a: i64, b: i64) -> i64 {
+                    if b == 0 {
+                        a.abs()
+                    } else {
+                        gcd(b, a % b)
+                    }
+                }
+                let g = gcd(num, den) * den.signum();
+                Rational {
+                    num: num / g,
+                    den: den / g,
+                }
+            }
+        }
+
+        impl core::ops::Add for Rational {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Rational::new(self.num * rhs.den + rhs.num * self.den, self.den * rhs.den)
+            }
+        }
+        impl core::ops::Mul for Rational {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Rational::new(self.num * rhs.num, self.den * rhs.den)
+            }
+        }
+        impl num_traits::Zero for Rational {
+            fn zero() -> Self {
+                Rational { num: 0, den: 1 }
+            }
+            fn is_zero(&self) -> bool {
+                self.num == 0
+            }
+        }
+        impl num_traits::One for Rational {
+            fn one() -> Self {
+                Rational { num: 1, den: 1 }
+            }
+        }
+
+        // dual numbers `re + eps × ε` with `ε² = 0`, which do not implement `PartialEq`
+        #[derive(Copy, Clone, Debug)]
+        struct Dual {
+            re: i64,
+            eps: i64,
+        }
+
+        impl core::ops::Add for Dual {
+            type Output = Self;
+            fn add(self, rhs: Self) -> Self {
+                Dual {
+                    re: self.re + rhs.re,
+                    eps: self.eps + rhs.eps,
+                }
+            }
+        }
+        impl core::ops::Mul for Dual {
+            type Output = Self;
+            fn mul(self, rhs: Self) -> Self {
+                Dual {
+                    re: self.re * rhs.re,
+                    eps: self.re * rhs.eps + self.eps * rhs.re,
+                }
+            }
+        }
+        impl num_traits::Zero for Dual {
+            fn zero() -> Self {
+                Dual { re: 0, eps: 0 }
+            }
+            fn is_zero(&self) -> bool {
+                self.re == 0 && self.eps == 0
+            }
+        }
+        impl num_traits::One for Dual {
+            fn one() -> Self {
+                Dual { re: 1, eps: 0 }
+            }
+        }
+
+        // the results are compared through `key`, since `T` may not implement `PartialEq`
+        fn check<T: RingScalar + core::fmt::Debug, K: PartialEq + core::fmt::Debug>(
+            random: impl Fn() -> T,
+            alpha: T,
+            beta: T,
+            key: impl Fn(T) -> K,
+        ) {
+            for_each_shape(|m, n, k, parallelism| {
+                let random = |len| -> Vec<T> { (0..len).map(|_| random()).collect() };
+                let (a, b, c) = (random(m * k), random(k * n), random(m * n));
+
+                for (read_dst, row_major_dst) in [(false, false), (true, false), (true, true)] {
+                    let (dst_cs, dst_rs) = if row_major_dst { (1, n) } else { (m, 1) };
+                    let mut dst = c.clone();
+                    unsafe {
+                        gemm_ring(
+                            m,
+                            n,
+                            k,
+                            dst.as_mut_ptr(),
+                            dst_cs as isize,
+                            dst_rs as isize,
+                            read_dst,
+                            a.as_ptr(),
+                            1,
+                            k as isize,
+                            b.as_ptr(),
+                            k as isize,
+                            1,
+                            alpha,
+                            beta,
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let mut expected = T::zero();
+                            for depth in 0..k {
+                                expected = expected + a[i * k + depth] * b[depth + j * k];
+                            }
+                            expected = beta * expected;
+                            let idx = i * dst_rs + j * dst_cs;
+                            if read_dst {
+                                expected = expected + alpha * c[idx];
+                            }
+                            assert_eq!(key(dst[idx]), key(expected));
+                        }
+                    }
+                }
+            });
+        }
+
+        check(|| Mod(rand::random::<u32>() % P), Mod(3), Mod(P - 2), |x| x);
+        // power of two denominators, so that the sums stay small
+        check(
+            || Rational::new(rand::random::<i64>() % 16, 1 << (rand::random::<u32>() % 3)),
+            Rational::new(3, 2),
+            Rational::new(-1, 4),
+            |x| x,
+        );
+        check(
+            || Dual {
+                re: rand::random::<i64>() % 16,
+                eps: rand::random::<i64>() % 16,
+            },
+            Dual { re: 1, eps: 0 },
+            Dual { re: -2, eps: 3 },
+            |x| (x.re, x.eps),
+        );
+    }
+
+    #[test]
+    fn test_gemm_tropical() {
+        // small integers, so that every sum is exact regardless of the evaluation order. With
+        // `special`, a few elements are the infinity opposite to the additive identity, or NaN,
+        // which must propagate to the result
+        fn check<
+            T: TropicalScalar
+                + core::ops::Add<Output = T>
+                + core::ops::Mul<Output = T>
+                + PartialEq
+                + core::fmt::Debug,
+        >(
+            zero: T,
+            from_f64: impl Fn(f64) -> T,
+            special: bool,
+        ) {
+            for_each_shape(|m, n, k, parallelism| {
+                // missing edges are encoded by the additive identity
+                let random = |len| -> Vec<T> {
+                    (0..len)
+                        .map(|_| {
+                            let x = rand::random::<u16>();
+                            if special && x < 64 {
+                                from_f64(f64::INFINITY)
+                            } else if special && x < 128 {
+                                from_f64(f64::NEG_INFINITY)
+                            } else if special && x < 192 {
+                                from_f64(f64::NAN)
+                            } else if x % 256 < 32 {
+                                zero
+                            } else {
+                                from_f64((x % 256) as f64 - 128.0)
+                            }
+                        })
+                        .collect()
+                };
+                let (a, b, c) = (random(m * k), random(k * n), random(m * n));
+                let (alpha, beta) = (from_f64(3.0), from_f64(-2.0));
+                #[allow(clippy::eq_op)]
+                let same = |x: T, y: T| x == y || (x != x && y != y);
+
+                for (read_dst, row_major_dst) in [(false, false), (true, false), (true, true)] {
+                    let (dst_cs, dst_rs) = if row_major_dst { (1, n) } else { (m, 1) };
+                    let mut dst = c.clone();
+                    unsafe {
+                        gemm_tropical(
+                            m,
+                            n,
+                            k,
+                            dst.as_mut_ptr(),
+                            dst_cs as isize,
+                            dst_rs as isize,
+                            read_dst,
+                            a.as_ptr(),
+                            1,
+                            k as isize,
+                            b.as_ptr(),
+                            k as isize,
+                            1,
+                            alpha,
+                            beta,
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let mut expected = zero;
+                            for depth in 0..k {
+                                expected = expected + a[i * k + depth] * b[depth + j * k];
+                            }
+                            expected = beta * expected;
+                            let idx = i * dst_rs + j * dst_cs;
+                            if read_dst {
+                                expected = expected + alpha * c[idx];
+                            }
+                            assert!(same(dst[idx], expected));
+                        }
+                    }
+                }
+            });
+        }
+
+        for special in [false, true] {
+            check(MinPlus::<f32>::ZERO, |x| MinPlus(x as f32), special);
+            check(MaxPlus::<f32>::ZERO, |x| MaxPlus(x as f32), special);
+            check(MinPlus::<f64>::ZERO, MinPlus, special);
+            check(MaxPlus::<f64>::ZERO, MaxPlus, special);
+        }
+
+        // the sum is NaN if either operand is, and mixed infinities give NaN products
+        for (x, y) in [(f64::NAN, 1.0), (1.0, f64::NAN), (f64::NAN, f64::INFINITY)] {
+            assert!((MinPlus(x) + MinPlus(y)).0.is_nan());
+            assert!((MaxPlus(x) + MaxPlus(y)).0.is_nan());
+        }
+        assert!((MinPlus(f64::INFINITY) * MinPlus(f64::NEG_INFINITY))
+            .0
+            .is_nan());
+        assert_eq!(
+            MinPlus(f32::NEG_INFINITY) + MinPlus(f32::INFINITY),
+            MinPlus(f32::NEG_INFINITY)
+        );
+        assert_eq!(
+            MaxPlus(f32::NEG_INFINITY) + MaxPlus(f32::INFINITY),
+            MaxPlus(f32::INFINITY)
+        );
+    }
+
+    #[test]
+    fn test_gemm_mod() {
+        fn check<T: ModScalar + Into<u128> + TryFrom<u128> + PartialEq + core::fmt::Debug>(p: T) {
+            let p_wide: u128 = p.into();
+            let residue = |x: u128| -> T { T::try_from(x % p_wide).ok().unwrap() };
+            // mostly large residues, so that the accumulators come close to overflowing
+            let random = |len| -> Vec<T> {
+                (0..len)
+                    .map(|_| {
+                        let x = rand::random::<u64>() as u128;
+                        residue(if x & 3 == 0 {
+                            x
+                        } else {
+                            p_wide - 1 - x % p_wide.min(4)
+                        })
+                    })
+                    .collect()
+            };
+
+            for_each_shape_and(&[(1, 63, 200), (130, 257, 513)], |m, n, k, parallelism| {
+                let (a, b, c) = (random(m * k), random(k * n), random(m * n));
+                let (alpha, beta) = (residue(p_wide - 2), residue(p_wide / 3));
+
+                for (read_dst, row_major_dst) in [(false, false), (true, false), (true, true)] {
+                    let (dst_cs, dst_rs) = if row_major_dst { (1, n) } else { (m, 1) };
+                    let mut dst = c.clone();
+                    unsafe {
+                        gemm_mod(
+                            m,
+                            n,
+                            k,
+                            dst.as_mut_ptr(),
+                            dst_cs as isize,
+                            dst_rs as isize,
+                            read_dst,
+                            a.as_ptr(),
+                            1,
+                            k as isize,
+                            b.as_ptr(),
+                            k as isize,
+                            1,
+                            alpha,
+                            beta,
+                            p,
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let mut expected = 0u128;
+                            for depth in 0..k {
+                                let prod =
+                                    a[i * k + depth].into() * b[depth + j * k].into() % p_wide;
+                                expected = (expected + prod) % p_wide;
+                            }
+                            expected = beta.into() * expected % p_wide;
+                            let idx = i * dst_rs + j * dst_cs;
+                            if read_dst {
+                                expected += alpha.into() * c[idx].into() % p_wide;
+                            }
+                            assert_eq!(dst[idx], residue(expected));
+                        }
+                    }
+                }
+            });
+        }
+
+        check(2u32);
+        check(65521u32);
+        check(4294967291u32);
+        check(2u64);
+        check((1u64 << 61) - 1);
+        check(18446744073709551557u64);
+    }
+
+    #[test]
+    fn test_gemm_int() {
+        fn check<T: IntScalar + core::fmt::Debug>(from_bits: fn(u64) -> T, bits: u32) {
+            // small values never overflow, moderate ones sometimes do, large ones almost always
+            let random = |len, magnitude: u32| -> Vec<T> {
+                (0..len)
+                    .map(|_| {
+                        let x = rand::random::<u64>();
+                        if magnitude >= bits {
+                            from_bits(x)
+                        } else {
+                            let half = 1u64 << (magnitude - 1);
+                            from_bits((x % (2 * half)).wrapping_sub(half))
+                        }
+                    })
+                    .collect()
+            };
+
+            for_each_shape_and(
+                &[(1, 63, 200), (130, 257, 513), (33, 1, 4096)],
+                |m, n, k, parallelism| {
+                    for magnitude in [4, bits / 2 - 4, bits] {
+                        let (a, b, c) = (
+                            random(m * k, magnitude),
+                            random(k * n, magnitude),
+                            random(m * n, magnitude),
+                        );
+                        let (alpha, beta) = (from_bits(3), from_bits(u64::MAX - 1));
+
+                        for (read_dst, row_major_dst) in
+                            [(false, false), (true, false), (true, true)]
+                        {
+                            let (dst_cs, dst_rs) = if row_major_dst { (1, n) } else { (m, 1) };
+
+                            let mut expected = c.clone();
+                            let mut expected_overflow = false;
+                            let mut wrapping = c.clone();
+                            for i in 0..m {
+                                for j in 0..n {
+                                    let (mut acc, mut acc_wrapping) = (T::ZERO, T::ZERO);
+                                    for depth in 0..k {
+                                        let (lhs, rhs) = (a[i * k + depth], b[depth + j * k]);
+                                        let (sum, overflow) = T::overflowing_mul_add(lhs, rhs, acc);
+                                        acc = sum;
+                                        expected_overflow |= overflow;
+                                        acc_wrapping = T::wrapping_mul_add(lhs, rhs, acc_wrapping);
+                                    }
+                                    let idx = i * dst_rs + j * dst_cs;
+                                    let (mut value, overflow) =
+                                        T::overflowing_mul_add(beta, acc, T::ZERO);
+                                    expected_overflow |= overflow;
+                                    let mut value_wrapping =
+                                        T::wrapping_mul_add(beta, acc_wrapping, T::ZERO);
+                                    if read_dst {
+                                        let overflow;
+                                        (value, overflow) =
+                                            T::overflowing_mul_add(alpha, c[idx], value);
+                                        expected_overflow |= overflow;
+                                        value_wrapping =
+                                            T::wrapping_mul_add(alpha, c[idx], value_wrapping);
+                                    }
+                                    expected[idx] = value;
+                                    wrapping[idx] = value_wrapping;
+                                }
+                            }
+
+                            let mut dst = c.clone();
+                            unsafe {
+                                gemm_int_wrapping(
+                                    m,
+                                    n,
+                                    k,
+                                    dst.as_mut_ptr(),
+                                    dst_cs as isize,
+                                    dst_rs as isize,
+                                    read_dst,
+                                    a.as_ptr(),
+                                    1,
+                                    k as isize,
+                                    b.as_ptr(),
+                                    k as isize,
+                                    1,
+                                    alpha,
+                                    beta,
+                                    parallelism,
+                                );
+                            }
+                            assert_eq!(dst, wrapping);
+
+                            let mut dst = c.clone();
+                            let result = unsafe {
+                                gemm_int_checked(
+                                    m,
+                                    n,
+                                    k,
+                                    dst.as_mut_ptr(),
+                                    dst_cs as isize,
+                                    dst_rs as isize,
+                                    read_dst,
+                                    a.as_ptr(),
+                                    1,
+                                    k as isize,
+                                    b.as_ptr(),
+                                    k as isize,
+                                    1,
+                                    alpha,
+                                    beta,
+                                    parallelism,
+                                )
+                            };
+                            if expected_overflow {
+                                assert_eq!(result, Err(GemmError::IntegerOverflow));
+                            } else {
+                                assert_eq!(result, Ok(()));
+                                assert_eq!(dst, expected);
+                            }
+                        }
+                    }
+                },
+            );
+        }
+
+        check(|x| x as i32, 32);
+        check(|x| x as i64, 64);
+
+        // the padding rows of a partial panel must not leak into the overflow flag, even when the
+        // scratch memory still holds the operands of a product that overflowed
+        let k = 4096;
+        for (m, value, overflow) in [
+            (36, i32::MAX, true),
+            (32, 1, false),
+            (33, 1, false),
+            (34, 1, false),
+            (35, 1, false),
+        ] {
+            let (a, b) = (vec![value; m * k], vec![1i32; k]);
+            let mut dst = vec![0i32; m];
+            let result = unsafe {
+                gemm_int_checked(
+                    m,
+                    1,
+                    k,
+                    dst.as_mut_ptr(),
+                    m as isize,
+                    1,
+                    false,
+                    a.as_ptr(),
+                    m as isize,
+                    1,
+                    b.as_ptr(),
+                    k as isize,
+                    1,
+                    0,
+                    1,
+                    Parallelism::None,
+                )
+            };
+            if overflow {
+                assert_eq!(result, Err(GemmError::IntegerOverflow));
+            } else {
+                assert_eq!(result, Ok(()));
+                assert_eq!(dst, vec![k as i32; m]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gemm_compensated() {
+        // integer inputs, so that the exact result can be computed in `i128`. half of the depth
+        // holds large products that cancel in pairs, which ruins a plainly accumulated result
+        fn check<T: CompensatedScalar + Into<f64>>(
+            from_int: fn(i128) -> T,
+            small_bits: u32,
+            large_shift: u32,
+            eps: f64,
+        ) {
+            let small = || -> i128 {
+                let half = 1i128 << (small_bits - 1);
+                (rand::random::<u64>() as i128 % (2 * half)) - half
+            };
+
+            for_each_shape_and(&[(1, 63, 200), (37, 41, 2049)], |m, n, k, parallelism| {
+                let mut a: Vec<i128> = (0..m * k).map(|_| small()).collect();
+                let mut b: Vec<i128> = (0..k * n).map(|_| small()).collect();
+                let c: Vec<i128> = (0..m * n).map(|_| small()).collect();
+                for depth in (0..k / 2).step_by(2) {
+                    let partner = k - 1 - depth;
+                    for i in 0..m {
+                        a[i * k + depth] <<= large_shift;
+                        a[i * k + partner] = a[i * k + depth];
+                    }
+                    for j in 0..n {
+                        b[depth + j * k] <<= large_shift;
+                        b[partner + j * k] = -b[depth + j * k];
+                    }
+                }
+                let (alpha, beta) = (5i128, -3i128);
+
+                let to_float = |v: &[i128]| -> Vec<T> { v.iter().map(|&x| from_int(x)).collect() };
+                let (a_float, b_float, c_float) = (to_float(&a), to_float(&b), to_float(&c));
+
+                for (read_dst, row_major_dst) in [(false, false), (true, false), (true, true)] {
+                    let (dst_cs, dst_rs) = if row_major_dst { (1, n) } else { (m, 1) };
+                    let mut dst = c_float.clone();
+                    unsafe {
+                        gemm_compensated(
+                            m,
+                            n,
+                            k,
+                            dst.as_mut_ptr(),
+                            dst_cs as isize,
+                            dst_rs as isize,
+                            read_dst,
+                            a_float.as_ptr(),
+                            1,
+                            k as isize,
+                            b_float.as_ptr(),
+                            k as isize,
+                            1,
+                            from_int(alpha),
+                            from_int(beta),
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let idx = i * dst_rs + j * dst_cs;
+                            let mut expected = 0i128;
+                            for depth in 0..k {
+                                expected += a[i * k + depth] * b[depth + j * k];
+                            }
+                            expected *= beta;
+                            if read_dst {
+                                expected += alpha * c[idx];
+                            }
+                            let expected = expected as f64;
+                            let got: f64 = dst[idx].into();
+                            assert!((got - expected).abs() <= 2.0 * eps * expected.abs());
+                        }
+                    }
+                }
+            });
+        }
+
+        check(|x| x as f32, 12, 4, f32::EPSILON as f64);
+        check(|x| x as f64, 30, 17, f64::EPSILON);
+    }
+
+    #[test]
+    fn test_gemm_interval() {
+        use num_rational::BigRational;
+
+        // values spanning many binades, some of them subnormal, and large products that cancel
+        let random = || -> f64 {
+            let mantissa = rand::random::<f64>() + 0.5;
+            let sign = if rand::random::<bool>() { 1.0 } else { -1.0 };
+            match rand::random::<u8>() % 8 {
+                0 => sign * mantissa * 1e-310,
+                1 => 0.0,
+                _ => sign * mantissa * 2.0f64.powi(rand::random::<i32>() % 30),
+            }
+        };
+        let exact = |x: f64| BigRational::from_float(x).unwrap();
+
+        for (m, n, k) in [(4, 4, 4), (7, 5, 13), (1, 9, 300), (5, 3, 0), (17, 11, 64)] {
+            for parallelism in [
+                Parallelism::None,
+                #[cfg(feature = "rayon")]
+                Parallelism::Rayon(0),
+            ] {
+                let mut a: Vec<f64> = (0..m * k).map(|_| random()).collect();
+                let mut b: Vec<f64> = (0..k * n).map(|_| random()).collect();
+                for depth in (0..k / 2).step_by(3) {
+                    let partner = k - 1 - depth;
+                    for i in 0..m {
+                        a[i * k + partner] = a[i * k + depth];
+                    }
+                    for j in 0..n {
+                        b[partner + j * k] = -b[depth + j * k];
+                    }
+                }
+
+                for row_major_dst in [false, true] {
+                    let (dst_cs, dst_rs) = if row_major_dst { (1, n) } else { (m, 1) };
+                    let mut lo = vec![f64::NAN; m * n];
+                    let mut hi = vec![f64::NAN; m * n];
+                    unsafe {
+                        gemm_interval(
+                            m,
+                            n,
+                            k,
+                            lo.as_mut_ptr(),
+                            hi.as_mut_ptr(),
+                            dst_cs as isize,
+                            dst_rs as isize,
+                            a.as_ptr(),
+                            1,
+                            k as isize,
+                            b.as_ptr(),
+                            k as isize,
+                            1,
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let mut expected = exact(0.0);
+                            for depth in 0..k {
+                                expected += exact(a[i * k + depth]) * exact(b[depth + j * k]);
+                            }
+                            let idx = i * dst_rs + j * dst_cs;
+                            assert!(lo[idx].is_finite() && hi[idx].is_finite());
+                            assert!(exact(lo[idx]) <= expected);
+                            assert!(expected <= exact(hi[idx]));
+                        }
+                    }
+                }
+            }
+        }
+
+        // the error bound overflows
+        let (a, b) = ([1e300, 1e300], [1e300, -1e300]);
+        let (mut lo, mut hi) = (0.0, 0.0);
+        unsafe {
+            gemm_interval(
+                1,
+                1,
+                2,
+                &mut lo,
+                &mut hi,
+                1,
+                1,
+                a.as_ptr(),
+                1,
+                2,
+                b.as_ptr(),
+                2,
+                1,
+                Parallelism::None,
+            );
+        }
+        assert_eq!((lo, hi), (f64::NEG_INFINITY, f64::INFINITY));
+    }
+
+    #[test]
+    fn test_gemm_binary() {
+        fn index(layout: BitLayout, stride: usize, row: usize, col: usize) -> (usize, usize) {
+            match layout {
+                BitLayout::RowMajor => (row * stride + col / 64, col % 64),
+                BitLayout::ColMajor => (col * stride + row / 64, row % 64),
+            }
+        }
+        fn get(bits: &[u64], layout: BitLayout, stride: usize, row: usize, col: usize) -> bool {
+            let (word, bit) = index(layout, stride, row, col);
+            (bits[word] >> bit) & 1 == 1
+        }
+        // random bits, including the padding, with one spare word per line
+        fn random(layout: BitLayout, n_rows: usize, n_cols: usize) -> (Vec<u64>, usize) {
+            let (outer, inner) = match layout {
+                BitLayout::RowMajor => (n_rows, n_cols),
+                BitLayout::ColMajor => (n_cols, n_rows),
+            };
+            let stride = inner.div_ceil(64) + 1;
+            let bits = (0..outer * stride).map(|_| rand::random()).collect();
+            (bits, stride)
+        }
+
+        let layouts = [BitLayout::RowMajor, BitLayout::ColMajor];
+        for_each_shape_and(
+            &[
+                (1, 1, 1),
+                (4, 4, 64),
+                (63, 5, 130),
+                (5, 65, 63),
+                (7, 3, 0),
+                (130, 200, 1000),
+                (300, 300, 700),
+            ],
+            |m, n, k, parallelism| {
+                for lhs_layout in layouts {
+                    for rhs_layout in layouts {
+                        let (a, a_stride) = random(lhs_layout, m, k);
+                        let (b, b_stride) = random(rhs_layout, k, n);
+                        let lhs = |i, depth| get(&a, lhs_layout, a_stride, i, depth);
+                        let rhs = |depth, j| get(&b, rhs_layout, b_stride, depth, j);
+
+                        let mut counts = vec![0i32; m * n];
+                        unsafe {
+                            gemm_xnor_popcount(
+                                m,
+                                n,
+                                k,
+                                counts.as_mut_ptr(),
+                                m as isize,
+                                1,
+                                a.as_ptr(),
+                                lhs_layout,
+                                a_stride,
+                                b.as_ptr(),
+                                rhs_layout,
+                                b_stride,
+                                parallelism,
+                            );
+                        }
+                        for i in 0..m {
+                            for j in 0..n {
+                                let expected = (0..k)
+                                    .filter(|&depth| lhs(i, depth) == rhs(depth, j))
+                                    .count();
+                                assert_eq!(counts[i + j * m], expected as i32);
+                            }
+                        }
+
+                        for dst_layout in layouts {
+                            let (c, c_stride) = random(dst_layout, m, n);
+                            let mut dst = c.clone();
+                            unsafe {
+                                gemm_or_and(
+                                    m,
+                                    n,
+                                    k,
+                                    dst.as_mut_ptr(),
+                                    dst_layout,
+                                    c_stride,
+                                    a.as_ptr(),
+                                    lhs_layout,
+                                    a_stride,
+                                    b.as_ptr(),
+                                    rhs_layout,
+                                    b_stride,
+                                    parallelism,
+                                );
+                            }
+
+                            // the bits outside of the matrix are preserved
+                            let mut expected = c;
+                            for i in 0..m {
+                                for j in 0..n {
+                                    let (word, bit) = index(dst_layout, c_stride, i, j);
+                                    expected[word] &= !(1 << bit);
+                                    if (0..k).any(|depth| lhs(i, depth) && rhs(depth, j)) {
+                                        expected[word] |= 1 << bit;
+                                    }
+                                }
+                            }
+                            assert_eq!(dst, expected);
+                        }
+                    }
+                }
+            },
+        );
+    }
+
+    #[test]
+    fn test_gemm_mixed() {
+        fn check<L, R, D>()
+        where
+            L: MixedScalar<R, D> + num_traits::NumCast,
+            R: GemmScalar + num_traits::NumCast,
+            D: GemmScalar + num_traits::NumCast,
+        {
+            let cast = |x: f64| -> D { num_traits::NumCast::from(x).unwrap() };
+            let widen = |x: &dyn num_traits::ToPrimitive| x.to_f64().unwrap();
+            // machine epsilon and smallest subnormal of the type of the given size
+            let eps = |size: usize| match size {
+                8 => (f64::EPSILON, 0.0),
+                4 => (f32::EPSILON as f64, 2.0f64.powi(-149)),
+                _ => (2.0f64.powi(-10), 2.0f64.powi(-24)),
+            };
+            let sizes = [
+                core::mem::size_of::<L>(),
+                core::mem::size_of::<R>(),
+                core::mem::size_of::<D>(),
+            ];
+            // the products are accumulated in f64 if any of the types is f64, and may be
+            // accumulated in f16 if all of them are f16
+            let acc_eps = if sizes.contains(&8) {
+                eps(8)
+            } else if sizes == [2, 2, 2] {
+                eps(2)
+            } else {
+                eps(4)
+            };
+            let dst_eps = eps(sizes[2]);
+
+            for_each_shape(|m, n, k, parallelism| {
+                let random = || rand::random::<f64>() - 0.5;
+                let a: Vec<L> = (0..m * k)
+                    .map(|_| num_traits::NumCast::from(random()).unwrap())
+                    .collect();
+                let b: Vec<R> = (0..k * n)
+                    .map(|_| num_traits::NumCast::from(random()).unwrap())
+                    .collect();
+                let c: Vec<D> = (0..m * n).map(|_| cast(random())).collect();
+
+                // a row-major destination is computed by the kernel with the operands swapped
+                for row_major in [false, true] {
+                    let (dst_cs, dst_rs, lhs_cs, lhs_rs, rhs_cs, rhs_rs) = if row_major {
+                        (1, n, 1, k, 1, n)
+                    } else {
+                        (m, 1, m, 1, k, 1)
+                    };
+                    let mut dst = c.clone();
+                    unsafe {
+                        gemm_mixed(
+                            m,
+                            n,
+                            k,
+                            dst.as_mut_ptr(),
+                            dst_cs as isize,
+                            dst_rs as isize,
+                            true,
+                            a.as_ptr(),
+                            lhs_cs as isize,
+                            lhs_rs as isize,
+                            b.as_ptr(),
+                            rhs_cs as isize,
+                            rhs_rs as isize,
+                            cast(0.5),
+                            cast(2.0),
+                            parallelism,
+                        );
+                    }
+
+                    for i in 0..m {
+                        for j in 0..n {
+                            let c = widen(&c[i * dst_rs + j * dst_cs]);
+                            let mut expected = 0.5 * c;
+                            let mut magnitude = 0.5 * c.abs();
+                            for depth in 0..k {
+                                let ab = widen(&a[i * lhs_rs + depth * lhs_cs])
+                                    * widen(&b[depth * rhs_rs + j * rhs_cs]);
+                                expected += 2.0 * ab;
+                                magnitude += 2.0 * ab.abs();
+                            }
+                            let actual = widen(&dst[i * dst_rs + j * dst_cs]);
+                            // the accumulator is rounded to `D` once, including when the depth
+                            // spans several chunks
+                            let tolerance =
+                                2.0 * (k + 2) as f64 * (acc_eps.0 * magnitude + acc_eps.1)
+                                    + 0.5 * (dst_eps.0 * expected.abs() + dst_eps.1);
+                            assert!((actual - expected).abs() <= tolerance);
+                        }
+                    }
+                }
+            });
+        }
+
+        check::<f32, f32, f32>();
+        check::<f32, f32, f64>();
+        check::<f32, f64, f32>();
+        check::<f32, f64, f64>();
+        check::<f64, f32, f32>();
+        check::<f64, f32, f64>();
+        check::<f64, f64, f32>();
+        check::<f64, f64, f64>();
+        #[cfg(feature = "f16")]
+        {
+            check::<f16, f16, f16>();
+            check::<f16, f16, f32>();
+            check::<f16, f16, f64>();
+            check::<f16, f32, f16>();
+            check::<f16, f32, f32>();
+            check::<f16, f32, f64>();
+            check::<f16, f64, f16>();
+            check::<f16, f64, f32>();
+            check::<f16, f64, f64>();
+            check::<f32, f16, f16>();
+            check::<f32, f16, f32>();
+            check::<f32, f16, f64>();
+            check::<f32, f32, f16>();
+            check::<f32, f64, f16>();
+            check::<f64, f16, f16>();
+            check::<f64, f16, f32>();
+            check::<f64, f16, f64>();
+            check::<f64, f32, f16>();
+            check::<f64, f64, f16>();
+        }
+    }
 }
@@ -0,0 +1,344 @@
+use crate::{gemm::GemmScalar, Parallelism};
+use core::marker::PhantomData;
+use core::mem::MaybeUninit;
+
+/// Returns the offsets of the lowest and highest elements of a non-empty `nrows×ncols` matrix
+/// with the given strides, relative to the element at `(0, 0)`, or `None` if they overflow
+/// `isize`.
+pub(crate) fn footprint(
+    nrows: usize,
+    ncols: usize,
+    row_stride: isize,
+    col_stride: isize,
+) -> Option<(isize, isize)> {
+    debug_assert!(nrows > 0 && ncols > 0);
+    let extent = |dim: usize, stride: isize| -> Option<isize> {
+        isize::try_from(dim - 1).ok()?.checked_mul(stride)
+    };
+
+    let row_extent = extent(nrows, row_stride)?;
+    let col_extent = extent(ncols, col_stride)?;
+    let lo = row_extent.min(0).checked_add(col_extent.min(0))?;
+    let hi = row_extent.max(0).checked_add(col_extent.max(0))?;
+    hi.checked_sub(lo)?;
+    Some((lo, hi))
+}
+
+/// Returns the offset of the element at `(0, 0)` inside a slice of length `len`, so that every
+/// element of a `nrows×ncols` matrix with the given strides lies inside the slice.
+///
+/// The matrix footprint is placed at the start of the slice, so negative strides move the first
+/// element towards the end.
+#[track_caller]
+fn footprint_base(
+    len: usize,
+    nrows: usize,
+    ncols: usize,
+    row_stride: isize,
+    col_stride: isize,
+) -> usize {
+    if nrows == 0 || ncols == 0 {
+        return 0;
+    }
+
+    let Some((lo, hi)) = footprint(nrows, ncols, row_stride, col_stride) else {
+        panic!("matrix footprint overflows isize");
+    };
+    let last = (hi - lo) as usize;
+    assert!(
+        last < len,
+        "matrix footprint ({} elements) exceeds slice length ({len})",
+        last + 1,
+    );
+    lo.unsigned_abs()
+}
+
+/// Returns `true` if two distinct indices of a `nrows×ncols` matrix with the given strides can
+/// map to the same element.
+///
+/// The check is conservative: the strides are accepted only if the matrix, seen along its
+/// smallest stride first, never wraps back onto itself.
+pub(crate) fn may_alias(nrows: usize, ncols: usize, row_stride: isize, col_stride: isize) -> bool {
+    if nrows == 0 || ncols == 0 {
+        return false;
+    }
+
+    // dimensions of size one never step by their stride
+    let mut dims = [
+        (nrows, row_stride.unsigned_abs()),
+        (ncols, col_stride.unsigned_abs()),
+    ];
+    dims.sort_unstable_by_key(|&(_, stride)| stride);
+    let [(inner_dim, inner_stride), (outer_dim, outer_stride)] = dims;
+
+    match (inner_dim > 1, outer_dim > 1) {
+        (false, false) => false,
+        (true, false) => inner_stride == 0,
+        (false, true) => outer_stride == 0,
+        (true, true) => {
+            inner_stride == 0
+                || match inner_dim.checked_mul(inner_stride) {
+                    Some(extent) => outer_stride < extent,
+                    None => true,
+                }
+        }
+    }
+}
+
+/// Immutable view over a strided matrix stored in a slice.
+#[derive(Copy, Clone, Debug)]
+pub struct MatRef<'a, T> {
+    ptr: *const T,
+    nrows: usize,
+    ncols: usize,
+    row_stride: isize,
+    col_stride: isize,
+    __marker: PhantomData<&'a [T]>,
+}
+
+unsafe impl<T: Sync> Send for MatRef<'_, T> {}
+unsafe impl<T: Sync> Sync for MatRef<'_, T> {}
+
+/// Mutable view over a strided matrix stored in a slice, possibly uninitialized.
+#[derive(Debug)]
+pub struct MatMut<'a, T> {
+    ptr: *mut T,
+    nrows: usize,
+    ncols: usize,
+    row_stride: isize,
+    col_stride: isize,
+    init: bool,
+    __marker: PhantomData<&'a mut [MaybeUninit<T>]>,
+}
+
+unsafe impl<T: Send> Send for MatMut<'_, T> {}
+unsafe impl<T: Sync> Sync for MatMut<'_, T> {}
+
+impl<'a, T> MatRef<'a, T> {
+    /// Creates a view over `slice`. Strides are in elements and may be negative, in which case
+    /// the element at `(0, 0)` is not the first element of the slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the footprint of the matrix does not fit in `slice`.
+    #[track_caller]
+    pub fn from_slice(
+        slice: &'a [T],
+        nrows: usize,
+        ncols: usize,
+        row_stride: isize,
+        col_stride: isize,
+    ) -> Self {
+        let base = footprint_base(slice.len(), nrows, ncols, row_stride, col_stride);
+        Self {
+            ptr: slice.as_ptr().wrapping_add(base),
+            nrows,
+            ncols,
+            row_stride,
+            col_stride,
+            __marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+    #[inline]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+    #[inline]
+    pub fn row_stride(&self) -> isize {
+        self.row_stride
+    }
+    #[inline]
+    pub fn col_stride(&self) -> isize {
+        self.col_stride
+    }
+    /// Returns a pointer to the element at `(0, 0)`.
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+
+    #[inline]
+    #[track_caller]
+    pub fn get(&self, row: usize, col: usize) -> &'a T {
+        assert!(row < self.nrows && col < self.ncols);
+        unsafe {
+            &*self
+                .ptr
+                .offset(row as isize * self.row_stride + col as isize * self.col_stride)
+        }
+    }
+
+    #[inline]
+    pub fn transpose(self) -> Self {
+        Self {
+            ptr: self.ptr,
+            nrows: self.ncols,
+            ncols: self.nrows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+            __marker: PhantomData,
+        }
+    }
+}
+
+impl<'a, T> MatMut<'a, T> {
+    /// Creates a view over `slice`. Strides are in elements and may be negative, in which case
+    /// the element at `(0, 0)` is not the first element of the slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the footprint of the matrix does not fit in `slice`, or if the strides make two
+    /// elements of the matrix overlap, e.g. a zero stride along a dimension larger than one.
+    #[track_caller]
+    pub fn from_slice(
+        slice: &'a mut [T],
+        nrows: usize,
+        ncols: usize,
+        row_stride: isize,
+        col_stride: isize,
+    ) -> Self {
+        assert!(
+            !may_alias(nrows, ncols, row_stride, col_stride),
+            "destination strides make matrix elements overlap",
+        );
+        let base = footprint_base(slice.len(), nrows, ncols, row_stride, col_stride);
+        Self {
+            ptr: slice.as_mut_ptr().wrapping_add(base),
+            nrows,
+            ncols,
+            row_stride,
+            col_stride,
+            init: true,
+            __marker: PhantomData,
+        }
+    }
+
+    /// Same as [`MatMut::from_slice`], for a destination that is only written to.
+    #[track_caller]
+    pub fn from_uninit_slice(
+        slice: &'a mut [MaybeUninit<T>],
+        nrows: usize,
+        ncols: usize,
+        row_stride: isize,
+        col_stride: isize,
+    ) -> Self {
+        assert!(
+            !may_alias(nrows, ncols, row_stride, col_stride),
+            "destination strides make matrix elements overlap",
+        );
+        let base = footprint_base(slice.len(), nrows, ncols, row_stride, col_stride);
+        Self {
+            ptr: (slice.as_mut_ptr() as *mut T).wrapping_add(base),
+            nrows,
+            ncols,
+            row_stride,
+            col_stride,
+            init: false,
+            __marker: PhantomData,
+        }
+    }
+
+    #[inline]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+    #[inline]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+    #[inline]
+    pub fn row_stride(&self) -> isize {
+        self.row_stride
+    }
+    #[inline]
+    pub fn col_stride(&self) -> isize {
+        self.col_stride
+    }
+    /// Returns `false` if the view was created from uninitialized memory.
+    #[inline]
+    pub fn is_init(&self) -> bool {
+        self.init
+    }
+    /// Returns a pointer to the element at `(0, 0)`.
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    #[inline]
+    pub fn transpose(self) -> Self {
+        Self {
+            ptr: self.ptr,
+            nrows: self.ncols,
+            ncols: self.nrows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+            init: self.init,
+            __marker: PhantomData,
+        }
+    }
+}
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// If `alpha` is `None`, `dst` is overwritten without being read.
+///
+/// # Panics
+///
+/// Panics if the dimensions of `dst`, `lhs` and `rhs` do not match, or if `alpha` is `Some` and
+/// `dst` was created from uninitialized memory.
+#[track_caller]
+pub fn matmul<T: GemmScalar>(
+    mut dst: MatMut<'_, T>,
+    lhs: MatRef<'_, T>,
+    rhs: MatRef<'_, T>,
+    alpha: Option<T>,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) {
+    assert!(lhs.ncols() == rhs.nrows());
+    assert!(dst.nrows() == lhs.nrows());
+    assert!(dst.ncols() == rhs.ncols());
+    assert!(
+        alpha.is_none() || dst.is_init(),
+        "cannot read from an uninitialized destination",
+    );
+
+    // alpha is ignored when dst is not read
+    let (read_dst, alpha) = match alpha {
+        Some(alpha) => (true, alpha),
+        None => (false, beta),
+    };
+
+    unsafe {
+        crate::gemm::gemm(
+            dst.nrows(),
+            dst.ncols(),
+            lhs.ncols(),
+            dst.as_mut_ptr(),
+            dst.col_stride(),
+            dst.row_stride(),
+            read_dst,
+            lhs.as_ptr(),
+            lhs.col_stride(),
+            lhs.row_stride(),
+            rhs.as_ptr(),
+            rhs.col_stride(),
+            rhs.row_stride(),
+            alpha,
+            beta,
+            conj_dst,
+            conj_lhs,
+            conj_rhs,
+            parallelism,
+        )
+    }
+}
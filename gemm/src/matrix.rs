@@ -0,0 +1,291 @@
+use crate::{blas::Layout, matmul, GemmScalar, MatMut, MatRef, Parallelism};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use gemm_common::{cache::DivCeil, gemm::CACHELINE_ALIGN};
+
+extern crate alloc;
+
+// `Parallelism::None` is stored as 0, and `Parallelism::Rayon(n)` as `n + 1`
+#[cfg(feature = "rayon")]
+static DEFAULT_PARALLELISM: AtomicUsize = AtomicUsize::new(1);
+#[cfg(not(feature = "rayon"))]
+static DEFAULT_PARALLELISM: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the parallelism used by the [`Matrix`] operations. Defaults to `Parallelism::Rayon(0)`
+/// if the `rayon` feature is enabled, and `Parallelism::None` otherwise.
+#[inline]
+pub fn get_default_parallelism() -> Parallelism {
+    match DEFAULT_PARALLELISM.load(Ordering::Relaxed) {
+        0 => Parallelism::None,
+        #[cfg(feature = "rayon")]
+        n => Parallelism::Rayon(n - 1),
+        #[cfg(not(feature = "rayon"))]
+        _ => Parallelism::None,
+    }
+}
+#[inline]
+pub fn set_default_parallelism(parallelism: Parallelism) {
+    let value = match parallelism {
+        Parallelism::None => 0,
+        #[cfg(feature = "rayon")]
+        Parallelism::Rayon(n) => n.saturating_add(1),
+    };
+    DEFAULT_PARALLELISM.store(value, Ordering::Relaxed);
+}
+
+/// Owned matrix, stored in row-major or column-major order.
+///
+/// The leading dimension is padded so that every row (row-major) or column (column-major) starts
+/// on a cache line boundary.
+pub struct Matrix<T> {
+    ptr: *mut T,
+    nrows: usize,
+    ncols: usize,
+    layout: Layout,
+    leading_dim: usize,
+}
+
+unsafe impl<T: Send> Send for Matrix<T> {}
+unsafe impl<T: Sync> Sync for Matrix<T> {}
+
+impl<T> Matrix<T> {
+    fn alloc_layout(len: usize) -> core::alloc::Layout {
+        core::alloc::Layout::array::<T>(len)
+            .and_then(|layout| layout.align_to(CACHELINE_ALIGN))
+            .expect("matrix size overflows isize")
+    }
+
+    fn len(&self) -> usize {
+        match self.layout {
+            Layout::RowMajor => self.nrows * self.leading_dim,
+            Layout::ColMajor => self.ncols * self.leading_dim,
+        }
+    }
+}
+
+impl<T: GemmScalar> Matrix<T> {
+    /// Creates an `nrows×ncols` matrix filled with zeros.
+    pub fn zeros(nrows: usize, ncols: usize, layout: Layout) -> Self {
+        let inner = match layout {
+            Layout::RowMajor => ncols,
+            Layout::ColMajor => nrows,
+        };
+        let outer = match layout {
+            Layout::RowMajor => nrows,
+            Layout::ColMajor => ncols,
+        };
+        let align = Ord::max(CACHELINE_ALIGN / core::mem::size_of::<T>(), 1);
+        let leading_dim = inner
+            .msrv_checked_next_multiple_of(align)
+            .expect("matrix size overflows usize");
+        let len = leading_dim
+            .checked_mul(outer)
+            .expect("matrix size overflows usize");
+
+        let ptr = if len == 0 {
+            core::ptr::NonNull::<T>::dangling().as_ptr()
+        } else {
+            let alloc_layout = Self::alloc_layout(len);
+            // the all-zero bit pattern is zero for every `GemmScalar`
+            let ptr = unsafe { alloc::alloc::alloc_zeroed(alloc_layout) } as *mut T;
+            if ptr.is_null() {
+                alloc::alloc::handle_alloc_error(alloc_layout);
+            }
+            ptr
+        };
+
+        Self {
+            ptr,
+            nrows,
+            ncols,
+            layout,
+            leading_dim,
+        }
+    }
+
+    /// Creates an `nrows×ncols` matrix whose element at `(i, j)` is `f(i, j)`.
+    pub fn from_fn(
+        nrows: usize,
+        ncols: usize,
+        layout: Layout,
+        mut f: impl FnMut(usize, usize) -> T,
+    ) -> Self {
+        let mut this = Self::zeros(nrows, ncols, layout);
+        for j in 0..ncols {
+            for i in 0..nrows {
+                this[(i, j)] = f(i, j);
+            }
+        }
+        this
+    }
+
+    #[inline]
+    pub fn nrows(&self) -> usize {
+        self.nrows
+    }
+    #[inline]
+    pub fn ncols(&self) -> usize {
+        self.ncols
+    }
+    #[inline]
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+    #[inline]
+    pub fn row_stride(&self) -> isize {
+        match self.layout {
+            Layout::RowMajor => self.leading_dim as isize,
+            Layout::ColMajor => 1,
+        }
+    }
+    #[inline]
+    pub fn col_stride(&self) -> isize {
+        match self.layout {
+            Layout::RowMajor => 1,
+            Layout::ColMajor => self.leading_dim as isize,
+        }
+    }
+    #[inline]
+    pub fn as_ptr(&self) -> *const T {
+        self.ptr
+    }
+    #[inline]
+    pub fn as_mut_ptr(&mut self) -> *mut T {
+        self.ptr
+    }
+
+    /// Returns the underlying storage, including the padding.
+    #[inline]
+    pub fn as_slice(&self) -> &[T] {
+        unsafe { core::slice::from_raw_parts(self.ptr, self.len()) }
+    }
+    /// Returns the underlying storage, including the padding.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [T] {
+        unsafe { core::slice::from_raw_parts_mut(self.ptr, self.len()) }
+    }
+
+    #[inline]
+    pub fn as_mat_ref(&self) -> MatRef<'_, T> {
+        let (nrows, ncols, rs, cs) = (self.nrows, self.ncols, self.row_stride(), self.col_stride());
+        MatRef::from_slice(self.as_slice(), nrows, ncols, rs, cs)
+    }
+    #[inline]
+    pub fn as_mat_mut(&mut self) -> MatMut<'_, T> {
+        let (nrows, ncols, rs, cs) = (self.nrows, self.ncols, self.row_stride(), self.col_stride());
+        MatMut::from_slice(self.as_mut_slice(), nrows, ncols, rs, cs)
+    }
+
+    /// dst := self×rhs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions do not match.
+    #[track_caller]
+    pub fn mul_into(&self, rhs: &Matrix<T>, dst: &mut Matrix<T>) {
+        matmul(
+            dst.as_mat_mut(),
+            self.as_mat_ref(),
+            rhs.as_mat_ref(),
+            None,
+            T::one(),
+            false,
+            false,
+            false,
+            get_default_parallelism(),
+        );
+    }
+
+    /// self := self + lhs×rhs
+    ///
+    /// # Panics
+    ///
+    /// Panics if the dimensions do not match.
+    #[track_caller]
+    pub fn mul_add_assign(&mut self, lhs: &Matrix<T>, rhs: &Matrix<T>) {
+        matmul(
+            self.as_mat_mut(),
+            lhs.as_mat_ref(),
+            rhs.as_mat_ref(),
+            Some(T::one()),
+            T::one(),
+            false,
+            false,
+            false,
+            get_default_parallelism(),
+        );
+    }
+}
+
+impl<T> Drop for Matrix<T> {
+    fn drop(&mut self) {
+        let len = self.len();
+        if len != 0 {
+            unsafe { alloc::alloc::dealloc(self.ptr as *mut u8, Self::alloc_layout(len)) };
+        }
+    }
+}
+
+impl<T: GemmScalar> Clone for Matrix<T> {
+    fn clone(&self) -> Self {
+        let mut this = Self::zeros(self.nrows, self.ncols, self.layout);
+        this.as_mut_slice().copy_from_slice(self.as_slice());
+        this
+    }
+}
+
+impl<T: GemmScalar> core::fmt::Debug for Matrix<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        struct Row<'a, T>(&'a Matrix<T>, usize);
+        impl<T: GemmScalar> core::fmt::Debug for Row<'_, T> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                f.debug_list()
+                    .entries((0..self.0.ncols).map(|j| &self.0[(self.1, j)]))
+                    .finish()
+            }
+        }
+
+        f.debug_list()
+            .entries((0..self.nrows).map(|i| Row(self, i)))
+            .finish()
+    }
+}
+
+impl<T: GemmScalar> core::ops::Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    #[inline]
+    #[track_caller]
+    fn index(&self, (i, j): (usize, usize)) -> &T {
+        assert!(i < self.nrows && j < self.ncols);
+        unsafe {
+            &*self
+                .ptr
+                .offset(i as isize * self.row_stride() + j as isize * self.col_stride())
+        }
+    }
+}
+
+impl<T: GemmScalar> core::ops::IndexMut<(usize, usize)> for Matrix<T> {
+    #[inline]
+    #[track_caller]
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut T {
+        assert!(i < self.nrows && j < self.ncols);
+        unsafe {
+            &mut *self
+                .ptr
+                .offset(i as isize * self.row_stride() + j as isize * self.col_stride())
+        }
+    }
+}
+
+impl<T: GemmScalar> core::ops::Mul<&Matrix<T>> for &Matrix<T> {
+    type Output = Matrix<T>;
+
+    /// Returns `self×rhs`, stored with the layout of `self`.
+    #[track_caller]
+    fn mul(self, rhs: &Matrix<T>) -> Matrix<T> {
+        let mut dst = Matrix::zeros(self.nrows, rhs.ncols, self.layout);
+        self.mul_into(rhs, &mut dst);
+        dst
+    }
+}
@@ -0,0 +1,73 @@
+use crate::{GemmScalar, Parallelism};
+use nalgebra::{Dim, Matrix, RawStorage, RawStorageMut};
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// Accepts any `nalgebra` matrix or view, with fixed or dynamic dimensions. If `alpha` is `None`,
+/// `dst` is overwritten without being read.
+///
+/// # Panics
+///
+/// Panics if the dimensions of `dst`, `lhs` and `rhs` do not match.
+#[track_caller]
+pub fn gemm_nalgebra<T, R1, C1, S1, R2, C2, S2, R3, C3, S3>(
+    dst: &mut Matrix<T, R1, C1, S1>,
+    lhs: &Matrix<T, R2, C2, S2>,
+    rhs: &Matrix<T, R3, C3, S3>,
+    alpha: Option<T>,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) where
+    T: GemmScalar,
+    R1: Dim,
+    C1: Dim,
+    S1: RawStorageMut<T, R1, C1>,
+    R2: Dim,
+    C2: Dim,
+    S2: RawStorage<T, R2, C2>,
+    R3: Dim,
+    C3: Dim,
+    S3: RawStorage<T, R3, C3>,
+{
+    let (m, n) = dst.shape();
+    let k = lhs.ncols();
+    assert!(lhs.nrows() == m);
+    assert!(rhs.nrows() == k);
+    assert!(rhs.ncols() == n);
+
+    let (read_dst, alpha) = match alpha {
+        Some(alpha) => (true, alpha),
+        None => (false, T::zero()),
+    };
+
+    let (dst_rs, dst_cs) = dst.strides();
+    let (lhs_rs, lhs_cs) = lhs.strides();
+    let (rhs_rs, rhs_cs) = rhs.strides();
+
+    unsafe {
+        crate::gemm::gemm(
+            m,
+            n,
+            k,
+            dst.as_mut_ptr(),
+            dst_cs as isize,
+            dst_rs as isize,
+            read_dst,
+            lhs.as_ptr(),
+            lhs_cs as isize,
+            lhs_rs as isize,
+            rhs.as_ptr(),
+            rhs_cs as isize,
+            rhs_rs as isize,
+            alpha,
+            beta,
+            conj_dst,
+            conj_lhs,
+            conj_rhs,
+            parallelism,
+        )
+    }
+}
@@ -0,0 +1,102 @@
+use crate::{GemmScalar, Parallelism};
+use ndarray::{ArrayView2, ArrayView3, ArrayViewMut2, ArrayViewMut3, Axis};
+
+/// dst := alpha×dst + beta×lhs×rhs
+///
+/// The strides of the views are forwarded as is, and may be negative. If `alpha` is `None`, `dst`
+/// is overwritten without being read.
+///
+/// # Panics
+///
+/// Panics if the dimensions of `dst`, `lhs` and `rhs` do not match.
+#[track_caller]
+pub fn gemm_ndarray<T: GemmScalar>(
+    mut dst: ArrayViewMut2<'_, T>,
+    lhs: ArrayView2<'_, T>,
+    rhs: ArrayView2<'_, T>,
+    alpha: Option<T>,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) {
+    let (m, n) = dst.dim();
+    let k = lhs.ncols();
+    assert!(lhs.nrows() == m);
+    assert!(rhs.nrows() == k);
+    assert!(rhs.ncols() == n);
+
+    let (read_dst, alpha) = match alpha {
+        Some(alpha) => (true, alpha),
+        None => (false, T::zero()),
+    };
+
+    let (dst_rs, dst_cs) = (dst.strides()[0], dst.strides()[1]);
+    let (lhs_rs, lhs_cs) = (lhs.strides()[0], lhs.strides()[1]);
+    let (rhs_rs, rhs_cs) = (rhs.strides()[0], rhs.strides()[1]);
+
+    unsafe {
+        crate::gemm::gemm(
+            m,
+            n,
+            k,
+            dst.as_mut_ptr(),
+            dst_cs,
+            dst_rs,
+            read_dst,
+            lhs.as_ptr(),
+            lhs_cs,
+            lhs_rs,
+            rhs.as_ptr(),
+            rhs_cs,
+            rhs_rs,
+            alpha,
+            beta,
+            conj_dst,
+            conj_lhs,
+            conj_rhs,
+            parallelism,
+        )
+    }
+}
+
+/// Same as [`gemm_ndarray`], for each matrix along axis 0.
+///
+/// # Panics
+///
+/// Panics if the batch sizes or the dimensions of `dst`, `lhs` and `rhs` do not match.
+#[track_caller]
+pub fn gemm_ndarray_batched<T: GemmScalar>(
+    mut dst: ArrayViewMut3<'_, T>,
+    lhs: ArrayView3<'_, T>,
+    rhs: ArrayView3<'_, T>,
+    alpha: Option<T>,
+    beta: T,
+    conj_dst: bool,
+    conj_lhs: bool,
+    conj_rhs: bool,
+    parallelism: Parallelism,
+) {
+    let batch = dst.len_of(Axis(0));
+    assert!(lhs.len_of(Axis(0)) == batch);
+    assert!(rhs.len_of(Axis(0)) == batch);
+
+    for ((dst, lhs), rhs) in dst
+        .axis_iter_mut(Axis(0))
+        .zip(lhs.axis_iter(Axis(0)))
+        .zip(rhs.axis_iter(Axis(0)))
+    {
+        gemm_ndarray(
+            dst,
+            lhs,
+            rhs,
+            alpha,
+            beta,
+            conj_dst,
+            conj_lhs,
+            conj_rhs,
+            parallelism,
+        );
+    }
+}